@@ -1,14 +1,32 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 use anyhow::Result;
 
-use crate::component::Component;
+use crate::component::{Component, ComponentDetail};
+use crate::config::SourceLayer;
+use crate::conflict::{Choices, Conflict, Resolution};
+use crate::deps::DependencyGraph;
+use crate::env_var::EnvVar;
+use crate::doctor::Finding;
+use crate::history::{Batch, ItemKind, Journal};
 use crate::mcp::{McpServer, McpScope};
+use crate::permission::{PermissionRule, RuleKind, SettingsScope};
 use crate::plugin::Plugin;
+use crate::profile::{Profile, ProfileEntry};
+use crate::remote::RemoteStatus;
+use crate::snapshot::Snapshot;
 use crate::fs;
-use crate::component::ComponentType;
-use crate::tree::TreeView;
+use crate::component::{ComponentType, InstallStatus};
+use crate::tree::{TreeState, TreeView};
+use crate::keymap::Keymap;
 use crate::theme::Theme;
+use crate::ui::HitMap;
+
+/// Longest gap between the clicks of a double-click
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum TargetCli {
@@ -51,6 +69,8 @@ pub enum Tab {
     Config,
     McpServers,
     Plugins,
+    Permissions,
+    Env,
 }
 
 impl Tab {
@@ -67,13 +87,15 @@ impl Tab {
             Tab::Config,
             Tab::McpServers,
             Tab::Plugins,
+            Tab::Permissions,
+            Tab::Env,
         ]
     }
 
     pub fn for_cli(cli: TargetCli) -> Vec<Tab> {
         match cli {
             TargetCli::Claude => Self::all().to_vec(),
-            TargetCli::Codex => vec![Tab::Skills, Tab::McpServers],
+            TargetCli::Codex => vec![Tab::Skills, Tab::Config, Tab::McpServers],
         }
     }
 
@@ -90,6 +112,8 @@ impl Tab {
             Tab::Config => "Config",
             Tab::McpServers => "MCP",
             Tab::Plugins => "Plugins",
+            Tab::Permissions => "Permissions",
+            Tab::Env => "Env",
         }
     }
 
@@ -106,12 +130,14 @@ impl Tab {
             Tab::Config => Some(ComponentType::ConfigFile),
             Tab::McpServers => None,
             Tab::Plugins => None,
+            Tab::Permissions => None,
+            Tab::Env => None,
         }
     }
 
 }
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum View {
     CliSelection,
     Loading,
@@ -120,6 +146,46 @@ pub enum View {
     EnvInput,
     ProjectPath,
     Installing,
+    History,
+    Snapshots,
+    Profiles,
+    Doctor,
+    Purge,
+    MergeReview,
+}
+
+/// State of one queued install/remove item
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl ItemState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, Self::Done | Self::Failed | Self::Cancelled)
+    }
+}
+
+/// A settings conflict in the merge review (see conflict.rs)
+#[derive(Clone, Debug)]
+pub struct ReviewItem {
+    pub file: String, // Config file, relative to dest_dir
+    pub conflict: Conflict,
+    pub resolution: Resolution,
+    pub remembered: bool, // Choice was made in an earlier review
+}
+
+#[derive(Clone, Debug)]
+pub struct ProcessItem {
+    pub idx: usize, // Index into components / mcp_servers / active plugins
+    pub name: String,
+    pub state: ItemState,
+    pub duration: Option<Duration>,
+    pub cancel: fs::CancelHandle, // Kills the item's CLI process while it runs
 }
 
 pub struct App {
@@ -129,27 +195,49 @@ pub struct App {
     pub current_view: View,
     pub should_quit: bool,
     pub theme: Theme,
+    pub keymap: Keymap,
+    pub show_help: bool, // Key overlay for the current view
+
+    // Mouse: areas of the last frame and the previous click (for double-clicks)
+    pub hit_map: RefCell<HitMap>,
+    pub last_click: Option<(Instant, u16, u16)>,
 
     pub components: Vec<Component>,
     pub list_index: usize, // Index within current tab's filtered list (legacy, for MCP/Plugins)
     pub tree_views: HashMap<Tab, TreeView>, // Tree views for component tabs
+    pub dep_graph: DependencyGraph,         // `requires:` edges between components
+    detail_cache: RefCell<Option<(usize, u64, ComponentDetail)>>, // Detail pane of the last rendered file
+    remote_fetch: Option<mpsc::Receiver<(usize, RemoteStatus)>>, // Background fetch of git layers
 
     pub mcp_servers: Vec<McpServer>,
     pub mcp_index: usize,
     pub mcp_scope: McpScope,
     pub mcp_project_path: String, // Project path for local scope
+    pub mcp_loaded: bool,         // Install status known (asked the CLI when the tab is first shown)
 
     pub plugins: Vec<Plugin>,
     pub plugin_index: usize,
+    pub plugins_loaded: bool, // Install status known (read when the tab is first shown)
+
+    // Marketplace browse mode (all plugins offered by cloned marketplaces)
+    pub plugin_browse: bool,
+    pub browse_plugins: Vec<Plugin>,
+    pub browse_index: usize,
 
     pub diff_content: Option<String>,
     pub diff_scroll: u16,
 
-    pub source_dir: PathBuf,
+    pub source_dir: PathBuf,          // Base layer (profiles and plugins.yaml edits go here)
+    pub layers: Vec<SourceLayer>,     // Base first, then overlays (see config.rs)
+    pub source_layers: Vec<PathBuf>,  // Root paths of `layers`, for the scanner
     pub dest_dir: PathBuf,
+    pub scan_cache: Option<PathBuf>, // Content hashes kept between scans (see fs/cache.rs)
 
     pub status_message: Option<String>,
 
+    // Removal queue awaiting confirmation (other installed components depend on it)
+    pub pending_removal: Option<Vec<usize>>,
+
     // Current defaults from settings.json
     pub current_output_style: Option<String>,
     pub current_statusline: Option<String>,
@@ -158,12 +246,55 @@ pub struct App {
     pub processing_progress: Option<usize>,
     pub processing_total: Option<usize>,
     pub processing_log: Vec<String>,
-    pub processing_queue: Vec<usize>, // Indices of items to process
+    pub processing_queue: Vec<usize>, // Indices of items to process (moved into processing_items on start)
+    pub processing_items: Vec<ProcessItem>,
+    pub processing_cursor: usize,     // Highlighted item in the Installing view
     pub is_removing: bool,            // true = removing, false = installing
     pub animation_frame: usize,       // For spinner animation
     pub needs_refresh: bool,          // True after processing, before refresh
     pub refreshing: bool,             // True while refresh thread is running
     pub processing_complete: bool,    // True when everything is done (including refresh)
+    pub journal: Option<Journal>,     // On-disk record of the running batch
+
+    // History view (past batches, newest first)
+    pub history: Vec<Batch>,
+    pub history_index: usize,
+
+    // Snapshots view (newest first)
+    pub snapshots: Vec<Snapshot>,
+    pub snapshot_index: usize,
+    pub snapshot_log: Vec<String>,        // Output of the last restore
+    pub pending_restore: Option<String>,  // Snapshot id awaiting confirmation
+
+    // Profiles view (source_dir/profiles/*.yaml)
+    pub profiles: Vec<ProfileEntry>,
+    pub profile_index: usize,
+    pub profile_name_input: Option<String>, // Some while typing a name to save the selection
+
+    // Doctor view (problems in the live settings.json)
+    pub doctor_findings: Vec<Finding>,
+    pub doctor_index: usize,
+    pub doctor_log: Vec<String>, // Results of the fixes applied this visit
+
+    // Purge view (confirmation list); a confirmed purge runs in the Installing view
+    pub purge_plan: Option<crate::purge::Plan>,
+    pub purge_index: usize,
+    pub purge_batch: Option<std::sync::Arc<crate::purge::Batch>>, // Steps of the running batch
+
+    // Permissions tab (rules across user/project/local settings, see permission.rs)
+    pub permissions: Vec<PermissionRule>,
+    pub permission_index: usize,
+    pub permission_scope: SettingsScope, // Settings file that [i]/[r] write to
+    pub default_mode: Option<(String, SettingsScope)>,
+
+    // Env tab (settings.json `env` block, see env_var.rs)
+    pub env_vars: Vec<EnvVar>,
+    pub env_index: usize,
+    pub env_edit: Option<String>, // Some while editing the highlighted value
+
+    // Merge review (config keys where the local value differs from the repo's)
+    pub review_items: Vec<ReviewItem>,
+    pub review_index: usize,
 
     // Env input state (for MCP servers requiring env vars)
     pub env_input_server_idx: Option<usize>,   // Index of MCP server being configured
@@ -178,12 +309,81 @@ pub struct App {
 
 impl App {
     pub fn new() -> Result<Self> {
-        let source_dir = find_source_dir()?;
+        // Git layers are fetched in the background (see start_remote_fetch)
+        let layers = crate::config::source_layers(&crate::config::load()?, find_source_dir, false)?;
         // Start with temporary dest_dir, will be set after CLI selection
         let dest_dir = dirs::home_dir()
             .ok_or_else(|| anyhow::anyhow!("Cannot find home directory"))?
             .join(".claude");
 
+        // Saved theme and keymap (user files that fail to parse are reported, not fatal)
+        let saved_theme = crate::config::load().ok().and_then(|c| c.theme);
+        let (theme, mut config_errors) = Theme::load(saved_theme.as_deref());
+        let (keymap, keymap_errors) = Keymap::load();
+        config_errors.extend(keymap_errors);
+
+        let mut app = Self::with_layers(layers, dest_dir, theme, keymap);
+        app.status_message = (!config_errors.is_empty()).then(|| config_errors.join("; "));
+        app.start_remote_fetch();
+        Ok(app)
+    }
+
+    /// Fetch git layers without blocking startup; results arrive through poll_remote_fetch
+    fn start_remote_fetch(&mut self) {
+        let remotes: Vec<(usize, RemoteStatus)> = self
+            .layers
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, l)| l.remote.clone().filter(|r| !r.fetched).map(|r| (idx, r)))
+            .collect();
+        if remotes.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (idx, mut status) in remotes {
+                match crate::remote::cache_root().and_then(|root| crate::remote::sync(&status.source, &root, true)) {
+                    Ok((_, fetched)) => status = fetched,
+                    Err(e) => status.error = Some(format!("{:#}", e)),
+                }
+                if tx.send((idx, status)).is_err() {
+                    return;
+                }
+            }
+        });
+        self.remote_fetch = Some(rx);
+    }
+
+    /// Apply finished background fetches; the upstream notice shows once all are done
+    pub fn poll_remote_fetch(&mut self) {
+        let Some(rx) = &self.remote_fetch else {
+            return;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok((idx, status)) => {
+                    if let Some(layer) = self.layers.get_mut(idx) {
+                        layer.remote = Some(status);
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => break,
+            }
+        }
+        self.remote_fetch = None;
+        if self.current_view == View::List
+            && let Some(notice) = self.remote_notice()
+        {
+            self.status_message = Some(notice);
+        }
+    }
+
+    /// App on the CLI selection screen for the given layers (no user config is read)
+    pub fn with_layers(layers: Vec<SourceLayer>, dest_dir: PathBuf, theme: Theme, keymap: Keymap) -> Self {
+        let source_layers = crate::config::layer_paths(&layers);
+        let source_dir = source_layers[0].clone();
+
         // Initialize with empty data, will scan after CLI selection
         let components = Vec::new();
         let mcp_servers = Vec::new();
@@ -195,45 +395,88 @@ impl App {
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default();
 
-        Ok(Self {
+        Self {
             target_cli: None,
             available_tabs: Vec::new(), // Will be set after CLI selection
             tab: Tab::Agents,
             current_view: View::CliSelection,
             should_quit: false,
-            theme: Theme::default(),
+            theme,
+            keymap,
+            show_help: false,
+            hit_map: RefCell::default(),
+            last_click: None,
             components,
             list_index: 0,
             tree_views,
+            dep_graph: DependencyGraph::default(),
+            detail_cache: RefCell::default(),
+            remote_fetch: None,
             mcp_servers,
             mcp_index: 0,
             mcp_scope: McpScope::default(),
             mcp_project_path: default_project.clone(),
+            mcp_loaded: false,
             plugins,
             plugin_index: 0,
+            plugins_loaded: false,
+            plugin_browse: false,
+            browse_plugins: Vec::new(),
+            browse_index: 0,
             diff_content: None,
             diff_scroll: 0,
             source_dir,
+            layers,
+            source_layers,
             dest_dir,
+            scan_cache: fs::cache::cache_path(),
             status_message: None,
+            pending_removal: None,
             current_output_style: None,
             current_statusline: None,
             processing_progress: None,
             processing_total: None,
             processing_log: Vec::new(),
             processing_queue: Vec::new(),
+            processing_items: Vec::new(),
+            processing_cursor: 0,
             is_removing: false,
             animation_frame: 0,
             needs_refresh: false,
             refreshing: false,
             processing_complete: false,
+            journal: None,
+            history: Vec::new(),
+            history_index: 0,
+            snapshots: Vec::new(),
+            snapshot_index: 0,
+            snapshot_log: Vec::new(),
+            pending_restore: None,
+            profiles: Vec::new(),
+            profile_index: 0,
+            profile_name_input: None,
+            doctor_findings: Vec::new(),
+            doctor_index: 0,
+            doctor_log: Vec::new(),
+            purge_plan: None,
+            purge_index: 0,
+            purge_batch: None,
+            permissions: Vec::new(),
+            permission_index: 0,
+            permission_scope: SettingsScope::User,
+            default_mode: None,
+            env_vars: Vec::new(),
+            env_index: 0,
+            env_edit: None,
+            review_items: Vec::new(),
+            review_index: 0,
             env_input_server_idx: None,
             env_input_vars: Vec::new(),
             env_input_current: 0,
             env_input_buffer: String::new(),
             env_input_values: Vec::new(),
             project_path_buffer: default_project,
-        })
+        }
     }
 
     pub fn select_cli(&mut self, cli: TargetCli) -> Result<()> {
@@ -261,14 +504,26 @@ impl App {
         let (current_output_style, current_statusline) = read_current_settings(&self.dest_dir);
         self.current_output_style = current_output_style;
         self.current_statusline = current_statusline;
+        self.load_permissions();
+        self.load_env_vars();
 
-        // Build tree views
+        // Build tree views and dependency graph
         self.tree_views = build_tree_views(&self.components);
+        self.dep_graph = DependencyGraph::build(&self.components);
 
         // Switch to list view
         self.current_view = View::List;
         if let Some(cli) = self.target_cli {
-            self.status_message = Some(format!("Selected {}", cli.display_name()));
+            let (errors, warnings) = crate::lint::summarize(&self.components);
+            let mut message = if errors + warnings > 0 {
+                format!("Selected {} · lint: {} errors, {} warnings", cli.display_name(), errors, warnings)
+            } else {
+                format!("Selected {}", cli.display_name())
+            };
+            if let Some(notice) = self.remote_notice() {
+                message = format!("{} · {}", message, notice);
+            }
+            self.status_message = Some(message);
         }
     }
 
@@ -319,9 +574,20 @@ impl App {
                 self.mcp_index = (self.mcp_index + 1) % len;
             }
         } else if self.tab == Tab::Plugins {
-            let len = self.plugins.len();
+            let len = self.active_plugins().len();
+            if len > 0 {
+                let index = self.active_plugin_index_mut();
+                *index = (*index + 1) % len;
+            }
+        } else if self.tab == Tab::Permissions {
+            let len = self.permissions.len();
             if len > 0 {
-                self.plugin_index = (self.plugin_index + 1) % len;
+                self.permission_index = (self.permission_index + 1) % len;
+            }
+        } else if self.tab == Tab::Env {
+            let len = self.env_vars.len();
+            if len > 0 {
+                self.env_index = (self.env_index + 1) % len;
             }
         } else if let Some(tree) = self.tree_views.get_mut(&self.tab) {
             tree.next();
@@ -335,9 +601,20 @@ impl App {
                 self.mcp_index = if self.mcp_index == 0 { len - 1 } else { self.mcp_index - 1 };
             }
         } else if self.tab == Tab::Plugins {
-            let len = self.plugins.len();
+            let len = self.active_plugins().len();
+            if len > 0 {
+                let index = self.active_plugin_index_mut();
+                *index = if *index == 0 { len - 1 } else { *index - 1 };
+            }
+        } else if self.tab == Tab::Permissions {
+            let len = self.permissions.len();
             if len > 0 {
-                self.plugin_index = if self.plugin_index == 0 { len - 1 } else { self.plugin_index - 1 };
+                self.permission_index = if self.permission_index == 0 { len - 1 } else { self.permission_index - 1 };
+            }
+        } else if self.tab == Tab::Env {
+            let len = self.env_vars.len();
+            if len > 0 {
+                self.env_index = if self.env_index == 0 { len - 1 } else { self.env_index - 1 };
             }
         } else if let Some(tree) = self.tree_views.get_mut(&self.tab) {
             tree.prev();
@@ -402,6 +679,350 @@ impl App {
         self.tree_views.get(&self.tab)
     }
 
+    /// Cursor row and row count of the list on the current tab
+    fn list_position(&self) -> (usize, usize) {
+        match self.tab {
+            Tab::McpServers => (self.mcp_index, self.mcp_servers.len()),
+            Tab::Plugins => (self.active_plugin_index(), self.active_plugins().len()),
+            Tab::Permissions => (self.permission_index, self.permissions.len()),
+            Tab::Env => (self.env_index, self.env_vars.len()),
+            _ => self
+                .tree_views
+                .get(&self.tab)
+                .map(|t| (t.cursor, t.visible_indices.len()))
+                .unwrap_or((0, 0)),
+        }
+    }
+
+    /// Move the cursor to a row of the current list (clamped, no wrap-around)
+    pub fn select_row(&mut self, row: usize) {
+        let (_, len) = self.list_position();
+        if len == 0 {
+            return;
+        }
+        let row = row.min(len - 1);
+        match self.tab {
+            Tab::McpServers => self.mcp_index = row,
+            Tab::Plugins => *self.active_plugin_index_mut() = row,
+            Tab::Permissions => self.permission_index = row,
+            Tab::Env => self.env_index = row,
+            _ => {
+                if let Some(tree) = self.tree_views.get_mut(&self.tab) {
+                    tree.select(row);
+                }
+            }
+        }
+    }
+
+    /// Move the cursor by `delta` rows (mouse wheel)
+    pub fn scroll_rows(&mut self, delta: isize) {
+        let (row, _) = self.list_position();
+        self.select_row(row.saturating_add_signed(delta));
+    }
+
+    /// Columns a row is indented by before its checkbox (tree depth)
+    pub fn row_indent(&self, row: usize) -> u16 {
+        self.tree_views
+            .get(&self.tab)
+            .map(|t| t.row_depth(row) as u16 * 2)
+            .unwrap_or(0)
+    }
+
+    /// Record a click; true when it completes a double-click on the same cell
+    pub fn register_click(&mut self, column: u16, row: u16) -> bool {
+        let now = Instant::now();
+        let double = matches!(self.last_click, Some((at, c, r))
+            if c == column && r == row && now.duration_since(at) <= DOUBLE_CLICK);
+        self.last_click = if double { None } else { Some((now, column, row)) };
+        double
+    }
+
+    /// Plugin list shown in the Plugins tab (catalog or marketplace browse)
+    pub fn active_plugins(&self) -> &[Plugin] {
+        if self.plugin_browse {
+            &self.browse_plugins
+        } else {
+            &self.plugins
+        }
+    }
+
+    fn active_plugins_mut(&mut self) -> &mut [Plugin] {
+        if self.plugin_browse {
+            &mut self.browse_plugins
+        } else {
+            &mut self.plugins
+        }
+    }
+
+    pub fn active_plugin_index(&self) -> usize {
+        if self.plugin_browse {
+            self.browse_index
+        } else {
+            self.plugin_index
+        }
+    }
+
+    fn active_plugin_index_mut(&mut self) -> &mut usize {
+        if self.plugin_browse {
+            &mut self.browse_index
+        } else {
+            &mut self.plugin_index
+        }
+    }
+
+    /// Toggle between plugins.yaml catalog and marketplace browse mode
+    pub fn toggle_plugin_browse(&mut self) {
+        if self.plugin_browse {
+            self.plugin_browse = false;
+            self.status_message = Some("Plugins: plugins.yaml".to_string());
+            return;
+        }
+
+        match fs::scanner::scan_marketplace_plugins(&self.source_layers, &self.dest_dir) {
+            Ok(plugins) if plugins.is_empty() => {
+                self.status_message = Some("No cloned marketplaces found under plugins/marketplaces".to_string());
+            }
+            Ok(plugins) => {
+                self.status_message = Some(format!("Browsing {} marketplace plugins", plugins.len()));
+                self.browse_plugins = plugins;
+                self.browse_index = 0;
+                self.plugin_browse = true;
+            }
+            Err(e) => {
+                self.status_message = Some(format!("Failed to read marketplaces: {}", e));
+            }
+        }
+    }
+
+    /// Add selected browse-mode plugins to plugins/plugins.yaml
+    pub fn add_browsed_to_catalog(&mut self) -> Result<()> {
+        if !self.plugin_browse {
+            return Ok(());
+        }
+
+        let defs: Vec<crate::plugin::PluginDef> = self.browse_plugins
+            .iter()
+            .filter(|p| p.selected)
+            .map(|p| p.def.clone())
+            .collect();
+
+        if defs.is_empty() {
+            self.status_message = Some("No items selected".to_string());
+            return Ok(());
+        }
+
+        let catalog_path = self.source_dir.join("plugins/plugins.yaml");
+        let content = if catalog_path.exists() {
+            std::fs::read_to_string(&catalog_path)?
+        } else {
+            String::new()
+        };
+
+        let before = self.plugins.len();
+        let updated = crate::plugin::add_plugins_to_yaml(&content, &defs);
+        if let Some(parent) = catalog_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&catalog_path, updated)?;
+
+        self.plugins = fs::scanner::scan_plugins(&self.source_layers)?;
+        self.plugins_loaded = true;
+        let added = self.plugins.len().saturating_sub(before);
+        self.status_message = Some(format!("Added {} plugins to plugins.yaml", added));
+        Ok(())
+    }
+
+    /// Settings files the Permissions tab reads, lowest precedence first
+    fn settings_files(&self) -> Vec<(SettingsScope, PathBuf)> {
+        let project = PathBuf::from(&self.mcp_project_path);
+        [SettingsScope::User, SettingsScope::Project, SettingsScope::Local]
+            .into_iter()
+            .map(|scope| (scope, scope.path(&self.dest_dir, &project)))
+            .collect()
+    }
+
+    pub fn load_permissions(&mut self) {
+        let catalog = match crate::permission::load_catalog(&self.source_layers) {
+            Ok(catalog) => catalog,
+            Err(e) => {
+                self.status_message = Some(format!("{:#}", e));
+                Vec::new()
+            }
+        };
+        let files = self.settings_files();
+        self.permissions = crate::permission::collect(&catalog, &files);
+        self.default_mode = crate::permission::default_mode(&files);
+        self.permission_index = self.permission_index.min(self.permissions.len().saturating_sub(1));
+    }
+
+    /// Switch to the next theme and remember it in the installer config
+    pub fn cycle_theme(&mut self) {
+        self.theme.cycle();
+        self.status_message = Some(match crate::config::save_theme(self.theme.id()) {
+            Ok(()) => format!("Theme: {}", self.theme.name()),
+            Err(e) => format!("Theme: {} (not saved: {})", self.theme.name(), e),
+        });
+    }
+
+    pub fn toggle_permission_scope(&mut self) {
+        self.permission_scope = self.permission_scope.next();
+        self.status_message = Some(format!(
+            "Permissions write to {} settings ({})",
+            self.permission_scope.display(),
+            self.permission_scope_path().display()
+        ));
+    }
+
+    pub fn permission_scope_path(&self) -> PathBuf {
+        self.permission_scope.path(&self.dest_dir, Path::new(&self.mcp_project_path))
+    }
+
+    /// Add (or remove) the selected rules in the settings file of `permission_scope`
+    fn apply_permissions(&mut self, remove: bool) -> Result<()> {
+        let selected: Vec<PermissionRule> = self.permissions.iter().filter(|r| r.selected).cloned().collect();
+        if selected.is_empty() {
+            self.status_message = Some("No items selected".to_string());
+            return Ok(());
+        }
+
+        let path = self.permission_scope_path();
+        let (mut changed, mut skipped) = (0, Vec::new());
+        for r in &selected {
+            let result = if remove {
+                crate::permission::remove_rule(&path, r.def.kind, &r.def.rule)
+            } else if let Some(error) = &r.error {
+                Err(anyhow::anyhow!("{}", error))
+            } else {
+                crate::permission::add_rule(&path, r.def.kind, &r.def.rule)
+            };
+            match result {
+                Ok(true) => changed += 1,
+                Ok(false) => {}
+                Err(e) => skipped.push(format!("{}: {}", r.def.rule, e)),
+            }
+        }
+
+        self.load_permissions();
+        let verb = if remove { "Removed" } else { "Added" };
+        let mut message = format!("{} {} rules in {} settings", verb, changed, self.permission_scope.display());
+        if !skipped.is_empty() {
+            message = format!("{} · skipped {}", message, skipped.join("; "));
+        }
+        self.status_message = Some(message);
+        Ok(())
+    }
+
+    pub fn load_env_vars(&mut self) {
+        let catalog = match crate::env_var::load_catalog(&self.source_layers) {
+            Ok(catalog) => catalog,
+            Err(e) => {
+                self.status_message = Some(format!("{:#}", e));
+                Vec::new()
+            }
+        };
+        // The repo value comes from the settings.json that would be merged
+        let repo_settings = self
+            .components
+            .iter()
+            .find(|c| c.component_type == ComponentType::ConfigFile && c.name == "settings.json")
+            .map(|c| c.source_path.clone());
+        self.env_vars = crate::env_var::collect(&catalog, repo_settings.as_deref(), &self.dest_dir.join("settings.json"));
+        self.env_index = self.env_index.min(self.env_vars.len().saturating_sub(1));
+    }
+
+    pub fn start_env_edit(&mut self) {
+        if let Some(var) = self.env_vars.get(self.env_index) {
+            let current = var.local.as_ref().or(var.repo.as_ref());
+            self.env_edit = Some(current.map(crate::env_var::format_value).unwrap_or_default());
+        }
+    }
+
+    pub fn env_edit_char(&mut self, c: char) {
+        if let Some(buffer) = &mut self.env_edit {
+            buffer.push(c);
+        }
+    }
+
+    pub fn env_edit_backspace(&mut self) {
+        if let Some(buffer) = &mut self.env_edit {
+            buffer.pop();
+        }
+    }
+
+    pub fn env_edit_cancel(&mut self) {
+        self.env_edit = None;
+    }
+
+    /// Validate and write the edited value; a local value that differs from the repo is kept on merge
+    pub fn submit_env_edit(&mut self) -> Result<()> {
+        let (Some(buffer), Some(var)) = (&self.env_edit, self.env_vars.get(self.env_index)) else {
+            return Ok(());
+        };
+        let value = match var.kind.parse(buffer) {
+            Ok(value) => value,
+            Err(e) => {
+                self.status_message = Some(format!("{}: {}", var.name, e));
+                return Ok(());
+            }
+        };
+
+        let name = var.name.clone();
+        let keep_local = var.repo.as_ref().is_some_and(|repo| *repo != value);
+        crate::env_var::set_local(&self.dest_dir.join("settings.json"), &name, Some(value.clone()))?;
+        self.remember_env_choice(&name, keep_local.then_some(Resolution::KeepLocal))?;
+
+        self.env_edit = None;
+        self.load_env_vars();
+        self.refresh_config_statuses();
+        self.status_message = Some(format!("Set {} = {}", name, crate::env_var::format_value(&value)));
+        Ok(())
+    }
+
+    /// Put the repo value back (or drop the key when the repo has none)
+    pub fn reset_env_var(&mut self) -> Result<()> {
+        let Some(var) = self.env_vars.get(self.env_index) else {
+            return Ok(());
+        };
+        let (name, repo) = (var.name.clone(), var.repo.clone());
+        crate::env_var::set_local(&self.dest_dir.join("settings.json"), &name, repo.clone())?;
+        self.remember_env_choice(&name, None)?;
+
+        self.load_env_vars();
+        self.refresh_config_statuses();
+        self.status_message = Some(match repo {
+            Some(value) => format!("Reset {} to {}", name, crate::env_var::format_value(&value)),
+            None => format!("Removed {} (not in the repo)", name),
+        });
+        Ok(())
+    }
+
+    pub fn unset_env_var(&mut self) -> Result<()> {
+        let Some(var) = self.env_vars.get(self.env_index) else {
+            return Ok(());
+        };
+        let name = var.name.clone();
+        crate::env_var::set_local(&self.dest_dir.join("settings.json"), &name, None)?;
+
+        self.load_env_vars();
+        self.refresh_config_statuses();
+        self.status_message = Some(format!("Unset {}", name));
+        Ok(())
+    }
+
+    /// Record (or forget) the merge choice for `env.<name>` in settings.json
+    fn remember_env_choice(&self, name: &str, resolution: Option<Resolution>) -> Result<()> {
+        let key = crate::conflict::key_path("env", name);
+        let mut choices = crate::conflict::load(&self.dest_dir, "settings.json");
+        let changed = match resolution {
+            Some(resolution) => choices.insert(key, resolution) != Some(resolution),
+            None => choices.remove(&key).is_some(),
+        };
+        if changed {
+            crate::conflict::save(&self.dest_dir, "settings.json", &choices)?;
+        }
+        Ok(())
+    }
+
     pub fn toggle_mcp_scope(&mut self) {
         self.mcp_scope = self.mcp_scope.toggle();
         if self.mcp_scope == McpScope::Local {
@@ -442,17 +1063,42 @@ impl App {
                 m.selected = !m.selected;
             }
         } else if self.tab == Tab::Plugins {
-            if let Some(p) = self.plugins.get_mut(self.plugin_index) {
+            let index = self.active_plugin_index();
+            if let Some(p) = self.active_plugins_mut().get_mut(index) {
                 p.selected = !p.selected;
             }
+        } else if self.tab == Tab::Permissions {
+            if let Some(r) = self.permissions.get_mut(self.permission_index) {
+                r.selected = !r.selected;
+            }
         } else if self.is_cursor_on_folder() {
             // Toggle all components under folder
             self.toggle_folder_selection();
         } else if let Some(idx) = self.selected_component_index() {
             if let Some(c) = self.components.get_mut(idx) {
                 c.selected = !c.selected;
+                if c.selected {
+                    self.select_dependencies(&[idx]);
+                }
+            }
+        }
+    }
+
+    /// Select everything the given components require (transitively, across tabs)
+    fn select_dependencies(&mut self, roots: &[usize]) {
+        let mut added = Vec::new();
+        for idx in self.dep_graph.closure(roots) {
+            if let Some(c) = self.components.get_mut(idx)
+                && !c.selected
+            {
+                c.selected = true;
+                added.push(c.display_name());
             }
         }
+
+        if !added.is_empty() {
+            self.status_message = Some(format!("Also selected dependencies: {}", added.join(", ")));
+        }
     }
 
     /// Toggle selection for all components under current folder
@@ -475,6 +1121,9 @@ impl App {
                         c.selected = new_state;
                     }
                 }
+                if new_state {
+                    self.select_dependencies(&indices);
+                }
             }
         }
     }
@@ -485,15 +1134,21 @@ impl App {
                 m.selected = true;
             }
         } else if self.tab == Tab::Plugins {
-            for p in &mut self.plugins {
+            for p in self.active_plugins_mut() {
                 p.selected = true;
             }
+        } else if self.tab == Tab::Permissions {
+            for r in &mut self.permissions {
+                r.selected = true;
+            }
         } else if let Some(comp_type) = self.tab.to_component_type() {
             for c in &mut self.components {
                 if c.component_type == comp_type {
                     c.selected = true;
                 }
             }
+            let indices: Vec<usize> = self.current_components().iter().map(|(i, _)| *i).collect();
+            self.select_dependencies(&indices);
         }
     }
 
@@ -503,9 +1158,13 @@ impl App {
                 m.selected = false;
             }
         } else if self.tab == Tab::Plugins {
-            for p in &mut self.plugins {
+            for p in self.active_plugins_mut() {
                 p.selected = false;
             }
+        } else if self.tab == Tab::Permissions {
+            for r in &mut self.permissions {
+                r.selected = false;
+            }
         } else if let Some(comp_type) = self.tab.to_component_type() {
             for c in &mut self.components {
                 if c.component_type == comp_type {
@@ -522,7 +1181,13 @@ impl App {
 
         if let Some(idx) = self.selected_component_index() {
             if let Some(c) = self.components.get(idx) {
-                let diff = fs::diff::compare_files(&c.source_path, &c.dest_path)?;
+                let diff = match c.component_type {
+                    ComponentType::ConfigFile => {
+                        let choices = crate::conflict::load(&self.dest_dir, &c.name);
+                        fs::merge::diff(&c.config_file_def(), &c.source_path, &c.dest_path, &choices)?
+                    }
+                    _ => fs::diff::compare_files(&c.source_path, &c.dest_path)?,
+                };
                 self.diff_content = Some(diff);
                 self.diff_scroll = 0;
                 self.current_view = View::Diff;
@@ -545,6 +1210,10 @@ impl App {
     }
 
     pub fn install_selected(&mut self) -> Result<()> {
+        if self.tab == Tab::Permissions {
+            return self.apply_permissions(false);
+        }
+
         // Build install queue
         let indices: Vec<usize> = if self.tab == Tab::McpServers {
             self.mcp_servers
@@ -554,7 +1223,7 @@ impl App {
                 .map(|(i, _)| i)
                 .collect()
         } else if self.tab == Tab::Plugins {
-            self.plugins
+            self.active_plugins()
                 .iter()
                 .enumerate()
                 .filter(|(_, p)| p.selected)
@@ -576,6 +1245,27 @@ impl App {
             return Ok(());
         }
 
+        // Install selected dependencies from other tabs first
+        let indices = if self.tab.to_component_type().is_some() {
+            let mut queue: Vec<usize> = self
+                .dep_graph
+                .closure(&indices)
+                .into_iter()
+                .filter(|&i| self.components[i].selected)
+                .collect();
+            queue.sort();
+            queue.extend(indices);
+            queue
+        } else {
+            indices
+        };
+
+        // Config files whose merge overwrites local values are reviewed first
+        if self.tab == Tab::Config && self.start_merge_review(&indices, true)? {
+            self.processing_queue = indices;
+            return Ok(());
+        }
+
         // For MCP servers, check if any have missing env vars
         if self.tab == Tab::McpServers {
             for &idx in &indices {
@@ -597,16 +1287,119 @@ impl App {
 
         // Initialize install state (no env input needed)
         self.processing_queue = indices;
-        self.processing_total = Some(self.processing_queue.len());
-        self.processing_progress = Some(0);
-        self.processing_log.clear();
-        self.processing_log.push(format!("Starting installation of {} items...", self.processing_queue.len()));
-        self.is_removing = false;
-        self.current_view = View::Installing;
+        self.begin_processing(false);
+
+        Ok(())
+    }
+
+    /// Open the merge review for the conflicts of `indices`.
+    /// With `unresolved_only`, nothing opens unless some conflict has no remembered choice.
+    fn start_merge_review(&mut self, indices: &[usize], unresolved_only: bool) -> Result<bool> {
+        let mut items = Vec::new();
+        for &idx in indices {
+            let c = &self.components[idx];
+            if c.component_type != ComponentType::ConfigFile {
+                continue;
+            }
+            let choices = crate::conflict::load(&self.dest_dir, &c.name);
+            for conflict in fs::merge::conflicts(&c.config_file_def(), &c.source_path, &c.dest_path)? {
+                let remembered = choices.get(&conflict.key).copied();
+                items.push(ReviewItem {
+                    file: c.name.clone(),
+                    conflict,
+                    resolution: remembered.unwrap_or_default(),
+                    remembered: remembered.is_some(),
+                });
+            }
+        }
+
+        if items.is_empty() || (unresolved_only && items.iter().all(|i| i.remembered)) {
+            return Ok(false);
+        }
+        self.review_items = items;
+        self.review_index = 0;
+        self.current_view = View::MergeReview;
+        Ok(true)
+    }
+
+    /// Review the conflicts of the config file under the cursor (remembered choices included)
+    pub fn open_merge_review(&mut self) -> Result<()> {
+        let Some(idx) = self.selected_component_index() else {
+            return Ok(());
+        };
+        if !self.start_merge_review(&[idx], false)? {
+            self.status_message = Some("No conflicts with the local file".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn next_review_item(&mut self) {
+        if self.review_index + 1 < self.review_items.len() {
+            self.review_index += 1;
+        }
+    }
+
+    pub fn prev_review_item(&mut self) {
+        self.review_index = self.review_index.saturating_sub(1);
+    }
+
+    pub fn toggle_review_item(&mut self) {
+        if let Some(item) = self.review_items.get_mut(self.review_index) {
+            item.resolution = item.resolution.toggle();
+        }
+    }
+
+    /// Set the highlighted conflict (`all` = every conflict) to `resolution`
+    pub fn resolve_review(&mut self, resolution: Resolution, all: bool) {
+        for (i, item) in self.review_items.iter_mut().enumerate() {
+            if all || i == self.review_index {
+                item.resolution = resolution;
+            }
+        }
+    }
+
+    /// Remember the choices, then continue the queued install (if any)
+    pub fn confirm_merge_review(&mut self) -> Result<()> {
+        let items = std::mem::take(&mut self.review_items);
+        let mut files: BTreeMap<String, Choices> = BTreeMap::new();
+        for item in &items {
+            files
+                .entry(item.file.clone())
+                .or_insert_with(|| crate::conflict::load(&self.dest_dir, &item.file))
+                .insert(item.conflict.key.clone(), item.resolution);
+        }
+        for (file, choices) in &files {
+            crate::conflict::save(&self.dest_dir, file, choices)?;
+        }
 
+        self.refresh_config_statuses();
+
+        if self.processing_queue.is_empty() {
+            let taken = items.iter().filter(|i| i.resolution == Resolution::TakeRepo).count();
+            self.status_message = Some(format!("Saved {} choices ({} take repo)", items.len(), taken));
+            self.current_view = View::List;
+        } else {
+            self.begin_processing(false);
+        }
         Ok(())
     }
 
+    /// Recompute config file statuses after settings or merge choices changed
+    fn refresh_config_statuses(&mut self) {
+        for c in self.components.iter_mut().filter(|c| c.component_type == ComponentType::ConfigFile) {
+            let choices = crate::conflict::load(&self.dest_dir, &c.name);
+            if let Ok(status) = fs::merge::status(&c.config_file_def(), &c.source_path, &c.dest_path, &choices) {
+                c.status = status;
+            }
+        }
+    }
+
+    pub fn cancel_merge_review(&mut self) {
+        self.review_items.clear();
+        self.processing_queue.clear();
+        self.current_view = View::List;
+    }
+
     fn start_env_input(&mut self, server_idx: usize, missing_vars: Vec<String>) {
         self.env_input_server_idx = Some(server_idx);
         self.env_input_vars = missing_vars;
@@ -655,13 +1448,7 @@ impl App {
     }
 
     fn continue_mcp_install(&mut self) -> Result<()> {
-        // Initialize install state
-        self.processing_total = Some(self.processing_queue.len());
-        self.processing_progress = Some(0);
-        self.processing_log.clear();
-        self.processing_log.push(format!("Starting installation of {} items...", self.processing_queue.len()));
-        self.is_removing = false;
-        self.current_view = View::Installing;
+        self.begin_processing(false);
         Ok(())
     }
 
@@ -675,33 +1462,383 @@ impl App {
             .map(|s| s.def.name.as_str())
     }
 
-    pub fn start_finish_processing(&mut self) {
-        let action = if self.is_removing { "Removal" } else { "Installation" };
-        self.processing_log.push(format!("[OK] {} complete!", action));
-        self.processing_log.push("".to_string());  // Empty line for spacing
-        self.processing_log.push("Refreshing status...".to_string());
-        self.needs_refresh = true;
+    /// Move `processing_queue` into per-item state and switch to the Installing view
+    fn begin_processing(&mut self, removing: bool) {
+        let queue = std::mem::take(&mut self.processing_queue);
+        self.processing_items = queue
+            .into_iter()
+            .map(|idx| ProcessItem {
+                idx,
+                name: self.processing_item_name(idx),
+                state: ItemState::Pending,
+                duration: None,
+                cancel: fs::CancelHandle::default(),
+            })
+            .collect();
+        self.processing_cursor = 0;
+        self.processing_total = Some(self.processing_items.len());
+        self.processing_progress = Some(0);
+        self.processing_log.clear();
+        let action = if removing { "removal" } else { "installation" };
+        self.processing_log.push(format!("Starting {} of {} items...", action, self.processing_items.len()));
+        self.is_removing = removing;
+        self.current_view = View::Installing;
+        self.begin_journal();
     }
 
-    pub fn apply_refresh_result(&mut self, components: Vec<Component>, mcp_servers: Vec<McpServer>, plugins: Vec<Plugin>) {
-        self.components = components;
+    /// Start recording the batch; component files are backed up before any worker runs
+    fn begin_journal(&mut self) {
+        // A purge is undone from its pre-purge snapshot instead
+        if let Some(batch) = &self.purge_batch {
+            self.processing_log.push(format!("[OK] Saved current state as {}", batch.safety));
+            return;
+        }
+        let action = if self.is_removing { "remove" } else { "install" };
+        let target = self.target_cli.unwrap_or(TargetCli::Claude).display_name().to_string();
+
+        let result = Journal::begin(&self.dest_dir, action, &target).and_then(|mut journal| {
+            if self.tab.to_component_type().is_some() {
+                for item in &self.processing_items {
+                    if let Some(c) = self.components.get(item.idx) {
+                        journal.record_file(&c.dest_path)?;
+                    }
+                }
+            }
+            if self.tab == Tab::McpServers {
+                journal.record_mcp_before(self.queued_mcp_registrations());
+            }
+            Ok(journal)
+        });
+
+        match result {
+            Ok(journal) => self.journal = Some(journal),
+            Err(e) => {
+                self.journal = None;
+                self.processing_log.push(format!("[SKIP] History not recorded: {}", e));
+            }
+        }
+    }
+
+    /// Current registrations (scope, project, env) of the queued MCP servers
+    fn queued_mcp_registrations(&self) -> Vec<fs::scanner::McpRegistration> {
+        let names: Vec<String> = self.processing_items.iter().map(|i| self.processing_item_name(i.idx)).collect();
+        fs::scanner::mcp_registrations(self.target_cli.unwrap_or(TargetCli::Claude), &self.dest_dir)
+            .into_iter()
+            .filter(|r| names.contains(&r.name))
+            .collect()
+    }
+
+    /// Journal kind and name of a queued item
+    fn journal_item(&self, idx: usize) -> (ItemKind, String) {
+        if self.tab == Tab::McpServers {
+            (ItemKind::Mcp, self.processing_item_name(idx))
+        } else if self.tab == Tab::Plugins {
+            (ItemKind::Plugin, self.processing_item_name(idx))
+        } else {
+            let name = self.components.get(idx).map(|c| c.display_name()).unwrap_or_default();
+            (ItemKind::Component, name)
+        }
+    }
+
+    /// Display name of a queued item (depends on the current tab)
+    pub fn processing_item_name(&self, idx: usize) -> String {
+        if let Some(batch) = &self.purge_batch {
+            batch.describe(idx)
+        } else if self.tab == Tab::McpServers {
+            self.mcp_servers.get(idx).map(|s| s.def.name.clone()).unwrap_or_default()
+        } else if self.tab == Tab::Plugins {
+            self.active_plugins().get(idx).map(|p| p.def.id()).unwrap_or_default()
+        } else {
+            self.components.get(idx).map(|c| c.name.clone()).unwrap_or_default()
+        }
+    }
+
+    /// Mark the next pending item that is not waiting on another one as running and
+    /// return its slot
+    pub fn next_pending_item(&mut self) -> Option<usize> {
+        let pending = |item: &ProcessItem| item.state == ItemState::Pending;
+        let slot = match (0..self.processing_items.len()).find(|&s| pending(&self.processing_items[s]) && !self.is_blocked(s)) {
+            Some(slot) => slot,
+            // Everything left waits on something: a `requires:` cycle, run in queue order
+            None if self.count_items(ItemState::Running) == 0 => self.processing_items.iter().position(pending)?,
+            None => return None,
+        };
+        let item = &mut self.processing_items[slot];
+        item.state = ItemState::Running;
+        item.cancel = fs::CancelHandle::default();
+        Some(slot)
+    }
+
+    /// Components wait for the batch items they require (install) or that require them
+    /// (remove) to finish
+    fn is_blocked(&self, slot: usize) -> bool {
+        if let Some(batch) = &self.purge_batch {
+            let phase = batch.phase(self.processing_items[slot].idx);
+            return self.processing_items.iter().any(|other| batch.phase(other.idx) < phase && !other.state.is_finished());
+        }
+        if self.tab.to_component_type().is_none() {
+            return false;
+        }
+        let idx = self.processing_items[slot].idx;
+        let first = if self.is_removing { self.dep_graph.required_by(idx) } else { self.dep_graph.requires(idx) };
+        self.processing_items
+            .iter()
+            .any(|other| other.idx != idx && first.contains(&other.idx) && !other.state.is_finished())
+    }
+
+    /// Record a worker result. `message` is the `[OK]`/`[ERR]` log line.
+    pub fn finish_process_item(&mut self, slot: usize, message: String, duration: Duration) {
+        let mut ok = !message.starts_with("[ERR]");
+        let mut message = message;
+        if let Some(item) = self.processing_items.get_mut(slot) {
+            item.state = if ok { ItemState::Done } else { ItemState::Failed };
+            item.duration = Some(duration);
+            // Killed from cancel_processing / cancel_processing_item
+            if !ok && item.cancel.is_cancelled() {
+                item.state = ItemState::Cancelled;
+                message = format!("[SKIP] Cancelled {}", item.name);
+            }
+            ok &= item.state == ItemState::Done;
+        }
+        if let Some(idx) = self.processing_items.get(slot).map(|i| i.idx) {
+            let (kind, name) = self.journal_item(idx);
+            if let Some(journal) = &mut self.journal {
+                journal.record_item(kind, name, ok);
+            }
+        }
+        self.processing_log.push(format!("{} ({:.1}s)", message, duration.as_secs_f64()));
+        self.update_processing_progress();
+    }
+
+    pub fn all_items_finished(&self) -> bool {
+        self.processing_items.iter().all(|i| i.state.is_finished())
+    }
+
+    fn update_processing_progress(&mut self) {
+        let finished = self.processing_items.iter().filter(|i| i.state.is_finished()).count();
+        self.processing_progress = Some(finished);
+    }
+
+    /// Cancel every pending item and kill the CLI processes of running ones (they are
+    /// marked cancelled when their worker returns)
+    pub fn cancel_processing(&mut self) {
+        let (mut cancelled, mut stopped) = (0, 0);
+        for item in &mut self.processing_items {
+            match item.state {
+                ItemState::Pending => {
+                    item.state = ItemState::Cancelled;
+                    cancelled += 1;
+                }
+                ItemState::Running => {
+                    item.cancel.cancel();
+                    stopped += 1;
+                }
+                _ => {}
+            }
+        }
+        if cancelled > 0 {
+            self.processing_log.push(format!("[SKIP] Cancelled {} pending items", cancelled));
+        }
+        if stopped > 0 {
+            self.processing_log.push(format!("[SKIP] Stopping {} running items", stopped));
+        }
+        self.update_processing_progress();
+    }
+
+    /// Cancel the highlighted item, killing its CLI process if it is running
+    pub fn cancel_processing_item(&mut self) {
+        let Some(item) = self.processing_items.get_mut(self.processing_cursor) else {
+            return;
+        };
+        match item.state {
+            ItemState::Pending => {
+                item.state = ItemState::Cancelled;
+                let name = item.name.clone();
+                self.processing_log.push(format!("[SKIP] Cancelled {}", name));
+                self.update_processing_progress();
+            }
+            ItemState::Running => {
+                item.cancel.cancel();
+                let name = item.name.clone();
+                self.processing_log.push(format!("[SKIP] Stopping {}", name));
+            }
+            _ => {}
+        }
+    }
+
+    /// Re-queue failed items (after the previous run has fully completed)
+    pub fn retry_failed(&mut self) {
+        let mut retried = 0;
+        for item in &mut self.processing_items {
+            if item.state == ItemState::Failed {
+                item.state = ItemState::Pending;
+                item.duration = None;
+                retried += 1;
+            }
+        }
+        if retried == 0 {
+            return;
+        }
+
+        self.processing_log.push(format!("Retrying {} failed items...", retried));
+        self.processing_complete = false;
+        self.update_processing_progress();
+    }
+
+    pub fn move_processing_cursor(&mut self, delta: isize) {
+        let max = self.processing_items.len().saturating_sub(1);
+        self.processing_cursor = self.processing_cursor.saturating_add_signed(delta).min(max);
+    }
+
+    fn count_items(&self, state: ItemState) -> usize {
+        self.processing_items.iter().filter(|i| i.state == state).count()
+    }
+
+    pub fn start_finish_processing(&mut self) {
+        let action = if self.is_removing { "Removal" } else { "Installation" };
+        let failed = self.count_items(ItemState::Failed);
+        let cancelled = self.count_items(ItemState::Cancelled);
+        if failed + cancelled > 0 {
+            self.processing_log.push(format!(
+                "[OK] {} finished: {} ok, {} failed, {} cancelled",
+                action,
+                self.count_items(ItemState::Done),
+                failed,
+                cancelled
+            ));
+        } else {
+            self.processing_log.push(format!("[OK] {} complete!", action));
+        }
+        if let Some(batch) = &self.purge_batch
+            && let Some(line) = crate::purge::forget_merge_choices(&batch.dest_dir, failed + cancelled > 0)
+        {
+            self.processing_log.push(line);
+        }
+        let mcp_after = (self.tab == Tab::McpServers).then(|| self.queued_mcp_registrations());
+        if let Some(journal) = &mut self.journal {
+            if let Some(registrations) = mcp_after {
+                journal.record_mcp_after(registrations);
+            }
+            if let Err(e) = journal.save(&self.dest_dir) {
+                self.processing_log.push(format!("[ERR] Failed to write history: {}", e));
+            }
+        }
+        self.processing_log.push("".to_string());  // Empty line for spacing
+        self.processing_log.push("Refreshing status...".to_string());
+        self.needs_refresh = true;
+    }
+
+    pub fn apply_refresh_result(&mut self, components: Vec<Component>, mcp_servers: Vec<McpServer>, plugins: Vec<Plugin>) {
+        self.components = components;
         self.mcp_servers = mcp_servers;
         self.plugins = plugins;
+        (self.mcp_loaded, self.plugins_loaded) = (true, true);
 
-        // Rebuild tree views with new components
+        // Rebuild tree views and dependency graph with new components
         self.tree_views = build_tree_views(&self.components);
+        self.dep_graph = DependencyGraph::build(&self.components);
+
+        // Installed settings may have changed
+        self.load_permissions();
+        self.load_env_vars();
+
+        // Refresh browse-mode status as well
+        if self.plugin_browse
+            && let Ok(browse) = fs::scanner::scan_marketplace_plugins(&self.source_layers, &self.dest_dir)
+        {
+            self.browse_plugins = browse;
+            self.browse_index = self.browse_index.min(self.browse_plugins.len().saturating_sub(1));
+        }
 
         let verb = if self.is_removing { "Removed" } else { "Installed" };
-        self.status_message = Some(format!("{} {} items", verb, self.processing_total.unwrap_or(0)));
+        let failed = self.count_items(ItemState::Failed);
+        self.status_message = Some(if let Some(batch) = &self.purge_batch {
+            format!(
+                "Purged {} items ({} errors) · previous state saved as {}",
+                self.count_items(ItemState::Done),
+                failed,
+                batch.safety
+            )
+        } else if failed > 0 {
+            format!("{} {} items, {} failed", verb, self.count_items(ItemState::Done), failed)
+        } else {
+            format!("{} {} items", verb, self.count_items(ItemState::Done))
+        });
         self.processing_log.push("[OK] Status refresh complete!".to_string());
         self.needs_refresh = false;
         self.refreshing = false;
         self.processing_complete = true;
     }
 
+    /// Swap in data rescanned after changes on disk (see watch.rs), keeping selections,
+    /// expanded folders and cursor positions
+    pub fn apply_live_refresh(&mut self, components: Vec<Component>, mcp_servers: Vec<McpServer>, plugins: Vec<Plugin>) {
+        let tree_states: HashMap<Tab, TreeState> = self
+            .tree_views
+            .iter()
+            .map(|(tab, tree)| (*tab, tree.save_state(&self.components)))
+            .collect();
+
+        // Items seen before keep their selection; new ones get the scanner's default
+        let key = |c: &Component| format!("{:?}/{}", c.component_type, c.name);
+        let previous: HashMap<String, bool> = self.components.iter().map(|c| (key(c), c.selected)).collect();
+        self.components = components;
+        for c in &mut self.components {
+            if let Some(&selected) = previous.get(&key(c)) {
+                c.selected = selected;
+            }
+        }
+
+        let previous: HashMap<String, bool> = self.mcp_servers.iter().map(|m| (m.def.name.clone(), m.selected)).collect();
+        self.mcp_servers = mcp_servers;
+        for m in &mut self.mcp_servers {
+            if let Some(&selected) = previous.get(&m.def.name) {
+                m.selected = selected;
+            }
+        }
+        self.mcp_index = self.mcp_index.min(self.mcp_servers.len().saturating_sub(1));
+
+        let previous: HashMap<String, bool> = self.plugins.iter().map(|p| (p.def.id(), p.selected)).collect();
+        self.plugins = plugins;
+        for p in &mut self.plugins {
+            if let Some(&selected) = previous.get(&p.def.id()) {
+                p.selected = selected;
+            }
+        }
+        self.plugin_index = self.plugin_index.min(self.plugins.len().saturating_sub(1));
+
+        self.tree_views = build_tree_views(&self.components);
+        for (tab, tree) in self.tree_views.iter_mut() {
+            if let Some(state) = tree_states.get(tab) {
+                tree.restore_state(state, &self.components);
+            }
+        }
+        self.dep_graph = DependencyGraph::build(&self.components);
+
+        let (current_output_style, current_statusline) = read_current_settings(&self.dest_dir);
+        self.current_output_style = current_output_style;
+        self.current_statusline = current_statusline;
+
+        let selected: Vec<(RuleKind, String)> = self
+            .permissions
+            .iter()
+            .filter(|r| r.selected)
+            .map(|r| (r.def.kind, r.def.rule.clone()))
+            .collect();
+        self.load_permissions();
+        for r in &mut self.permissions {
+            r.selected = selected.iter().any(|(kind, rule)| *kind == r.def.kind && *rule == r.def.rule);
+        }
+        self.load_env_vars();
+    }
+
     pub fn close_processing(&mut self) {
         self.current_view = View::List;
+        self.purge_batch = None;
         self.processing_queue.clear();
+        self.processing_items.clear();
+        self.processing_cursor = 0;
+        self.journal = None;
         self.processing_progress = None;
         self.processing_total = None;
         self.processing_log.clear();
@@ -711,12 +1848,506 @@ impl App {
         self.processing_complete = false;
     }
 
+    pub fn open_history(&mut self) {
+        self.history = crate::history::list_batches(&self.dest_dir);
+        self.history_index = 0;
+        self.current_view = View::History;
+    }
+
+    pub fn close_history(&mut self) {
+        self.current_view = View::List;
+    }
+
+    pub fn next_history(&mut self) {
+        if self.history_index + 1 < self.history.len() {
+            self.history_index += 1;
+        }
+    }
+
+    pub fn prev_history(&mut self) {
+        self.history_index = self.history_index.saturating_sub(1);
+    }
+
+    /// Undo the highlighted batch (only the most recent batch not yet undone)
+    pub fn undo_selected_batch(&mut self) -> Result<()> {
+        let Some(batch) = self.history.get(self.history_index).cloned() else {
+            return Ok(());
+        };
+        if batch.undone {
+            self.status_message = Some("Batch already undone".to_string());
+            return Ok(());
+        }
+        if self.history.iter().position(|b| !b.undone) != Some(self.history_index) {
+            self.status_message = Some("Only the most recent batch can be undone".to_string());
+            return Ok(());
+        }
+
+        let mut log = crate::history::undo(&self.dest_dir, &batch)?;
+        let reinstall = batch.action == "remove";
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+
+        // MCP servers and plugins live in CLI-managed config: run the inverse command
+        let mcp_undo = batch.mcp_undo();
+        for item in batch.items.iter().filter(|i| i.ok) {
+            let result = match item.kind {
+                ItemKind::Component => continue,
+                ItemKind::Mcp if mcp_undo.is_some() => continue,
+                // Journals without registrations: best effort at user scope
+                ItemKind::Mcp => match self.mcp_servers.iter().find(|m| m.def.name == item.name) {
+                    Some(server) if reinstall => {
+                        let env = server.env_from_environment();
+                        fs::installer::install_mcp_server(server, McpScope::User, None, &env, target_cli).map(|_| ())
+                    }
+                    Some(server) => fs::installer::remove_mcp_server(server, target_cli),
+                    None => Err(anyhow::anyhow!("not in catalog")),
+                },
+                ItemKind::Plugin => match self.plugins.iter().chain(&self.browse_plugins).find(|p| p.def.id() == item.name) {
+                    Some(plugin) if reinstall => fs::installer::install_plugin(plugin).map(|_| ()),
+                    Some(plugin) => fs::installer::remove_plugin(plugin),
+                    None => Err(anyhow::anyhow!("not in catalog")),
+                },
+            };
+            log.push(match result {
+                Ok(()) => format!("[OK] Reverted {}", item.name),
+                Err(e) => format!("[ERR] {}: {}", item.name, e),
+            });
+        }
+
+        // Replay the journaled registrations: drop what the batch added, re-add what it removed.
+        // The journal keeps env var names only: values come from the server's registrations
+        // before the replay, else from the environment.
+        if let Some((remove, restore)) = mcp_undo {
+            let live = self.mcp_registrations();
+            let steps = remove.iter().map(|r| (r, false)).chain(restore.iter().map(|r| (r, true)));
+            for (registration, re_add) in steps {
+                let project = registration.project.as_deref();
+                let result = match self.mcp_servers.iter().find(|m| m.def.name == registration.name) {
+                    Some(server) if re_add => {
+                        let mut env = Vec::new();
+                        let mut missing = Vec::new();
+                        for (key, _) in &registration.env {
+                            let value = live.iter()
+                                .filter(|r| r.name == registration.name)
+                                .find_map(|r| r.env.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()))
+                                .or_else(|| std::env::var(key).ok());
+                            match value {
+                                Some(value) => env.push((key.clone(), value)),
+                                None => missing.push(key.as_str()),
+                            }
+                        }
+                        if !missing.is_empty() {
+                            log.push(format!("[SKIP] {}: set {} to re-add it", registration.name, missing.join(", ")));
+                            continue;
+                        }
+                        fs::installer::install_mcp_server(server, registration.scope, project, &env, target_cli).map(|_| ())
+                    }
+                    Some(server) if target_cli == TargetCli::Claude => {
+                        fs::installer::remove_mcp_server_from(server, registration.scope, project)
+                    }
+                    Some(server) => fs::installer::remove_mcp_server(server, target_cli),
+                    None => Err(anyhow::anyhow!("not in catalog")),
+                };
+                log.push(match result {
+                    Ok(()) => format!("[OK] Reverted {} ({} scope)", registration.name, registration.scope.display()),
+                    Err(e) => format!("[ERR] {}: {}", registration.name, e),
+                });
+            }
+        }
+
+        self.rescan()?;
+        self.history = crate::history::list_batches(&self.dest_dir);
+
+        let errors = log.iter().filter(|l| l.starts_with("[ERR]")).count();
+        self.status_message = Some(if errors > 0 {
+            format!("Undid batch with {} errors: {}", errors, log.iter().find(|l| l.starts_with("[ERR]")).cloned().unwrap_or_default())
+        } else {
+            format!("Undid {} of {}", batch.action, crate::history::format_timestamp(batch.timestamp))
+        });
+        Ok(())
+    }
+
+    pub fn open_snapshots(&mut self) {
+        self.snapshots = crate::snapshot::list(&self.dest_dir);
+        self.snapshot_index = 0;
+        self.snapshot_log.clear();
+        self.pending_restore = None;
+        self.current_view = View::Snapshots;
+    }
+
+    pub fn close_snapshots(&mut self) {
+        self.pending_restore = None;
+        self.current_view = View::List;
+    }
+
+    pub fn next_snapshot(&mut self) {
+        if self.snapshot_index + 1 < self.snapshots.len() {
+            self.snapshot_index += 1;
+        }
+        self.snapshot_log.clear();
+        self.pending_restore = None;
+    }
+
+    pub fn prev_snapshot(&mut self) {
+        self.snapshot_index = self.snapshot_index.saturating_sub(1);
+        self.snapshot_log.clear();
+        self.pending_restore = None;
+    }
+
+    pub fn create_snapshot(&mut self) -> Result<()> {
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+        let snapshot = crate::snapshot::create(&self.dest_dir, &self.source_layers, target_cli, None)?;
+
+        self.status_message = Some(format!("Snapshot {} ({} files)", snapshot.id, snapshot.files.len()));
+        self.snapshots = crate::snapshot::list(&self.dest_dir);
+        self.snapshot_index = 0;
+        Ok(())
+    }
+
+    /// Restore the highlighted snapshot (press twice). A safety snapshot is taken first.
+    pub fn restore_selected_snapshot(&mut self) -> Result<()> {
+        let Some(snapshot) = self.snapshots.get(self.snapshot_index).cloned() else {
+            return Ok(());
+        };
+        if self.pending_restore.as_deref() != Some(snapshot.id.as_str()) {
+            self.status_message = Some(format!(
+                "Restore {} replaces managed files · press r again to confirm",
+                crate::history::format_timestamp(snapshot.timestamp)
+            ));
+            self.pending_restore = Some(snapshot.id);
+            return Ok(());
+        }
+        self.pending_restore = None;
+
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+        let safety = crate::snapshot::create(&self.dest_dir, &self.source_layers, target_cli, Some("pre-restore"))?;
+
+        let plugins: Vec<Plugin> = self.plugins.iter().chain(&self.browse_plugins).cloned().collect();
+        self.snapshot_log = crate::snapshot::restore(
+            &self.dest_dir,
+            &snapshot,
+            target_cli,
+            &self.mcp_servers,
+            &plugins,
+        )?;
+
+        self.rescan()?;
+        self.snapshots = crate::snapshot::list(&self.dest_dir);
+        self.snapshot_index = self.snapshots.iter().position(|s| s.id == snapshot.id).unwrap_or(0);
+
+        let errors = self.snapshot_log.iter().filter(|l| l.starts_with("[ERR]")).count();
+        self.status_message = Some(format!(
+            "Restored {} ({} errors) · previous state saved as {}",
+            snapshot.id, errors, safety.id
+        ));
+        Ok(())
+    }
+
+    /// Run the checks and show them. MCP and plugin status is loaded first if the tabs have
+    /// not been shown yet, so the PATH checks see which servers are installed.
+    pub fn open_doctor(&mut self) -> Result<()> {
+        if !self.mcp_loaded || !self.plugins_loaded {
+            self.rescan()?;
+        }
+        self.doctor_log.clear();
+        self.run_doctor();
+        self.doctor_index = 0;
+        self.current_view = View::Doctor;
+        Ok(())
+    }
+
+    pub fn close_doctor(&mut self) {
+        self.current_view = View::List;
+    }
+
+    pub fn next_finding(&mut self) {
+        if self.doctor_index + 1 < self.doctor_findings.len() {
+            self.doctor_index += 1;
+        }
+    }
+
+    pub fn prev_finding(&mut self) {
+        self.doctor_index = self.doctor_index.saturating_sub(1);
+    }
+
+    fn run_doctor(&mut self) {
+        let plugins: Vec<Plugin> = self.plugins.iter().chain(&self.browse_plugins).cloned().collect();
+        self.doctor_findings = crate::doctor::check(&self.dest_dir, &self.components, &self.mcp_servers, &plugins);
+        self.doctor_index = self.doctor_index.min(self.doctor_findings.len().saturating_sub(1));
+    }
+
+    /// Apply the fix of the highlighted finding (`all` = every finding with a fix), then re-check
+    pub fn fix_findings(&mut self, all: bool) -> Result<()> {
+        let fixes: Vec<crate::doctor::Fix> = if all {
+            self.doctor_findings.iter().filter_map(|f| f.fix.clone()).collect()
+        } else {
+            self.doctor_findings.get(self.doctor_index).and_then(|f| f.fix.clone()).into_iter().collect()
+        };
+        if fixes.is_empty() {
+            self.status_message = Some("No fix available".to_string());
+            return Ok(());
+        }
+
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+        for fix in &fixes {
+            let result = crate::doctor::apply(fix, &self.source_dir, &self.dest_dir, &self.components, &self.mcp_servers, target_cli);
+            self.doctor_log.push(match result {
+                Ok(line) => line,
+                Err(e) => format!("[ERR] {}: {}", fix.describe(&self.components, &self.mcp_servers), e),
+            });
+        }
+
+        self.rescan()?;
+        self.run_doctor();
+        self.status_message = Some(format!("Applied {} fixes · {} problems left", fixes.len(), self.doctor_findings.len()));
+        Ok(())
+    }
+
+    /// Show what a purge would take out. MCP and plugin status is loaded first if the tabs
+    /// have not been shown yet.
+    pub fn open_purge(&mut self) -> Result<()> {
+        if !self.mcp_loaded || !self.plugins_loaded {
+            self.rescan()?;
+        }
+        let registrations = self.mcp_registrations();
+        let (output_style, statusline) = read_current_settings(&self.dest_dir);
+        let inputs = crate::purge::Inputs {
+            components: &self.components,
+            mcp_servers: &self.mcp_servers,
+            registrations: &registrations,
+            plugins: &self.plugins,
+            output_style: output_style.as_deref(),
+            statusline: statusline.as_deref(),
+        };
+        self.purge_plan = Some(crate::purge::plan(&inputs));
+        self.purge_index = 0;
+        self.current_view = View::Purge;
+        Ok(())
+    }
+
+    pub fn close_purge(&mut self) {
+        self.purge_plan = None;
+        self.current_view = View::List;
+    }
+
+    /// Rows of the purge list: steps, then the components that are kept
+    pub fn purge_rows(&self) -> usize {
+        self.purge_plan.as_ref().map_or(0, |plan| plan.steps.len() + plan.kept.len())
+    }
+
+    pub fn next_purge_item(&mut self) {
+        if self.purge_index + 1 < self.purge_rows() {
+            self.purge_index += 1;
+        }
+    }
+
+    pub fn prev_purge_item(&mut self) {
+        self.purge_index = self.purge_index.saturating_sub(1);
+    }
+
+    /// Run the plan shown in the Purge view on the workers of the Installing view. A safety
+    /// snapshot is taken first.
+    pub fn confirm_purge(&mut self) -> Result<()> {
+        let Some(plan) = self.purge_plan.take() else {
+            return Ok(());
+        };
+        if plan.is_empty() {
+            self.close_purge();
+            return Ok(());
+        }
+
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+        let safety = crate::snapshot::create(&self.dest_dir, &self.source_layers, target_cli, Some("pre-purge"))?;
+
+        self.processing_queue = (0..plan.steps.len()).collect();
+        self.purge_batch = Some(std::sync::Arc::new(crate::purge::Batch {
+            plan,
+            components: self.components.clone(),
+            mcp_servers: self.mcp_servers.clone(),
+            plugins: self.plugins.clone(),
+            dest_dir: self.dest_dir.clone(),
+            target_cli,
+            safety: safety.id,
+        }));
+        self.begin_processing(true);
+        Ok(())
+    }
+
+    /// Scopes the MCP servers are registered in (~/.claude.json, or Codex's config.toml)
+    pub fn mcp_registrations(&self) -> Vec<fs::scanner::McpRegistration> {
+        fs::scanner::mcp_registrations(self.target_cli.unwrap_or(TargetCli::Claude), &self.dest_dir)
+    }
+
+    pub fn open_profiles(&mut self) {
+        self.profiles = crate::profile::list(&self.source_dir);
+        self.profile_index = 0;
+        self.profile_name_input = None;
+        self.current_view = View::Profiles;
+    }
+
+    pub fn close_profiles(&mut self) {
+        self.profile_name_input = None;
+        self.current_view = View::List;
+    }
+
+    pub fn next_profile(&mut self) {
+        if self.profile_index + 1 < self.profiles.len() {
+            self.profile_index += 1;
+        }
+    }
+
+    pub fn prev_profile(&mut self) {
+        self.profile_index = self.profile_index.saturating_sub(1);
+    }
+
+    /// Replace the current selection with the highlighted profile
+    pub fn load_selected_profile(&mut self) {
+        let Some(entry) = self.profiles.get(self.profile_index) else {
+            return;
+        };
+        let profile = match &entry.result {
+            Ok(profile) => profile.clone(),
+            Err(e) => {
+                self.status_message = Some(format!("Cannot load {}: {}", entry.name, e));
+                return;
+            }
+        };
+        let name = entry.name.clone();
+
+        let wanted = crate::profile::wanted_components(&profile, &self.components, &self.dep_graph);
+        for (idx, c) in self.components.iter_mut().enumerate() {
+            c.selected = wanted.contains(&idx);
+        }
+
+        for m in &mut self.mcp_servers {
+            m.selected = profile.mcp_entry(&m.def.name).is_some();
+        }
+        // The TUI has a single scope: take it from the first local entry, if any
+        if let Some(local) = profile.mcp_servers.iter().find(|m| m.scope() == McpScope::Local) {
+            self.mcp_scope = McpScope::Local;
+            if let Some(project) = local.project() {
+                self.mcp_project_path = project.to_string();
+            }
+        } else {
+            self.mcp_scope = McpScope::User;
+        }
+
+        for p in self.plugins.iter_mut().chain(self.browse_plugins.iter_mut()) {
+            p.selected = profile.wants_plugin(p);
+        }
+
+        self.status_message = Some(format!(
+            "Loaded profile {}: {} components, {} MCP servers, {} plugins",
+            name,
+            wanted.len(),
+            self.mcp_servers.iter().filter(|m| m.selected).count(),
+            self.plugins.iter().filter(|p| p.selected).count()
+        ));
+        self.current_view = View::List;
+    }
+
+    pub fn start_profile_save(&mut self) {
+        self.profile_name_input = Some(String::new());
+    }
+
+    pub fn profile_input_char(&mut self, c: char) {
+        if let Some(input) = &mut self.profile_name_input
+            && (c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            input.push(c);
+        }
+    }
+
+    pub fn profile_input_backspace(&mut self) {
+        if let Some(input) = &mut self.profile_name_input {
+            input.pop();
+        }
+    }
+
+    pub fn profile_input_cancel(&mut self) {
+        self.profile_name_input = None;
+    }
+
+    /// Save the current selection (all tabs) under the typed name
+    pub fn save_profile(&mut self) -> Result<()> {
+        let Some(name) = self.profile_name_input.take().filter(|n| !n.is_empty()) else {
+            return Ok(());
+        };
+
+        let profile = Profile::from_selection(
+            &self.components,
+            &self.mcp_servers,
+            self.mcp_scope,
+            &self.mcp_project_path,
+            &self.plugins,
+        );
+        let path = crate::profile::save(&self.source_dir, &name, &profile)?;
+
+        self.profiles = crate::profile::list(&self.source_dir);
+        self.profile_index = self.profiles.iter().position(|p| p.name == name).unwrap_or(0);
+        self.status_message = Some(format!("Saved {}", path.display()));
+        Ok(())
+    }
+
+    /// Display name of a source layer
+    pub fn layer_name(&self, layer: usize) -> String {
+        crate::config::layer_name(&self.layers, layer)
+    }
+
+    /// Git-backed layers that are behind upstream or could not be fetched
+    fn remote_notice(&self) -> Option<String> {
+        let notes: Vec<String> = self
+            .layers
+            .iter()
+            .filter_map(|l| l.remote.as_ref().map(|r| (l, r)))
+            .filter_map(|(l, r)| match (&r.error, r.fetched, r.behind) {
+                (Some(_), _, _) => Some(format!("{} offline", l.name)),
+                (None, false, _) | (None, true, 0) => None,
+                (None, true, n) => Some(format!("{} has {} new commits (installer layers update)", l.name, n)),
+            })
+            .collect();
+        (!notes.is_empty()).then(|| notes.join(", "))
+    }
+
+    /// Detail pane contents of a component, read from disk again only when the scan saw
+    /// different content (the pane is redrawn on every tick)
+    pub fn component_detail(&self, idx: usize) -> ComponentDetail {
+        let component = &self.components[idx];
+        let Some(hash) = component.source_hash else {
+            return ComponentDetail::load(component);
+        };
+        let mut cache = self.detail_cache.borrow_mut();
+        match &*cache {
+            Some((cached_idx, cached_hash, detail)) if *cached_idx == idx && *cached_hash == hash => detail.clone(),
+            _ => {
+                let detail = ComponentDetail::load(component);
+                *cache = Some((idx, hash, detail.clone()));
+                detail
+            }
+        }
+    }
+
+    /// Rescan everything synchronously (outside the Installing view)
+    fn rescan(&mut self) -> Result<()> {
+        let target_cli = self.target_cli.unwrap_or(TargetCli::Claude);
+        self.components = fs::scanner::scan_components(&self.source_layers, &self.dest_dir, target_cli, self.scan_cache.as_deref())?;
+        self.mcp_servers = fs::scanner::scan_mcp_servers(&self.source_layers, target_cli, &self.dest_dir)?;
+        self.plugins = fs::scanner::scan_plugins(&self.source_layers)?;
+        (self.mcp_loaded, self.plugins_loaded) = (true, true);
+        self.tree_views = build_tree_views(&self.components);
+        self.dep_graph = DependencyGraph::build(&self.components);
+        Ok(())
+    }
+
     pub fn tick(&mut self) {
         // Update animation frame for spinner
         self.animation_frame = (self.animation_frame + 1) % 10;
     }
 
     pub fn remove_selected(&mut self) -> Result<()> {
+        if self.tab == Tab::Permissions {
+            return self.apply_permissions(true);
+        }
+
         // Build remove queue
         let indices: Vec<usize> = if self.tab == Tab::McpServers {
             self.mcp_servers
@@ -726,7 +2357,7 @@ impl App {
                 .map(|(i, _)| i)
                 .collect()
         } else if self.tab == Tab::Plugins {
-            self.plugins
+            self.active_plugins()
                 .iter()
                 .enumerate()
                 .filter(|(_, p)| p.selected)
@@ -748,18 +2379,39 @@ impl App {
             return Ok(());
         }
 
+        // Warn once when installed components outside the queue depend on it
+        if self.tab.to_component_type().is_some() && self.pending_removal.as_ref() != Some(&indices) {
+            let dependents = self.installed_dependents(&indices);
+            if !dependents.is_empty() {
+                self.status_message = Some(format!(
+                    "Required by {} · press r again to remove anyway",
+                    dependents.join(", ")
+                ));
+                self.pending_removal = Some(indices);
+                return Ok(());
+            }
+        }
+        self.pending_removal = None;
+
         // Initialize remove state
         self.processing_queue = indices;
-        self.processing_total = Some(self.processing_queue.len());
-        self.processing_progress = Some(0);
-        self.processing_log.clear();
-        self.processing_log.push(format!("Starting removal of {} items...", self.processing_queue.len()));
-        self.is_removing = true;
-        self.current_view = View::Installing;
+        self.begin_processing(true);
 
         Ok(())
     }
 
+    /// Installed components (not in `indices`) that require any of `indices`
+    fn installed_dependents(&self, indices: &[usize]) -> Vec<String> {
+        let mut dependents: Vec<usize> = indices
+            .iter()
+            .flat_map(|&idx| self.dep_graph.required_by(idx).iter().copied())
+            .filter(|i| !indices.contains(i) && self.components[*i].status != InstallStatus::New)
+            .collect();
+        dependents.sort();
+        dependents.dedup();
+        dependents.iter().map(|&i| self.components[i].display_name()).collect()
+    }
+
     pub fn set_default_style(&mut self) -> Result<()> {
         // Only works for OutputStyles tab
         if self.tab != Tab::OutputStyles {
@@ -827,7 +2479,7 @@ impl App {
     }
 }
 
-fn read_current_settings(dest_dir: &Path) -> (Option<String>, Option<String>) {
+pub fn read_current_settings(dest_dir: &Path) -> (Option<String>, Option<String>) {
     use serde_json::Value;
 
     let settings_path = dest_dir.join("settings.json");
@@ -861,7 +2513,7 @@ fn read_current_settings(dest_dir: &Path) -> (Option<String>, Option<String>) {
     (output_style, statusline)
 }
 
-fn find_source_dir() -> Result<PathBuf> {
+pub fn find_source_dir() -> Result<PathBuf> {
     // Try to find source dir relative to executable
     let exe_dir = std::env::current_exe()?
         .parent()
@@ -892,6 +2544,11 @@ fn find_source_dir() -> Result<PathBuf> {
     anyhow::bail!("Cannot find source directory. Run from dotfiles root or config/ai/claude/tools/installer")
 }
 
+/// Base source followed by the overlays from the installer config (git layers are synced)
+pub fn find_source_layers() -> Result<Vec<SourceLayer>> {
+    crate::config::source_layers(&crate::config::load()?, find_source_dir, true)
+}
+
 fn build_tree_views(components: &[Component]) -> HashMap<Tab, TreeView> {
     let mut tree_views = HashMap::new();
 
@@ -921,3 +2578,59 @@ fn build_tree_views(components: &[Component]) -> HashMap<Tab, TreeView> {
 
     tree_views
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// App on the Agents tab with `a` requiring `b`, both queued in that order
+    fn batch(removing: bool) -> App {
+        let layers = vec![SourceLayer { name: "base".to_string(), path: PathBuf::from("source"), remote: None }];
+        let mut app = App::with_layers(layers, PathBuf::from("dest"), Theme::bundled("mocha"), Keymap::default());
+        let agent = |name: &str| Component::new(ComponentType::Agents, name.into(), name.into(), name.into(), InstallStatus::New);
+        app.components = vec![agent("a.md"), agent("b.md")];
+        app.components[0].requires = vec!["agents/b".to_string()];
+        app.dep_graph = DependencyGraph::build(&app.components);
+        app.tab = Tab::Agents;
+        app.processing_queue = vec![0, 1];
+        app.begin_processing(removing);
+        app
+    }
+
+    #[test]
+    fn test_items_wait_for_dependencies() {
+        let mut app = batch(false);
+        assert_eq!(app.next_pending_item(), Some(1)); // b.md first
+        assert_eq!(app.next_pending_item(), None);
+        app.finish_process_item(1, "[OK] b.md".into(), Duration::ZERO);
+        assert_eq!(app.next_pending_item(), Some(0));
+
+        // Removal goes the other way: the dependent first
+        let mut app = batch(true);
+        assert_eq!(app.next_pending_item(), Some(0));
+        assert_eq!(app.next_pending_item(), None);
+    }
+
+    #[test]
+    fn test_cancel_stops_running_items_and_retry_requeues_failed() {
+        let mut app = batch(false);
+        let running = app.next_pending_item().unwrap();
+        app.cancel_processing();
+        assert!(app.processing_items[running].cancel.is_cancelled());
+        assert_eq!(app.processing_items[0].state, ItemState::Cancelled);
+
+        // The worker comes back with the killed process' error
+        app.finish_process_item(running, "[ERR] b.md: Cancelled".into(), Duration::ZERO);
+        assert_eq!(app.processing_items[running].state, ItemState::Cancelled);
+        assert!(app.all_items_finished());
+
+        let mut app = batch(false);
+        let slot = app.next_pending_item().unwrap();
+        app.finish_process_item(slot, "[ERR] b.md: exit 1".into(), Duration::ZERO);
+        assert_eq!(app.processing_items[slot].state, ItemState::Failed);
+        app.retry_failed();
+        assert_eq!(app.processing_items[slot].state, ItemState::Pending);
+        assert_eq!(app.next_pending_item(), Some(slot));
+        assert!(!app.processing_items[slot].cancel.is_cancelled());
+    }
+}
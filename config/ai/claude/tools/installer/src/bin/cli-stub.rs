@@ -0,0 +1,60 @@
+//! Stand-in for the `claude` / `codex` executables in tests
+//!
+//! Point `INSTALLER_CLAUDE_BIN` or `INSTALLER_CODEX_BIN` at this binary and set
+//! `CLI_STUB_DIR`. Every call appends its argv as a JSON array to `calls.jsonl` in that
+//! directory. Output comes from `responses.json` there, a list of
+//!
+//! ```json
+//! [{ "args": "mcp add", "stdout": "", "stderr": "boom", "code": 1 }]
+//! ```
+//!
+//! The entry whose `args` is the longest prefix of the call wins; without a match the stub
+//! prints nothing and exits 0.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Response {
+    args: String,
+    #[serde(default)]
+    stdout: String,
+    #[serde(default)]
+    stderr: String,
+    #[serde(default)]
+    code: i32,
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some(dir) = std::env::var_os("CLI_STUB_DIR").map(PathBuf::from) else {
+        eprintln!("cli-stub: CLI_STUB_DIR is not set");
+        std::process::exit(2);
+    };
+
+    let mut calls = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("calls.jsonl"))
+        .expect("cli-stub: cannot open calls.jsonl");
+    writeln!(calls, "{}", serde_json::to_string(&args).unwrap()).expect("cli-stub: cannot record call");
+
+    let responses: Vec<Response> = std::fs::read_to_string(dir.join("responses.json"))
+        .ok()
+        .map(|text| serde_json::from_str(&text).expect("cli-stub: invalid responses.json"))
+        .unwrap_or_default();
+
+    let call = args.join(" ");
+    let matched = responses
+        .iter()
+        .filter(|r| call == r.args || call.starts_with(&format!("{} ", r.args)))
+        .max_by_key(|r| r.args.len());
+
+    if let Some(response) = matched {
+        print!("{}", response.stdout);
+        eprint!("{}", response.stderr);
+        std::process::exit(response.code);
+    }
+}
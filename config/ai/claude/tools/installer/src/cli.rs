@@ -0,0 +1,469 @@
+use anyhow::Result;
+
+use crate::app::{find_source_layers, TargetCli};
+use crate::fs;
+use crate::history::format_timestamp;
+use crate::deps::DependencyGraph;
+use crate::doctor;
+use crate::lint;
+use crate::profile;
+use crate::purge;
+use crate::report::Report;
+use crate::snapshot;
+
+const USAGE: &str = "Usage: installer [command]
+
+Commands:
+  (none)              Start the interactive installer
+  lint                Validate source components and report errors/warnings
+  doctor [--fix]      Check settings.json references (hooks, statusline, output style,
+                      MCP executables, plugin marketplaces); --fix applies every fix
+  status [--json] [--check] [--target claude|codex]
+                      Report component, MCP, plugin and settings.json drift;
+                      --check exits 1 when anything installed differs from the source
+  layers              List source layers (base first, later layers override)
+  layers update       Move git-backed layers to their latest upstream commit
+  snapshot [label]    Snapshot the managed ~/.claude state
+  snapshots           List snapshots
+  restore <id>        Restore a snapshot (the current state is snapshotted first)
+  purge [--dry-run] [--yes] [--target claude|codex]
+                      Remove everything the installer manages (asks first;
+                      locally edited files are kept)
+  profile list        List selection profiles
+  profile apply <name> [--dry-run] [--target claude|codex]
+                      Converge ~/.claude (or ~/.codex) to a profile (installs and removes)";
+
+/// Run a headless subcommand. Returns the process exit code.
+pub fn run(args: &[String]) -> Result<i32> {
+    match args[0].as_str() {
+        "lint" => run_lint(),
+        "doctor" => run_doctor(args[1..].iter().any(|a| a == "--fix")),
+        "status" => run_status(
+            args[1..].iter().any(|a| a == "--json"),
+            args[1..].iter().any(|a| a == "--check"),
+            target_arg(&args[1..])?,
+        ),
+        "layers" => match args.get(1).map(|s| s.as_str()) {
+            None => run_layers(),
+            Some("update") => run_layers_update(),
+            Some(_) => {
+                eprintln!("Usage: installer layers [update]");
+                Ok(2)
+            }
+        },
+        "snapshot" => run_snapshot(args.get(1).map(|s| s.as_str())),
+        "snapshots" => run_snapshots(),
+        "purge" => run_purge(
+            args[1..].iter().any(|a| a == "--dry-run"),
+            args[1..].iter().any(|a| a == "--yes"),
+            target_arg(&args[1..])?,
+        ),
+        "profile" => match (args.get(1).map(|s| s.as_str()), args.get(2)) {
+            (Some("list"), _) => run_profile_list(),
+            (Some("apply"), Some(name)) => {
+                run_profile_apply(name, args[3..].iter().any(|a| a == "--dry-run"), target_arg(&args[3..])?)
+            }
+            _ => {
+                eprintln!("Usage: installer profile list | apply <name> [--dry-run] [--target claude|codex]");
+                Ok(2)
+            }
+        },
+        "restore" => match args.get(1) {
+            Some(id) => run_restore(id),
+            None => {
+                eprintln!("Usage: installer restore <id>");
+                Ok(2)
+            }
+        },
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        other => {
+            eprintln!("Unknown command: {}\n\n{}", other, USAGE);
+            Ok(2)
+        }
+    }
+}
+
+/// `--target claude|codex` (Claude when absent)
+fn target_arg(args: &[String]) -> Result<TargetCli> {
+    let Some(pos) = args.iter().position(|a| a == "--target") else {
+        return Ok(TargetCli::Claude);
+    };
+    match args.get(pos + 1).map(|s| s.as_str()) {
+        Some("claude") => Ok(TargetCli::Claude),
+        Some("codex") => Ok(TargetCli::Codex),
+        other => anyhow::bail!("--target takes claude or codex, got {}", other.unwrap_or("nothing")),
+    }
+}
+
+/// `installer lint`: exit code 1 when any component has errors
+fn run_lint() -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = TargetCli::Claude.get_dest_dir()?;
+    let components = fs::scanner::scan_components(&sources, &dest_dir, TargetCli::Claude, fs::cache::cache_path().as_deref())?;
+
+    for c in components.iter().filter(|c| !c.issues.is_empty()) {
+        println!("{}", c.display_name());
+        let mut issues = c.issues.clone();
+        issues.sort_by_key(|i| std::cmp::Reverse(i.level));
+        for issue in issues {
+            println!("  {}: {}", issue.level.display(), issue.message);
+        }
+    }
+
+    let (errors, warnings) = lint::summarize(&components);
+    let affected = components.iter().filter(|c| !c.issues.is_empty()).count();
+    println!(
+        "\n{} errors, {} warnings in {} of {} components",
+        errors,
+        warnings,
+        affected,
+        components.len()
+    );
+
+    Ok(if errors > 0 { 1 } else { 0 })
+}
+
+/// `installer doctor [--fix]`: exit code 1 when errors remain
+fn run_doctor(fix: bool) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = TargetCli::Claude.get_dest_dir()?;
+    let target = TargetCli::Claude;
+
+    let scan = || -> Result<_> {
+        let components = fs::scanner::scan_components(&sources, &dest_dir, target, fs::cache::cache_path().as_deref())?;
+        let mcp_servers = fs::scanner::scan_mcp_servers(&sources, target, &dest_dir)?;
+        let plugins = fs::scanner::scan_plugins(&sources)?;
+        let findings = doctor::check(&dest_dir, &components, &mcp_servers, &plugins);
+        Ok((components, mcp_servers, findings))
+    };
+
+    let (components, mcp_servers, mut findings) = scan()?;
+    if fix {
+        for f in findings.iter().filter_map(|f| f.fix.as_ref()) {
+            match doctor::apply(f, &sources[0], &dest_dir, &components, &mcp_servers, target) {
+                Ok(line) => println!("{}", line),
+                Err(e) => println!("[ERR] {}: {}", f.describe(&components, &mcp_servers), e),
+            }
+        }
+        findings = scan()?.2;
+    }
+
+    for finding in &findings {
+        println!("{:<8} {:<14} {}", finding.level.display(), finding.area, finding.message);
+        if let Some(f) = &finding.fix {
+            println!("{:<8} {:<14} fix: {}", "", "", f.describe(&components, &mcp_servers));
+        }
+    }
+
+    let errors = findings.iter().filter(|f| f.level == lint::LintLevel::Error).count();
+    println!("\n{} errors, {} warnings", errors, findings.len() - errors);
+    Ok(if errors > 0 { 1 } else { 0 })
+}
+
+/// `installer status [--json] [--check] [--target ..]`: with --check, exit code 1 when
+/// anything installed differs
+fn run_status(json: bool, check: bool, target: TargetCli) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = target.get_dest_dir()?;
+
+    let components = fs::scanner::scan_components(&sources, &dest_dir, target, fs::cache::cache_path().as_deref())?;
+    let mcp_servers = fs::scanner::scan_mcp_servers(&sources, target, &dest_dir)?;
+    // Codex has no plugins
+    let plugins = match target {
+        TargetCli::Claude => fs::scanner::scan_plugins(&sources)?,
+        TargetCli::Codex => Vec::new(),
+    };
+    // The scan just refreshed the cache, so hashing reads nothing new
+    let cache = fs::cache::ScanCache::load(fs::cache::cache_path());
+    let report = Report::build(&components, &mcp_servers, &plugins, &dest_dir, target, &cache)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for c in report.components.iter().filter(|c| c.status != "unchanged") {
+            println!("{:<10} {}/{}", c.status, c.component_type, c.name);
+        }
+        for m in &report.mcp_servers {
+            let status = if m.drift { "drift" } else { m.status };
+            println!("{:<10} mcp {}{}", status, m.name, m.scope.map(|s| format!(" ({})", s)).unwrap_or_default());
+        }
+        for p in &report.plugins {
+            println!("{:<10} plugin {}", if p.drift { "drift" } else { p.status }, p.id);
+        }
+        for d in &report.settings_drift {
+            let local = d.local.as_ref().map(|v| v.to_string()).unwrap_or_else(|| "missing".to_string());
+            let kept = if d.kept_local { "  [kept local]" } else { "" };
+            println!("{:<10} {} {}: {} (repo {}){}", "drift", d.file, d.key, local, d.repo, kept);
+        }
+        let unchanged = report.components.iter().filter(|c| c.status == "unchanged").count();
+        println!(
+            "\n{} of {} components unchanged, {} drifted settings keys",
+            unchanged,
+            report.components.len(),
+            report.settings_drift.len()
+        );
+    }
+
+    Ok(if check && report.differs() { 1 } else { 0 })
+}
+
+/// `installer layers`
+fn run_layers() -> Result<i32> {
+    let layers = find_source_layers()?;
+    for (idx, layer) in layers.iter().enumerate() {
+        println!("{}  {:<12} {}", idx, layer.name, layer.path.display());
+        if let Some(remote) = &layer.remote {
+            println!("   {:<12} {}  {}", "", remote.source.git, remote.summary());
+            if let Some(e) = &remote.error {
+                println!("   {:<12} fetch failed: {}", "", e);
+            }
+        }
+    }
+    if layers.len() == 1
+        && let Some(path) = crate::config::config_path()
+    {
+        println!("\nAdd overlays under `layers:` in {}", path.display());
+    }
+    Ok(0)
+}
+
+/// `installer layers update`: exit code 1 when any remote failed
+fn run_layers_update() -> Result<i32> {
+    let remotes = crate::config::remote_sources(&crate::config::load()?);
+    if remotes.is_empty() {
+        println!("No git-backed layers configured");
+        return Ok(0);
+    }
+
+    let cache_root = crate::remote::cache_root()?;
+    let mut failed = false;
+    for remote in &remotes {
+        match crate::remote::update(remote, &cache_root) {
+            Ok(status) => println!("[OK] {} {}", remote.name(), status.summary()),
+            Err(e) => {
+                println!("[ERR] {}: {:#}", remote.name(), e);
+                failed = true;
+            }
+        }
+    }
+    Ok(if failed { 1 } else { 0 })
+}
+
+/// `installer snapshot [label]`
+fn run_snapshot(label: Option<&str>) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = TargetCli::Claude.get_dest_dir()?;
+    let snapshot = snapshot::create(&dest_dir, &sources, TargetCli::Claude, label)?;
+
+    println!(
+        "Created snapshot {} ({} files, {} MCP servers, {} plugins)",
+        snapshot.id,
+        snapshot.files.len(),
+        snapshot.mcp_servers.len(),
+        snapshot.plugins.len()
+    );
+    Ok(0)
+}
+
+/// `installer snapshots`
+fn run_snapshots() -> Result<i32> {
+    let dest_dir = TargetCli::Claude.get_dest_dir()?;
+    for s in snapshot::list(&dest_dir) {
+        println!(
+            "{}  {}  {} files{}",
+            s.id,
+            format_timestamp(s.timestamp),
+            s.files.len(),
+            s.label.map(|l| format!("  [{}]", l)).unwrap_or_default()
+        );
+    }
+    Ok(0)
+}
+
+/// `installer restore <id>`: exit code 1 when any step failed
+fn run_restore(id: &str) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = TargetCli::Claude.get_dest_dir()?;
+    let target = snapshot::find(&dest_dir, id)?;
+
+    let safety = snapshot::create(&dest_dir, &sources, TargetCli::Claude, Some("pre-restore"))?;
+    println!("Saved current state as {}", safety.id);
+
+    let mcp_catalog = fs::scanner::scan_mcp_servers(&sources, TargetCli::Claude, &dest_dir)?;
+    let plugin_catalog = fs::scanner::scan_plugins(&sources)?;
+    let log = snapshot::restore(&dest_dir, &target, TargetCli::Claude, &mcp_catalog, &plugin_catalog)?;
+
+    for line in &log {
+        println!("{}", line);
+    }
+
+    Ok(if log.iter().any(|l| l.starts_with("[ERR]")) { 1 } else { 0 })
+}
+
+/// `installer purge [--dry-run] [--yes] [--target ..]`: exit code 1 when any step failed
+fn run_purge(dry_run: bool, yes: bool, target: TargetCli) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let dest_dir = target.get_dest_dir()?;
+
+    let components = fs::scanner::scan_components(&sources, &dest_dir, target, fs::cache::cache_path().as_deref())?;
+    let mcp_servers = fs::scanner::scan_mcp_servers(&sources, target, &dest_dir)?;
+    // Codex has no plugins
+    let plugins = match target {
+        TargetCli::Claude => fs::scanner::scan_plugins(&sources)?,
+        TargetCli::Codex => Vec::new(),
+    };
+    let registrations = fs::scanner::mcp_registrations(target, &dest_dir);
+    let (output_style, statusline) = crate::app::read_current_settings(&dest_dir);
+
+    let inputs = purge::Inputs {
+        components: &components,
+        mcp_servers: &mcp_servers,
+        registrations: &registrations,
+        plugins: &plugins,
+        output_style: output_style.as_deref(),
+        statusline: statusline.as_deref(),
+    };
+    let plan = purge::plan(&inputs);
+
+    for step in &plan.steps {
+        println!("{}", step.describe(&components, &mcp_servers, &plugins));
+    }
+    for &i in &plan.kept {
+        println!("keep {} (edited locally)", components[i].display_name());
+    }
+    if plan.is_empty() {
+        println!("Nothing to purge");
+        return Ok(0);
+    }
+    if dry_run {
+        return Ok(0);
+    }
+    if !yes {
+        print!("\nPurge {} items? [y/N] ", plan.steps.len());
+        std::io::Write::flush(&mut std::io::stdout())?;
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Cancelled");
+            return Ok(0);
+        }
+    }
+
+    let safety = snapshot::create(&dest_dir, &sources, target, Some("pre-purge"))?;
+    println!("Saved current state as {}", safety.id);
+
+    let log = purge::run(&plan, &inputs, &dest_dir, target);
+    for line in &log {
+        println!("{}", line);
+    }
+    Ok(if log.iter().any(|l| l.starts_with("[ERR]")) { 1 } else { 0 })
+}
+
+/// `installer profile list`
+fn run_profile_list() -> Result<i32> {
+    let layers = find_source_layers()?;
+    for entry in profile::list(&layers[0].path) {
+        match entry.result {
+            Ok(p) => println!("{}  {}", entry.name, p.description.unwrap_or_default()),
+            Err(e) => println!("{}  (invalid: {})", entry.name, e),
+        }
+    }
+    Ok(0)
+}
+
+/// One convergence step of `profile apply` (indices into the scanned lists)
+enum Step {
+    InstallComponent(usize),
+    RemoveComponent(usize),
+    InstallMcp(usize),
+    RemoveMcp(usize),
+    UnregisterMcp(usize), // Index into plan.misplaced_mcp
+    InstallPlugin(usize),
+    RemovePlugin(usize),
+}
+
+/// `installer profile apply <name>`: exit code 1 when any step failed
+fn run_profile_apply(name: &str, dry_run: bool, target: TargetCli) -> Result<i32> {
+    let sources = crate::config::layer_paths(&find_source_layers()?);
+    let source_dir = sources[0].clone();
+    let dest_dir = target.get_dest_dir()?;
+    let profile = profile::load(&source_dir, name)?;
+
+    let components = fs::scanner::scan_components(&sources, &dest_dir, target, fs::cache::cache_path().as_deref())?;
+    let mcp_servers = fs::scanner::scan_mcp_servers(&sources, target, &dest_dir)?;
+    // Codex has no plugins
+    let plugins = match target {
+        TargetCli::Claude => fs::scanner::scan_plugins(&sources)?,
+        TargetCli::Codex => Vec::new(),
+    };
+    let graph = DependencyGraph::build(&components);
+    let registrations = fs::scanner::mcp_registrations(target, &dest_dir);
+    let plan = profile::plan(&profile, &components, &graph, &mcp_servers, &registrations, &plugins);
+
+    for unknown in &plan.unknown {
+        println!("warning: {} matches nothing in the catalogs", unknown);
+    }
+    if plan.is_empty() {
+        println!("Already matches profile {}", name);
+        return Ok(0);
+    }
+
+    let mut steps: Vec<(String, Step)> = Vec::new();
+    steps.extend(plan.install_components.iter().map(|&i| (format!("install {}", components[i].display_name()), Step::InstallComponent(i))));
+    steps.extend(plan.remove_components.iter().map(|&i| (format!("remove {}", components[i].display_name()), Step::RemoveComponent(i))));
+    // Misplaced registrations go first so the re-add lands at the profile's scope
+    steps.extend(plan.misplaced_mcp.iter().enumerate().map(|(i, r)| {
+        (format!("remove mcp {} ({} scope)", r.name, r.scope.display()), Step::UnregisterMcp(i))
+    }));
+    steps.extend(plan.install_mcp.iter().map(|&i| (format!("install mcp {}", mcp_servers[i].def.name), Step::InstallMcp(i))));
+    steps.extend(plan.remove_mcp.iter().map(|&i| (format!("remove mcp {}", mcp_servers[i].def.name), Step::RemoveMcp(i))));
+    steps.extend(plan.install_plugins.iter().map(|&i| (format!("install plugin {}", plugins[i].def.id()), Step::InstallPlugin(i))));
+    steps.extend(plan.remove_plugins.iter().map(|&i| (format!("remove plugin {}", plugins[i].def.id()), Step::RemovePlugin(i))));
+
+    let mut failed = 0;
+    for (label, step) in &steps {
+        if dry_run {
+            println!("would {}", label);
+            continue;
+        }
+
+        let result = match *step {
+            Step::InstallComponent(i) => fs::installer::install_component(&components[i], &source_dir, &dest_dir),
+            Step::RemoveComponent(i) => fs::installer::remove_component(&components[i], &dest_dir),
+            Step::InstallMcp(i) => {
+                let server = &mcp_servers[i];
+                let entry = profile.mcp_entry(&server.def.name);
+                let scope = entry.map(|e| e.scope()).unwrap_or_default();
+                let project = entry.and_then(|e| e.project()).map(|p| crate::config::expand_home(p).to_string_lossy().into_owned());
+                let env = server.env_from_environment();
+                fs::installer::install_mcp_server(server, scope, project.as_deref(), &env, target).map(|_| ())
+            }
+            Step::RemoveMcp(i) => fs::installer::remove_mcp_server(&mcp_servers[i], target),
+            Step::UnregisterMcp(i) => {
+                let registration = &plan.misplaced_mcp[i];
+                match mcp_servers.iter().find(|m| m.def.name == registration.name) {
+                    Some(server) => fs::installer::remove_mcp_server_from(server, registration.scope, registration.project.as_deref()),
+                    None => Err(anyhow::anyhow!("not in catalog")),
+                }
+            }
+            Step::InstallPlugin(i) => fs::installer::install_plugin(&plugins[i]).map(|_| ()),
+            Step::RemovePlugin(i) => fs::installer::remove_plugin(&plugins[i]),
+        };
+
+        match result {
+            Ok(()) => println!("[OK] {}", label),
+            Err(e) => {
+                failed += 1;
+                println!("[ERR] {}: {}", label, e);
+            }
+        }
+    }
+
+    if !dry_run {
+        println!("\n{} steps, {} failed", steps.len(), failed);
+    }
+    Ok(if failed > 0 { 1 } else { 0 })
+}
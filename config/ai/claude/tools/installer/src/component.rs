@@ -1,7 +1,11 @@
 use std::path::{Path, PathBuf};
 use serde::Deserialize;
 
-#[derive(Clone, Debug, PartialEq)]
+use crate::config_file::ConfigFileDef;
+use crate::frontmatter::{parse_frontmatter, Frontmatter};
+use crate::lint::{LintIssue, LintLevel};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum ComponentType {
     Agents,
     Commands,
@@ -28,6 +32,14 @@ impl ComponentType {
             Self::ConfigFile => "config",
         }
     }
+
+    /// Directory under the CLI config dir (None for top-level config files)
+    pub fn dest_subdir(&self) -> Option<&str> {
+        match self {
+            Self::ConfigFile => None,
+            _ => Some(self.display_name()),
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -56,9 +68,11 @@ pub struct HookConfig {
     #[serde(rename = "type")]
     pub hook_type: String,
     #[serde(default)]
-    pub timeout: Option<u32>,
+    pub timeout: Option<u32>, // Seconds, written to settings.json as is
     #[serde(default)]
     pub description: Option<String>,
+    #[serde(default)]
+    pub requires: Vec<String>,
 }
 
 impl HookConfig {
@@ -98,6 +112,12 @@ pub struct Component {
     pub selected: bool,
     pub status: InstallStatus,
     pub hook_config: Option<HookConfig>,
+    pub issues: Vec<LintIssue>, // Validation results from the scan-time lint pass
+    pub requires: Vec<String>,  // `requires:` references (see deps.rs)
+    pub layer: usize,           // Source layer the file comes from (0 = base)
+    pub overrides: Vec<usize>,  // Earlier layers that have the same file
+    pub config_file: Option<ConfigFileDef>, // Manifest entry (config files only)
+    pub source_hash: Option<u64>,           // Content hash from the scan (files only)
 }
 
 impl Component {
@@ -116,6 +136,12 @@ impl Component {
             selected: status != InstallStatus::Unchanged,
             status,
             hook_config: None,
+            issues: Vec::new(),
+            requires: Vec::new(),
+            layer: 0,
+            overrides: Vec::new(),
+            config_file: None,
+            source_hash: None,
         }
     }
 
@@ -124,7 +150,100 @@ impl Component {
         self
     }
 
+    pub fn with_config_file(mut self, def: ConfigFileDef) -> Self {
+        self.config_file = Some(def);
+        self
+    }
+
+    /// Merge strategy of a config file (manifest entry, or the default for its name)
+    pub fn config_file_def(&self) -> ConfigFileDef {
+        self.config_file.clone().unwrap_or_else(|| ConfigFileDef::fallback(&self.name))
+    }
+
     pub fn display_name(&self) -> String {
         format!("{}/{}", self.component_type.display_name(), self.name)
     }
+
+    pub fn error_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.level == LintLevel::Error).count()
+    }
+
+    pub fn warning_count(&self) -> usize {
+        self.issues.iter().filter(|i| i.level == LintLevel::Warning).count()
+    }
+}
+
+/// Number of body lines shown in the detail pane preview
+const PREVIEW_LINES: usize = 12;
+
+/// Details shown in the component detail pane
+#[derive(Clone, Debug, Default)]
+pub struct ComponentDetail {
+    pub frontmatter: Option<Frontmatter>,
+    pub preview: Vec<String>,
+    pub size: u64,
+    pub estimated_tokens: u64,
+    pub is_binary: bool,
+}
+
+impl ComponentDetail {
+    /// Load details from the component's source file
+    pub fn load(component: &Component) -> Self {
+        let size = std::fs::metadata(&component.source_path)
+            .map(|m| m.len())
+            .unwrap_or(0);
+
+        let mut detail = Self {
+            size,
+            estimated_tokens: estimate_tokens(size),
+            ..Self::default()
+        };
+
+        let bytes = match std::fs::read(&component.source_path) {
+            Ok(b) => b,
+            Err(_) => return detail,
+        };
+
+        let content = match String::from_utf8(bytes) {
+            Ok(c) => c,
+            Err(_) => {
+                detail.is_binary = true;
+                return detail;
+            }
+        };
+
+        let body = match parse_frontmatter(&content) {
+            Ok(Some((frontmatter, body))) => {
+                detail.frontmatter = Some(frontmatter);
+                body
+            }
+            // Invalid frontmatter is reported by the lint pass
+            Ok(None) | Err(_) => content.as_str(),
+        };
+
+        detail.preview = body
+            .lines()
+            .skip_while(|l| l.trim().is_empty())
+            .take(PREVIEW_LINES)
+            .map(|l| l.to_string())
+            .collect();
+
+        detail
+    }
+}
+
+/// Rough token estimate (~4 bytes per token)
+pub fn estimate_tokens(bytes: u64) -> u64 {
+    bytes.div_ceil(4)
+}
+
+/// Human-readable file size
+pub fn format_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else if bytes >= 1024 {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    } else {
+        format!("{} B", bytes)
+    }
 }
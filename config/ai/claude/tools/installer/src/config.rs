@@ -0,0 +1,192 @@
+//! Installer settings (`~/.config/claude-installer/config.yaml`)
+//!
+//! ```yaml
+//! source:                  # Base layer from git instead of the dotfiles checkout
+//!   git: https://github.com/team/dotfiles.git
+//!   ref: main
+//!   path: config/ai/claude
+//! layers:                  # Overlays, applied over the base in order
+//!   - git: file:///srv/git/team-claude.git
+//!   - ~/my-claude-overlay
+//! theme: frappe            # Id of a bundled or ~/.config/claude-installer/themes/ theme
+//! ```
+//!
+//! `INSTALLER_LAYERS` (a PATH-style list of directories) replaces `layers` when set.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::remote::{self, RemoteSource, RemoteStatus};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<RemoteSource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerSpec>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme: Option<String>,
+}
+
+/// Overlay entry: a local directory or a git remote
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LayerSpec {
+    Path(String),
+    Git(RemoteSource),
+}
+
+/// A resolved source root
+#[derive(Clone, Debug)]
+pub struct SourceLayer {
+    pub name: String,
+    pub path: PathBuf,
+    pub remote: Option<RemoteStatus>, // Set for git-backed layers
+}
+
+impl SourceLayer {
+    fn local(name: String, path: PathBuf) -> Self {
+        Self { name, path, remote: None }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("claude-installer").join("config.yaml"))
+}
+
+/// Load the config file (missing file = defaults)
+pub fn load() -> Result<Config> {
+    let Some(path) = config_path().filter(|p| p.exists()) else {
+        return Ok(Config::default());
+    };
+
+    let content = std::fs::read_to_string(&path)?;
+    let config: Config = serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid installer config: {}", path.display()))?;
+    Ok(config)
+}
+
+/// Remember the chosen theme, keeping the rest of the config file (comments too) as written
+pub fn save_theme(id: &str) -> Result<()> {
+    let path = config_path().context("No config directory")?;
+    let content = std::fs::read_to_string(&path).unwrap_or_default();
+    let updated = with_theme(&content, id);
+    serde_yaml::from_str::<Config>(&updated).with_context(|| format!("Invalid installer config: {}", path.display()))?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, updated)?;
+    Ok(())
+}
+
+/// Replace the top-level `theme:` line (keeping its comment) or append one
+fn with_theme(content: &str, id: &str) -> String {
+    let mut found = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| match line.strip_prefix("theme:") {
+            Some(rest) if !found => {
+                found = true;
+                // The comment and the spacing before it stay
+                let comment = rest.find('#').map(|hash| rest[..hash].trim_end().len()).unwrap_or(rest.len());
+                format!("theme: {}{}", id, &rest[comment..])
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !found {
+        lines.push(format!("theme: {}", id));
+    }
+    lines.join("\n") + "\n"
+}
+
+/// Ordered source roots: the base first, then each overlay.
+/// `local_base` locates the dotfiles checkout when no remote `source` is configured.
+/// Git layers are cloned when missing; existing checkouts are fetched only with `fetch`.
+pub fn source_layers(config: &Config, local_base: impl FnOnce() -> Result<PathBuf>, fetch: bool) -> Result<Vec<SourceLayer>> {
+    let base = match &config.source {
+        Some(remote) => sync_remote("base".to_string(), remote, fetch)?,
+        None => SourceLayer::local("base".to_string(), local_base()?),
+    };
+
+    let overlays: Vec<LayerSpec> = match std::env::var_os("INSTALLER_LAYERS") {
+        Some(value) => std::env::split_paths(&value)
+            .filter(|p| !p.as_os_str().is_empty())
+            .map(|p| LayerSpec::Path(p.to_string_lossy().to_string()))
+            .collect(),
+        None => config.layers.clone(),
+    };
+
+    let mut layers = vec![base];
+    for overlay in overlays {
+        let layer = match overlay {
+            LayerSpec::Path(path) => {
+                let path = expand_home(&path);
+                let resolved = path
+                    .canonicalize()
+                    .with_context(|| format!("Source layer not found: {}", path.display()))?;
+                let name = resolved
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| resolved.display().to_string());
+                SourceLayer::local(name, resolved)
+            }
+            LayerSpec::Git(remote) => sync_remote(remote.name(), &remote, fetch)?,
+        };
+        if !layers.iter().any(|l| l.path == layer.path) {
+            layers.push(layer);
+        }
+    }
+    Ok(layers)
+}
+
+fn sync_remote(name: String, remote: &RemoteSource, fetch: bool) -> Result<SourceLayer> {
+    let (path, status) = remote::sync(remote, &remote::cache_root()?, fetch)?;
+    Ok(SourceLayer { name, path, remote: Some(status) })
+}
+
+/// Git-backed sources from the config (base first)
+pub fn remote_sources(config: &Config) -> Vec<RemoteSource> {
+    let overlays = config.layers.iter().filter_map(|l| match l {
+        LayerSpec::Git(remote) => Some(remote.clone()),
+        LayerSpec::Path(_) => None,
+    });
+    config.source.clone().into_iter().chain(overlays).collect()
+}
+
+/// Expand a leading `~/` to the home directory
+pub fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Root paths for the scanner
+pub fn layer_paths(layers: &[SourceLayer]) -> Vec<PathBuf> {
+    layers.iter().map(|l| l.path.clone()).collect()
+}
+
+/// Short label for a layer in the TUI
+pub fn layer_name(layers: &[SourceLayer], layer: usize) -> String {
+    layers
+        .get(layer)
+        .map(|l| l.name.clone())
+        .unwrap_or_else(|| format!("layer {}", layer))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_theme_keeps_comments() {
+        let config = "# Team layers\nlayers:\n  - ~/overlay  # mine\ntheme: mocha   # dark\n";
+        assert_eq!(with_theme(config, "latte"), "# Team layers\nlayers:\n  - ~/overlay  # mine\ntheme: latte   # dark\n");
+        assert_eq!(with_theme("# empty\n", "frappe"), "# empty\ntheme: frappe\n");
+        assert_eq!(with_theme("", "frappe"), "theme: frappe\n");
+    }
+}
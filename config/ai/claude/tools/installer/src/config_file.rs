@@ -0,0 +1,96 @@
+//! Config files manifest (`<source_dir>/config-files.yaml`)
+//!
+//! ```yaml
+//! files:
+//!   - source: settings.json
+//!     strategy: json-merge
+//!   - source: CLAUDE.md
+//!     strategy: markdown-block     # Kept between markers, the rest of the file is the user's
+//!   - source: keybindings.json     # overwrite is the default
+//!   - source: codex/config.toml
+//!     dest: config.toml            # Relative to the CLI config dir (default: same as source)
+//!     target: codex                # claude (default), codex or all
+//!     strategy: toml-merge
+//! ```
+//!
+//! Without a manifest, `settings.json` is installed with `json-merge`.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::app::TargetCli;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MergeStrategy {
+    #[default]
+    Overwrite,
+    JsonMerge,
+    TomlMerge,
+    MarkdownBlock,
+}
+
+impl MergeStrategy {
+    pub fn display(&self) -> &str {
+        match self {
+            Self::Overwrite => "overwrite",
+            Self::JsonMerge => "JSON deep-merge",
+            Self::TomlMerge => "TOML merge",
+            Self::MarkdownBlock => "managed markdown block",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ConfigFileDef {
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dest: Option<String>,
+    #[serde(default)]
+    pub strategy: MergeStrategy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+}
+
+impl ConfigFileDef {
+    /// Definition used when a component carries none (settings.json is always merged)
+    pub fn fallback(name: &str) -> Self {
+        let strategy = if name == "settings.json" { MergeStrategy::JsonMerge } else { MergeStrategy::Overwrite };
+        Self { source: name.to_string(), dest: None, strategy, target: None }
+    }
+
+    /// Path relative to the CLI config dir
+    pub fn dest(&self) -> &str {
+        self.dest.as_deref().unwrap_or(&self.source)
+    }
+
+    pub fn applies_to(&self, target_cli: TargetCli) -> bool {
+        match self.target.as_deref() {
+            None | Some("claude") => target_cli == TargetCli::Claude,
+            Some("codex") => target_cli == TargetCli::Codex,
+            Some("all") => true,
+            Some(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ConfigManifest {
+    #[serde(default)]
+    files: Vec<ConfigFileDef>,
+}
+
+/// Config files declared by one source layer
+pub fn load_manifest(source_dir: &Path) -> Result<Vec<ConfigFileDef>> {
+    let path = source_dir.join("config-files.yaml");
+    if !path.exists() {
+        return Ok(vec![ConfigFileDef::fallback("settings.json")]);
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    let manifest: ConfigManifest = serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid config manifest: {}", path.display()))?;
+    Ok(manifest.files)
+}
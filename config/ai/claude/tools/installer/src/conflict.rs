@@ -0,0 +1,245 @@
+//! Merge conflicts: JSON or TOML keys where the local file already has a different value
+//!
+//! Choices are remembered per machine in `<dest_dir>/.installer/merge-choices.json`,
+//! next to what the last installs changed, so removal takes out only that:
+//!
+//! ```json
+//! { "settings.json": {
+//!     "choices": { "env.MCP_TIMEOUT": "keep-local", "model": "take-repo" },
+//!     "installed": {
+//!       "added": [{ "path": ["env", "A"], "value": "1" }],
+//!       "items": [{ "path": ["permissions", "allow"], "value": "Bash(ls:*)" }],
+//!       "replaced": [{ "path": ["model"], "value": "sonnet" }] } } }
+//! ```
+//!
+//! TOML values are kept as their JSON equivalent. A conflict nobody has reviewed keeps
+//! the local value.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Resolution {
+    #[default]
+    KeepLocal,
+    TakeRepo,
+}
+
+impl Resolution {
+    pub fn display(&self) -> &str {
+        match self {
+            Self::KeepLocal => "keep local",
+            Self::TakeRepo => "take repo",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            Self::KeepLocal => Self::TakeRepo,
+            Self::TakeRepo => Self::KeepLocal,
+        }
+    }
+}
+
+/// Key path (`env.MCP_TIMEOUT`) -> choice, for one config file
+pub type Choices = BTreeMap<String, Resolution>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Conflict {
+    pub key: String,
+    pub local: Value,
+    pub repo: Value,
+}
+
+/// A value at a JSON key path (`["env", "A"]`)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Contribution {
+    pub path: Vec<String>,
+    pub value: Value,
+}
+
+/// What installs changed in a JSON or TOML file, so removal can undo exactly that
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Ledger {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub added: Vec<Contribution>, // Keys that did not exist, with the value written
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<Contribution>, // Array items appended
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replaced: Vec<Contribution>, // Local values a "take repo" choice overwrote
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct FileState {
+    #[serde(default)]
+    choices: Choices,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    installed: Option<Ledger>,
+}
+
+pub fn choices_path(dest_dir: &Path) -> PathBuf {
+    dest_dir.join(".installer").join("merge-choices.json")
+}
+
+fn load_all(dest_dir: &Path) -> BTreeMap<String, FileState> {
+    std::fs::read_to_string(choices_path(dest_dir))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_all(dest_dir: &Path, all: &BTreeMap<String, FileState>) -> Result<()> {
+    let path = choices_path(dest_dir);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(all)?)?;
+    Ok(())
+}
+
+/// Remembered choices for a config file (path relative to dest_dir)
+pub fn load(dest_dir: &Path, file: &str) -> Choices {
+    load_all(dest_dir).remove(file).map(|s| s.choices).unwrap_or_default()
+}
+
+pub fn save(dest_dir: &Path, file: &str, choices: &Choices) -> Result<()> {
+    let mut all = load_all(dest_dir);
+    all.entry(file.to_string()).or_default().choices = choices.clone();
+    save_all(dest_dir, &all)
+}
+
+/// What installs changed in a config file (None = installed before changes were recorded)
+pub fn load_ledger(dest_dir: &Path, file: &str) -> Option<Ledger> {
+    load_all(dest_dir).remove(file).and_then(|s| s.installed)
+}
+
+/// Record the changes of an install; None once the file's contributions are removed
+pub fn save_ledger(dest_dir: &Path, file: &str, ledger: Option<Ledger>) -> Result<()> {
+    let mut all = load_all(dest_dir);
+    all.entry(file.to_string()).or_default().installed = ledger;
+    save_all(dest_dir, &all)
+}
+
+/// Keys whose local value differs from the repo's. Objects are compared key by key
+/// and arrays merge as sets, so neither conflicts as a whole.
+pub fn find(local: &Value, repo: &Value) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    collect(local, repo, "", &mut conflicts);
+    conflicts
+}
+
+fn collect(local: &Value, repo: &Value, prefix: &str, conflicts: &mut Vec<Conflict>) {
+    let (Value::Object(local_map), Value::Object(repo_map)) = (local, repo) else {
+        return;
+    };
+
+    for (key, repo_value) in repo_map {
+        let path = key_path(prefix, key);
+        match (local_map.get(key), repo_value) {
+            (None, _) => {}
+            (Some(local_value @ Value::Object(_)), Value::Object(_)) => {
+                collect(local_value, repo_value, &path, conflicts)
+            }
+            (Some(Value::Array(_)), Value::Array(_)) => {}
+            (Some(local_value), _) if local_value == repo_value => {}
+            (Some(local_value), _) => conflicts.push(Conflict {
+                key: path,
+                local: local_value.clone(),
+                repo: repo_value.clone(),
+            }),
+        }
+    }
+}
+
+pub fn key_path(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", prefix, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config_file::{ConfigFileDef, MergeStrategy};
+    use crate::fs::merge;
+    use crate::test_util::{temp_dir, write};
+
+    const REPO: &str = r#"{"model":"opus","env":{"A":"1"},"permissions":{"allow":["Read","Bash(ls:*)"]}}"#;
+    const LOCAL: &str = r#"{"model":"sonnet","permissions":{"allow":["Read","Edit"]}}"#;
+
+    /// Install REPO over LOCAL with `choices`, persisting choices and ledger like the installer
+    fn install(name: &str, choices: Choices) -> (PathBuf, ConfigFileDef, Value) {
+        let dir = temp_dir(name);
+        let source = write(&dir, "source.json", REPO);
+        let dest = write(&dir, "settings.json", LOCAL);
+        let def = ConfigFileDef { source: "source.json".into(), dest: None, strategy: MergeStrategy::JsonMerge, target: None };
+
+        save(&dir, "settings.json", &choices).unwrap();
+        let mut ledger = load_ledger(&dir, "settings.json").unwrap_or_default();
+        merge::install(&def, &source, &dest, &choices, &mut ledger).unwrap();
+        save_ledger(&dir, "settings.json", Some(ledger)).unwrap();
+
+        let installed = serde_json::from_str(&std::fs::read_to_string(&dest).unwrap()).unwrap();
+        (dir, def, installed)
+    }
+
+    fn remove(dir: &Path, def: &ConfigFileDef) -> Value {
+        let ledger = load_ledger(dir, "settings.json");
+        assert!(ledger.is_some());
+        merge::remove(def, &dir.join("source.json"), &dir.join("settings.json"), ledger.as_ref()).unwrap();
+        serde_json::from_str(&std::fs::read_to_string(dir.join("settings.json")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_keep_local_round_trip() {
+        let (dir, def, installed) = install("conflict-keep", Choices::from([("model".to_string(), Resolution::KeepLocal)]));
+        assert_eq!(installed["model"], "sonnet");
+        assert_eq!(installed["env"]["A"], "1");
+        assert_eq!(installed["permissions"]["allow"], serde_json::json!(["Read", "Edit", "Bash(ls:*)"]));
+        assert_eq!(load(&dir, "settings.json").get("model"), Some(&Resolution::KeepLocal));
+
+        // "Read" was the user's before the install and stays
+        let removed = remove(&dir, &def);
+        assert_eq!(removed, serde_json::from_str::<Value>(LOCAL).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_take_repo_round_trip_restores_local_value() {
+        let (dir, def, installed) = install("conflict-take", Choices::from([("model".to_string(), Resolution::TakeRepo)]));
+        assert_eq!(installed["model"], "opus");
+        let replaced = load_ledger(&dir, "settings.json").unwrap().replaced;
+        assert_eq!(replaced, vec![Contribution { path: vec!["model".to_string()], value: "sonnet".into() }]);
+
+        let removed = remove(&dir, &def);
+        assert_eq!(removed, serde_json::from_str::<Value>(LOCAL).unwrap());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_remove_keeps_user_edits_after_install() {
+        let (dir, def, _) = install("conflict-edit", Choices::from([("model".to_string(), Resolution::TakeRepo)]));
+
+        // The user changes the replaced value and extends an added object
+        let mut edited: Value = serde_json::from_str(&std::fs::read_to_string(dir.join("settings.json")).unwrap()).unwrap();
+        edited["model"] = "haiku".into();
+        edited["env"]["MINE"] = "x".into();
+        std::fs::write(dir.join("settings.json"), edited.to_string()).unwrap();
+
+        let removed = remove(&dir, &def);
+        assert_eq!(removed["model"], "haiku");
+        assert_eq!(removed["env"], serde_json::json!({"MINE": "x"}));
+        assert_eq!(removed["permissions"]["allow"], serde_json::json!(["Read", "Edit"]));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,183 @@
+//! Component dependency declarations (`requires:`)
+//!
+//! Declared in markdown frontmatter or hook.yaml as a list of component references:
+//! - `agents/code-standards.md` exact component (`.md` may be omitted)
+//! - `skills/rust-best-practices/` or `agents/*` everything under a folder
+
+use crate::component::Component;
+use crate::frontmatter::parse_frontmatter;
+use crate::lint::LintIssue;
+
+/// Fill `Component::requires` from frontmatter (markdown) or hook.yaml (hooks)
+pub fn load_requires(components: &mut [Component]) {
+    for c in components.iter_mut() {
+        if let Some(config) = &c.hook_config {
+            c.requires = config.requires.clone();
+            continue;
+        }
+
+        if c.source_path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+
+        if let Ok(content) = std::fs::read_to_string(&c.source_path)
+            && let Ok(Some((fm, _))) = parse_frontmatter(&content)
+        {
+            c.requires = fm.requires;
+        }
+    }
+}
+
+/// Report `requires:` references that match no scanned component
+pub fn validate(components: &mut [Component]) {
+    for idx in 0..components.len() {
+        let unresolved: Vec<String> = components[idx]
+            .requires
+            .iter()
+            .filter(|r| {
+                !components
+                    .iter()
+                    .enumerate()
+                    .any(|(i, c)| i != idx && matches(r, c))
+            })
+            .cloned()
+            .collect();
+
+        for reference in unresolved {
+            components[idx].issues.push(LintIssue::error(format!(
+                "Unknown dependency '{}'",
+                reference
+            )));
+        }
+    }
+}
+
+/// Check if a `requires:` reference points at a component
+pub fn matches(reference: &str, component: &Component) -> bool {
+    let reference = reference.trim().replace('\\', "/");
+    let name = component.display_name().replace('\\', "/");
+
+    let folder = reference.trim_end_matches('*').trim_end_matches('/');
+    if reference.ends_with('/') || reference.ends_with("/*") {
+        return name.starts_with(&format!("{}/", folder));
+    }
+
+    name == reference
+        || name == format!("{}.md", reference)
+        || name.starts_with(&format!("{}/", reference))
+}
+
+/// Resolved dependency edges between component indices
+#[derive(Clone, Debug, Default)]
+pub struct DependencyGraph {
+    requires: Vec<Vec<usize>>,
+    required_by: Vec<Vec<usize>>,
+}
+
+impl DependencyGraph {
+    pub fn build(components: &[Component]) -> Self {
+        let mut requires = vec![Vec::new(); components.len()];
+        let mut required_by = vec![Vec::new(); components.len()];
+
+        for (idx, c) in components.iter().enumerate() {
+            for reference in &c.requires {
+                for (dep_idx, dep) in components.iter().enumerate() {
+                    if dep_idx != idx && matches(reference, dep) && !requires[idx].contains(&dep_idx) {
+                        requires[idx].push(dep_idx);
+                        required_by[dep_idx].push(idx);
+                    }
+                }
+            }
+        }
+
+        Self { requires, required_by }
+    }
+
+    /// Direct dependencies of a component
+    pub fn requires(&self, idx: usize) -> &[usize] {
+        self.requires.get(idx).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Components that directly depend on this one
+    pub fn required_by(&self, idx: usize) -> &[usize] {
+        self.required_by.get(idx).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Transitive dependencies of `roots` (excluding the roots themselves)
+    pub fn closure(&self, roots: &[usize]) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = roots.to_vec();
+
+        while let Some(idx) = stack.pop() {
+            for &dep in self.requires(idx) {
+                if !roots.contains(&dep) && !result.contains(&dep) {
+                    result.push(dep);
+                    stack.push(dep);
+                }
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::{ComponentType, InstallStatus};
+    use std::path::PathBuf;
+
+    fn make(component_type: ComponentType, name: &str, requires: &[&str]) -> Component {
+        let mut c = Component::new(
+            component_type,
+            name.to_string(),
+            PathBuf::from(name),
+            PathBuf::from(name),
+            InstallStatus::New,
+        );
+        c.requires = requires.iter().map(|r| r.to_string()).collect();
+        c
+    }
+
+    #[test]
+    fn test_reference_matching() {
+        let agent = make(ComponentType::Agents, "affaan-m/planner.md", &[]);
+        assert!(matches("agents/affaan-m/planner.md", &agent));
+        assert!(matches("agents/affaan-m/planner", &agent));
+        assert!(matches("agents/affaan-m/", &agent));
+        assert!(matches("agents/*", &agent));
+        assert!(matches("agents/affaan-m", &agent));
+        assert!(!matches("agents/affaan", &agent));
+        assert!(!matches("rules/*", &agent));
+    }
+
+    #[test]
+    fn test_transitive_closure() {
+        let components = vec![
+            make(ComponentType::Commands, "review.md", &["agents/reviewer"]),
+            make(ComponentType::Agents, "reviewer.md", &["rules/security.md"]),
+            make(ComponentType::Rules, "security.md", &[]),
+            make(ComponentType::Rules, "unrelated.md", &[]),
+        ];
+
+        let graph = DependencyGraph::build(&components);
+        assert_eq!(graph.requires(0), &[1]);
+        assert_eq!(graph.required_by(2), &[1]);
+
+        let mut closure = graph.closure(&[0]);
+        closure.sort();
+        assert_eq!(closure, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_unresolved_dependency() {
+        let mut components = vec![
+            make(ComponentType::Commands, "review.md", &["agents/missing", "rules/*"]),
+            make(ComponentType::Rules, "security.md", &[]),
+        ];
+
+        validate(&mut components);
+        assert_eq!(components[0].issues.len(), 1);
+        assert!(components[0].issues[0].message.contains("agents/missing"));
+    }
+}
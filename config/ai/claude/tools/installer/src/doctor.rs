@@ -0,0 +1,520 @@
+//! Audit of the live CLI config: does settings.json still point at things that exist?
+//!
+//! Checks hook commands and timeouts, the statusline, the output style, MCP server
+//! executables and plugin marketplaces. Each finding may carry a [`Fix`].
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde_json::Value;
+
+use crate::app::TargetCli;
+use crate::component::{Component, ComponentType};
+use crate::fs;
+use crate::frontmatter::parse_frontmatter;
+use crate::lint::LintLevel;
+use crate::mcp::{McpServer, McpStatus};
+use crate::plugin::Plugin;
+
+/// Output styles built into Claude Code (no file needed)
+const BUILTIN_OUTPUT_STYLES: &[&str] = &["default", "explanatory", "learning"];
+
+/// Hook timeouts above this many seconds are almost certainly milliseconds by mistake
+pub const MAX_HOOK_TIMEOUT: u64 = 600;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Fix {
+    Reinstall(usize), // Index into the scanned components
+    MakeExecutable(PathBuf),
+    RemoveHook { event: String, command: String },
+    ClearHookTimeout { event: String, command: String },
+    SetStatusline(String), // Rewrite statusLine as a command object for this binary
+    UnsetStatusline,
+    UnsetOutputStyle,
+    RemoveMcp(usize), // Index into the MCP catalog
+    AddMarketplace { name: String, source: String },
+    UpdateMarketplace(String),
+    DisablePlugin(String),
+}
+
+impl Fix {
+    pub fn describe(&self, components: &[Component], mcp_servers: &[McpServer]) -> String {
+        match self {
+            Self::Reinstall(idx) => format!(
+                "Reinstall {} from source",
+                components.get(*idx).map(|c| c.display_name()).unwrap_or_default()
+            ),
+            Self::MakeExecutable(path) => format!("chmod +x {}", path.display()),
+            Self::RemoveHook { event, command } => format!("Remove the {} hook '{}'", event, command),
+            Self::ClearHookTimeout { event, .. } => format!("Drop the {} hook timeout (use the default)", event),
+            Self::SetStatusline(name) => format!("Rewrite statusLine as a command for {}", name),
+            Self::UnsetStatusline => "Remove statusLine from settings.json".to_string(),
+            Self::UnsetOutputStyle => "Remove outputStyle (back to the default style)".to_string(),
+            Self::RemoveMcp(idx) => format!(
+                "Remove MCP server {}",
+                mcp_servers.get(*idx).map(|m| m.def.name.as_str()).unwrap_or_default()
+            ),
+            Self::AddMarketplace { name, source } => format!("Add marketplace {} from {}", name, source),
+            Self::UpdateMarketplace(name) => format!("Re-clone marketplace {}", name),
+            Self::DisablePlugin(id) => format!("Remove {} from enabledPlugins", id),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub level: LintLevel,
+    pub area: &'static str, // "hooks", "statusline", "output style", "mcp", "plugins"
+    pub message: String,
+    pub fix: Option<Fix>,
+}
+
+impl Finding {
+    fn new(level: LintLevel, area: &'static str, message: String, fix: Option<Fix>) -> Self {
+        Self { level, area, message, fix }
+    }
+}
+
+/// Run every check. `components` and `mcp_servers` are the scanned catalogs (for fixes),
+/// `plugin_catalog` supplies marketplace sources from plugins.yaml.
+pub fn check(dest_dir: &Path, components: &[Component], mcp_servers: &[McpServer], plugin_catalog: &[Plugin]) -> Vec<Finding> {
+    let mut findings = Vec::new();
+
+    let settings_path = dest_dir.join("settings.json");
+    let settings: Value = match std::fs::read_to_string(&settings_path) {
+        Ok(content) => match serde_json::from_str(&content) {
+            Ok(v) => v,
+            Err(e) => {
+                findings.push(Finding::new(LintLevel::Error, "settings", format!("settings.json is not valid JSON: {}", e), None));
+                Value::Null
+            }
+        },
+        Err(_) => Value::Null,
+    };
+
+    check_hooks(&settings, dest_dir, components, &mut findings);
+    check_statusline(&settings, dest_dir, components, &mut findings);
+    check_output_style(&settings, dest_dir, components, &mut findings);
+    check_mcp(mcp_servers, &mut findings);
+    check_plugins(&settings, dest_dir, plugin_catalog, &mut findings);
+
+    findings
+}
+
+fn check_hooks(settings: &Value, dest_dir: &Path, components: &[Component], findings: &mut Vec<Finding>) {
+    let Some(events) = settings.get("hooks").and_then(|h| h.as_object()) else {
+        return;
+    };
+
+    for (event, matchers) in events {
+        let hooks = matchers
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.get("hooks").and_then(|h| h.as_array()))
+            .flatten();
+
+        for hook in hooks {
+            let Some(command) = hook.get("command").and_then(|c| c.as_str()) else {
+                continue;
+            };
+            let remove = Fix::RemoveHook { event: event.clone(), command: command.to_string() };
+
+            match hook.get("timeout").and_then(|t| t.as_u64()) {
+                Some(0) => findings.push(Finding::new(
+                    LintLevel::Error,
+                    "hooks",
+                    format!("{} hook '{}' has timeout 0", event, command),
+                    Some(Fix::ClearHookTimeout { event: event.clone(), command: command.to_string() }),
+                )),
+                Some(t) if t > MAX_HOOK_TIMEOUT => findings.push(Finding::new(
+                    LintLevel::Warning,
+                    "hooks",
+                    format!("{} hook '{}' has timeout {}s (milliseconds?)", event, command, t),
+                    Some(Fix::ClearHookTimeout { event: event.clone(), command: command.to_string() }),
+                )),
+                _ => {}
+            }
+
+            if let Some(problem) = check_command(command, dest_dir) {
+                let fix = match &problem {
+                    CommandProblem::Missing(path) => reinstall_for(path, components).or(Some(remove)),
+                    CommandProblem::NotExecutable(path) => Some(Fix::MakeExecutable(path.clone())),
+                    CommandProblem::NotOnPath(_) => Some(remove),
+                };
+                let level = match problem {
+                    CommandProblem::NotOnPath(_) => LintLevel::Warning,
+                    _ => LintLevel::Error,
+                };
+                findings.push(Finding::new(level, "hooks", format!("{} hook: {}", event, problem.describe()), fix));
+            }
+        }
+    }
+}
+
+fn check_statusline(settings: &Value, dest_dir: &Path, components: &[Component], findings: &mut Vec<Finding>) {
+    let command = match settings.get("statusLine") {
+        None | Some(Value::Null) => return,
+        Some(Value::String(name)) => {
+            // Older installs wrote the bare binary name, which Claude Code ignores
+            findings.push(Finding::new(
+                LintLevel::Error,
+                "statusline",
+                format!("statusLine is a plain string '{}', not a command object", name),
+                Some(Fix::SetStatusline(name.clone())),
+            ));
+            return;
+        }
+        Some(value) => value.get("command").and_then(|c| c.as_str()),
+    };
+
+    let Some(command) = command else {
+        findings.push(Finding::new(LintLevel::Error, "statusline", "statusLine has no command".to_string(), Some(Fix::UnsetStatusline)));
+        return;
+    };
+
+    if let Some(problem) = check_command(command, dest_dir) {
+        let fix = match &problem {
+            CommandProblem::Missing(path) => reinstall_for(path, components).or(Some(Fix::UnsetStatusline)),
+            CommandProblem::NotExecutable(path) => Some(Fix::MakeExecutable(path.clone())),
+            CommandProblem::NotOnPath(_) => Some(Fix::UnsetStatusline),
+        };
+        findings.push(Finding::new(LintLevel::Error, "statusline", problem.describe(), fix));
+    }
+}
+
+fn check_output_style(settings: &Value, dest_dir: &Path, components: &[Component], findings: &mut Vec<Finding>) {
+    let Some(style) = settings.get("outputStyle").and_then(|s| s.as_str()) else {
+        return;
+    };
+    if BUILTIN_OUTPUT_STYLES.contains(&style.to_lowercase().as_str()) {
+        return;
+    }
+
+    let installed = std::fs::read_dir(dest_dir.join("output-styles"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .any(|e| style_matches(&e.path(), style));
+    if installed {
+        return;
+    }
+
+    let source = components
+        .iter()
+        .position(|c| c.component_type == ComponentType::OutputStyles && style_matches(&c.source_path, style));
+    findings.push(Finding::new(
+        LintLevel::Error,
+        "output style",
+        format!("outputStyle '{}' has no file in output-styles/", style),
+        Some(source.map(Fix::Reinstall).unwrap_or(Fix::UnsetOutputStyle)),
+    ));
+}
+
+/// An output style file answers to its file stem or its frontmatter name
+fn style_matches(path: &Path, style: &str) -> bool {
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return false;
+    }
+    if path.file_stem().and_then(|s| s.to_str()).is_some_and(|s| s.eq_ignore_ascii_case(style)) {
+        return true;
+    }
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| parse_frontmatter(&content).ok().flatten().and_then(|(fm, _)| fm.name))
+        .is_some_and(|name| name.eq_ignore_ascii_case(style))
+}
+
+fn check_mcp(mcp_servers: &[McpServer], findings: &mut Vec<Finding>) {
+    for (idx, server) in mcp_servers.iter().enumerate() {
+        if server.status != McpStatus::Installed {
+            continue;
+        }
+        let Some(program) = server.def.command.as_deref().and_then(|c| c.split_whitespace().next()) else {
+            continue; // HTTP/SSE server
+        };
+        if find_on_path(program).is_none() {
+            findings.push(Finding::new(
+                LintLevel::Error,
+                "mcp",
+                format!("MCP server {} runs '{}', which is not on PATH", server.def.name, program),
+                Some(Fix::RemoveMcp(idx)),
+            ));
+        }
+    }
+}
+
+fn check_plugins(settings: &Value, dest_dir: &Path, plugin_catalog: &[Plugin], findings: &mut Vec<Finding>) {
+    let Some(enabled) = settings.get("enabledPlugins").and_then(|p| p.as_object()) else {
+        return;
+    };
+
+    let plugins_dir = dest_dir.join("plugins");
+    let known = fs::scanner::read_known_marketplaces(&plugins_dir);
+
+    for id in enabled.keys() {
+        let Some((_, marketplace)) = id.split_once('@') else {
+            continue;
+        };
+
+        match known.iter().find(|(name, _, _)| name == marketplace) {
+            Some((_, _, location)) if !location.exists() => findings.push(Finding::new(
+                LintLevel::Error,
+                "plugins",
+                format!("{}: marketplace checkout {} is missing", id, location.display()),
+                Some(Fix::UpdateMarketplace(marketplace.to_string())),
+            )),
+            Some(_) => {}
+            None => {
+                let source = plugin_catalog
+                    .iter()
+                    .find(|p| p.def.marketplace == marketplace && !p.def.source.is_empty())
+                    .map(|p| p.def.source.clone());
+                let fix = match source {
+                    Some(source) => Fix::AddMarketplace { name: marketplace.to_string(), source },
+                    None => Fix::DisablePlugin(id.clone()),
+                };
+                findings.push(Finding::new(
+                    LintLevel::Error,
+                    "plugins",
+                    format!("{}: marketplace {} is not registered", id, marketplace),
+                    Some(fix),
+                ));
+            }
+        }
+    }
+}
+
+enum CommandProblem {
+    Missing(PathBuf),
+    NotExecutable(PathBuf),
+    NotOnPath(String),
+}
+
+impl CommandProblem {
+    fn describe(&self) -> String {
+        match self {
+            Self::Missing(path) => format!("{} does not exist", path.display()),
+            Self::NotExecutable(path) => format!("{} is not executable", path.display()),
+            Self::NotOnPath(program) => format!("'{}' is not on PATH", program),
+        }
+    }
+}
+
+/// Check the program of a shell command line (and any further absolute path arguments)
+fn check_command(command: &str, dest_dir: &Path) -> Option<CommandProblem> {
+    let mut words = command.split_whitespace().map(|w| w.trim_matches(['"', '\'']));
+    let program = words.next()?;
+
+    if program.contains('/') || program.contains('\\') {
+        // Project-relative commands cannot be checked from here
+        let path = expand_path(program, dest_dir)?;
+        if !path.exists() {
+            return Some(CommandProblem::Missing(path));
+        }
+        if !is_executable(&path) {
+            return Some(CommandProblem::NotExecutable(path));
+        }
+    } else if find_on_path(program).is_none() {
+        return Some(CommandProblem::NotOnPath(program.to_string()));
+    }
+
+    // Script arguments such as `python3 ~/.claude/hooks/x.py`
+    words
+        .filter(|w| w.starts_with("~/") || w.starts_with("$HOME") || w.starts_with('/'))
+        .filter_map(|w| expand_path(w, dest_dir))
+        .find(|p| !p.exists())
+        .map(CommandProblem::Missing)
+}
+
+/// Expand `~/.claude/...` to the configured dest dir, other `~`/`$HOME` paths to home.
+/// Returns None for paths relative to a project.
+fn expand_path(word: &str, dest_dir: &Path) -> Option<PathBuf> {
+    let word = word.replace("${HOME}", "~").replace("$HOME", "~");
+    if word.contains('$') {
+        return None; // $CLAUDE_PROJECT_DIR and friends
+    }
+
+    let dest_name = dest_dir.file_name().and_then(|n| n.to_str()).unwrap_or(".claude");
+    if let Some(rest) = word.strip_prefix(&format!("~/{}/", dest_name)) {
+        return Some(dest_dir.join(rest));
+    }
+    if let Some(rest) = word.strip_prefix("~/") {
+        return dirs::home_dir().map(|h| h.join(rest));
+    }
+
+    let path = PathBuf::from(word);
+    path.is_absolute().then_some(path)
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path).map(|m| m.permissions().mode() & 0o111 != 0).unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) { &["", ".exe", ".cmd", ".bat"] } else { &[""] };
+    std::env::var_os("PATH").and_then(|paths| {
+        std::env::split_paths(&paths)
+            .flat_map(|dir| extensions.iter().map(move |ext| dir.join(format!("{}{}", program, ext))))
+            .find(|p| p.is_file())
+    })
+}
+
+/// A source component that installs to `path`
+fn reinstall_for(path: &Path, components: &[Component]) -> Option<Fix> {
+    components.iter().position(|c| c.dest_path == path).map(Fix::Reinstall)
+}
+
+/// Apply a fix. Returns a log line.
+pub fn apply(
+    fix: &Fix,
+    source_dir: &Path,
+    dest_dir: &Path,
+    components: &[Component],
+    mcp_servers: &[McpServer],
+    target_cli: TargetCli,
+) -> Result<String> {
+    let description = fix.describe(components, mcp_servers);
+
+    match fix {
+        Fix::Reinstall(idx) => {
+            let component = components.get(*idx).ok_or_else(|| anyhow::anyhow!("Component no longer scanned"))?;
+            fs::installer::install_component(component, source_dir, dest_dir)?;
+            if matches!(component.component_type, ComponentType::Hooks | ComponentType::Statusline) {
+                make_executable(&component.dest_path)?;
+            }
+        }
+        Fix::MakeExecutable(path) => make_executable(path)?,
+        Fix::RemoveHook { event, command } => edit_hooks(dest_dir, event, |hooks| {
+            hooks.retain(|h| h.get("command").and_then(|c| c.as_str()) != Some(command));
+        })?,
+        Fix::ClearHookTimeout { event, command } => edit_hooks(dest_dir, event, |hooks| {
+            for hook in hooks.iter_mut().filter(|h| h.get("command").and_then(|c| c.as_str()) == Some(command)) {
+                if let Some(obj) = hook.as_object_mut() {
+                    obj.remove("timeout");
+                }
+            }
+        })?,
+        Fix::SetStatusline(name) => fs::installer::set_statusline(dest_dir, name)?,
+        Fix::UnsetStatusline => fs::installer::unset_statusline(dest_dir)?,
+        Fix::UnsetOutputStyle => fs::installer::unset_output_style(dest_dir)?,
+        Fix::RemoveMcp(idx) => {
+            let server = mcp_servers.get(*idx).ok_or_else(|| anyhow::anyhow!("MCP server no longer scanned"))?;
+            fs::installer::remove_mcp_server(server, target_cli)?;
+        }
+        Fix::AddMarketplace { source, .. } => fs::installer::add_marketplace(source)?,
+        Fix::UpdateMarketplace(name) => fs::installer::update_marketplace(name)?,
+        Fix::DisablePlugin(id) => edit_settings(dest_dir, |settings| {
+            if let Some(enabled) = settings.get_mut("enabledPlugins").and_then(|p| p.as_object_mut()) {
+                enabled.remove(id);
+            }
+        })?,
+    }
+
+    Ok(format!("[OK] {}", description))
+}
+
+fn make_executable(path: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(path)?.permissions();
+        perms.set_mode(perms.mode() | 0o755);
+        std::fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+fn edit_settings(dest_dir: &Path, edit: impl FnOnce(&mut Value)) -> Result<()> {
+    let settings_path = dest_dir.join("settings.json");
+    let content = std::fs::read_to_string(&settings_path)?;
+    let mut settings: Value = serde_json::from_str(&content)?;
+    edit(&mut settings);
+    std::fs::write(&settings_path, serde_json::to_string_pretty(&settings)?)?;
+    Ok(())
+}
+
+/// Edit the hook lists of one event, then drop matchers and events left empty
+fn edit_hooks(dest_dir: &Path, event: &str, edit: impl Fn(&mut Vec<Value>)) -> Result<()> {
+    edit_settings(dest_dir, |settings| {
+        let Some(events) = settings.get_mut("hooks").and_then(|h| h.as_object_mut()) else {
+            return;
+        };
+        if let Some(matchers) = events.get_mut(event).and_then(|m| m.as_array_mut()) {
+            for matcher in matchers.iter_mut() {
+                if let Some(hooks) = matcher.get_mut("hooks").and_then(|h| h.as_array_mut()) {
+                    edit(hooks);
+                }
+            }
+            matchers.retain(|m| m.get("hooks").and_then(|h| h.as_array()).is_none_or(|h| !h.is_empty()));
+            if matchers.is_empty() {
+                events.remove(event);
+            }
+        }
+        if events.is_empty()
+            && let Some(obj) = settings.as_object_mut()
+        {
+            obj.remove("hooks");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use crate::component::InstallStatus;
+
+    #[test]
+    fn test_missing_hook_binary_is_reinstalled_or_removed() {
+        let dest = temp_dir("doctor-hooks").join(".claude");
+        std::fs::create_dir_all(&dest).unwrap();
+        let source = dest.parent().unwrap().join("source-hook_linux");
+        std::fs::write(&source, "#!/bin/sh\n").unwrap();
+        std::fs::write(
+            dest.join("settings.json"),
+            r#"{"hooks":{"Stop":[{"hooks":[
+                {"type":"command","command":"~/.claude/hooks/known_linux","timeout":0},
+                {"type":"command","command":"~/.claude/hooks/gone_linux"}
+            ]}]},"outputStyle":"Missing","statusLine":"statusline_linux"}"#,
+        )
+        .unwrap();
+
+        let components = vec![Component::new(
+            ComponentType::Hooks,
+            "known".to_string(),
+            source,
+            dest.join("hooks/known_linux"),
+            InstallStatus::New,
+        )];
+        let findings = check(&dest, &components, &[], &[]);
+        let fixes: Vec<Option<Fix>> = findings.iter().map(|f| f.fix.clone()).collect();
+
+        assert!(fixes.contains(&Some(Fix::ClearHookTimeout { event: "Stop".into(), command: "~/.claude/hooks/known_linux".into() })));
+        assert!(fixes.contains(&Some(Fix::Reinstall(0))));
+        assert!(fixes.contains(&Some(Fix::RemoveHook { event: "Stop".into(), command: "~/.claude/hooks/gone_linux".into() })));
+        assert!(fixes.contains(&Some(Fix::UnsetOutputStyle)));
+        assert!(fixes.contains(&Some(Fix::SetStatusline("statusline_linux".into()))));
+
+        for fix in fixes.iter().flatten() {
+            if !matches!(fix, Fix::SetStatusline(_)) {
+                apply(fix, dest.parent().unwrap(), &dest, &components, &[], TargetCli::Claude).unwrap();
+            }
+        }
+        let remaining = check(&dest, &components, &[], &[]);
+        assert_eq!(remaining.len(), 1, "{:?}", remaining.iter().map(|f| &f.message).collect::<Vec<_>>());
+        assert_eq!(remaining[0].area, "statusline");
+
+        let _ = std::fs::remove_dir_all(dest.parent().unwrap());
+    }
+}
@@ -0,0 +1,246 @@
+//! settings.json `env` block: repo value, local value and catalog notes per key
+//!
+//! The catalog lives in `<source>/env/env.yaml`:
+//!
+//! ```yaml
+//! vars:
+//!   - name: MCP_TIMEOUT
+//!     type: number              # number, bool or string (default: type of the repo value)
+//!     description: MCP server startup timeout in ms
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EnvType {
+    Number,
+    Bool,
+    String,
+}
+
+impl EnvType {
+    pub fn display(&self) -> &str {
+        match self {
+            Self::Number => "number",
+            Self::Bool => "bool",
+            Self::String => "string",
+        }
+    }
+
+    fn of_value(value: &Value) -> Self {
+        match value {
+            Value::Number(_) => Self::Number,
+            Value::Bool(_) => Self::Bool,
+            _ => Self::String,
+        }
+    }
+
+    /// Parse user input into the JSON value written to settings.json
+    pub fn parse(&self, input: &str) -> Result<Value, String> {
+        let input = input.trim();
+        match self {
+            Self::Number => {
+                if let Ok(n) = input.parse::<i64>() {
+                    Ok(Value::from(n))
+                } else {
+                    input
+                        .parse::<f64>()
+                        .ok()
+                        .and_then(serde_json::Number::from_f64)
+                        .map(Value::Number)
+                        .ok_or_else(|| format!("'{}' is not a number", input))
+                }
+            }
+            Self::Bool => match input {
+                "true" => Ok(Value::Bool(true)),
+                "false" => Ok(Value::Bool(false)),
+                _ => Err(format!("'{}' is not true or false", input)),
+            },
+            Self::String => Ok(Value::String(input.to_string())),
+        }
+    }
+
+    /// Whether a value already in settings.json fits the type (numbers and bools may be quoted)
+    fn accepts(&self, value: &Value) -> bool {
+        match (self, value) {
+            (Self::String, Value::String(_)) => true,
+            (Self::Number, Value::Number(_)) | (Self::Bool, Value::Bool(_)) => true,
+            (Self::Number | Self::Bool, Value::String(s)) => self.parse(s).is_ok(),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EnvVarDef {
+    pub name: String,
+    #[serde(rename = "type", default)]
+    pub kind: Option<EnvType>,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct EnvCatalog {
+    #[serde(default)]
+    vars: Vec<EnvVarDef>,
+}
+
+#[derive(Clone, Debug)]
+pub struct EnvVar {
+    pub name: String,
+    pub kind: EnvType,
+    pub description: Option<String>,
+    pub repo: Option<Value>,  // Value in the source settings.json
+    pub local: Option<Value>, // Value in the installed settings.json
+}
+
+impl EnvVar {
+    pub fn differs(&self) -> bool {
+        self.local.is_some() && self.local != self.repo
+    }
+
+    /// Type problem with the local value
+    pub fn error(&self) -> Option<String> {
+        let local = self.local.as_ref()?;
+        (!self.kind.accepts(local)).then(|| format!("expected {}", self.kind.display()))
+    }
+}
+
+/// Catalog entries from all source layers (a later layer replaces the same name)
+pub fn load_catalog(sources: &[PathBuf]) -> Result<Vec<EnvVarDef>> {
+    let mut vars: Vec<EnvVarDef> = Vec::new();
+    for source_dir in sources {
+        let path = source_dir.join("env").join("env.yaml");
+        if !path.exists() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let catalog: EnvCatalog = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid env catalog: {}", path.display()))?;
+        for def in catalog.vars {
+            match vars.iter_mut().find(|v| v.name == def.name) {
+                Some(existing) => *existing = def,
+                None => vars.push(def),
+            }
+        }
+    }
+    Ok(vars)
+}
+
+/// Every key in the repo or local `env` block or the catalog, sorted by name
+pub fn collect(catalog: &[EnvVarDef], repo_settings: Option<&Path>, local_settings: &Path) -> Vec<EnvVar> {
+    let repo = repo_settings.map(read_env).unwrap_or_default();
+    let local = read_env(local_settings);
+
+    let mut names: Vec<&String> = repo.keys().chain(local.keys()).chain(catalog.iter().map(|d| &d.name)).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .map(|name| {
+            let def = catalog.iter().find(|d| &d.name == name);
+            let repo_value = repo.get(name).cloned();
+            let local_value = local.get(name).cloned();
+            let kind = def
+                .and_then(|d| d.kind)
+                .or_else(|| repo_value.as_ref().or(local_value.as_ref()).map(EnvType::of_value))
+                .unwrap_or(EnvType::String);
+            EnvVar {
+                name: name.clone(),
+                kind,
+                description: def.and_then(|d| d.description.clone()),
+                repo: repo_value,
+                local: local_value,
+            }
+        })
+        .collect()
+}
+
+/// Value as shown and edited (strings without quotes)
+pub fn format_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Set (or with None, remove) a key in the `env` block of a settings file
+pub fn set_local(settings_path: &Path, name: &str, value: Option<Value>) -> Result<()> {
+    let mut settings: Value = if settings_path.exists() {
+        let content = std::fs::read_to_string(settings_path)?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid JSON: {}", settings_path.display()))?
+    } else {
+        Value::Object(Map::new())
+    };
+    let Some(root) = settings.as_object_mut() else {
+        anyhow::bail!("{} is not a JSON object", settings_path.display());
+    };
+
+    let env = root.entry("env").or_insert_with(|| Value::Object(Map::new()));
+    let Some(env) = env.as_object_mut() else {
+        anyhow::bail!("env in {} is not an object", settings_path.display());
+    };
+    match value {
+        Some(value) => {
+            env.insert(name.to_string(), value);
+        }
+        None => {
+            env.remove(name);
+        }
+    }
+    if env.is_empty() {
+        root.remove("env");
+    }
+
+    if let Some(parent) = settings_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(settings_path, serde_json::to_string_pretty(&settings)?)?;
+    Ok(())
+}
+
+fn read_env(settings_path: &Path) -> Map<String, Value> {
+    std::fs::read_to_string(settings_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<Value>(&content).ok())
+        .and_then(|settings| settings.get("env").and_then(|e| e.as_object()).cloned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_types_and_collect() {
+        assert_eq!(EnvType::Number.parse("64000"), Ok(Value::from(64000)));
+        assert!(EnvType::Number.parse("64k").is_err());
+        assert_eq!(EnvType::Bool.parse("true"), Ok(Value::Bool(true)));
+        assert!(EnvType::Bool.parse("yes").is_err());
+
+        let dir = temp_dir("env-collect");
+        let (repo, local) = (dir.join("repo.json"), dir.join("local.json"));
+        std::fs::write(&repo, r#"{"env":{"MCP_TIMEOUT":10000,"ENABLE_TOOL_SEARCH":true}}"#).unwrap();
+        std::fs::write(&local, r#"{"env":{"MCP_TIMEOUT":"abc"}}"#).unwrap();
+        let catalog = vec![EnvVarDef { name: "DISABLE_TELEMETRY".to_string(), kind: Some(EnvType::Bool), description: None }];
+
+        let vars = collect(&catalog, Some(&repo), &local);
+        let names: Vec<&str> = vars.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, ["DISABLE_TELEMETRY", "ENABLE_TOOL_SEARCH", "MCP_TIMEOUT"]);
+        assert_eq!(vars[2].error(), Some("expected number".to_string()));
+
+        set_local(&local, "MCP_TIMEOUT", None).unwrap();
+        assert_eq!(std::fs::read_to_string(&local).unwrap(), "{}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
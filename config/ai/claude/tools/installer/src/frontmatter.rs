@@ -0,0 +1,136 @@
+use anyhow::Result;
+use serde_yaml::{Mapping, Value};
+
+/// Markdown frontmatter fields used by agents, skills, commands and output styles
+#[derive(Clone, Debug, Default)]
+pub struct Frontmatter {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub tools: Vec<String>,
+    pub model: Option<String>,
+    pub requires: Vec<String>,
+}
+
+impl Frontmatter {
+    fn from_mapping(raw: &Mapping) -> Self {
+        let get_str = |key: &str| {
+            raw.get(key)
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        Self {
+            name: get_str("name"),
+            description: get_str("description"),
+            keywords: string_list(raw.get("keywords")),
+            tools: string_list(raw.get("tools")),
+            model: get_str("model"),
+            requires: string_list(raw.get("requires")),
+        }
+    }
+}
+
+/// Split a markdown document into (yaml, body)
+/// Returns None when the document has no frontmatter block
+pub fn split_frontmatter(content: &str) -> Option<Result<(&str, &str)>> {
+    let content = content.trim_start_matches('\u{feff}').trim_start();
+
+    if !content.starts_with("---") {
+        return None;
+    }
+
+    let after_first = &content[3..];
+    let Some(end_pos) = after_first.find("\n---") else {
+        return Some(Err(anyhow::anyhow!("Frontmatter is not closed with '---'")));
+    };
+
+    let yaml_str = &after_first[..end_pos];
+    let rest = &after_first[end_pos + 4..];
+    // Skip the remainder of the closing '---' line
+    let body = rest.split_once('\n').map(|(_, b)| b).unwrap_or("");
+
+    Some(Ok((yaml_str, body)))
+}
+
+/// Parse the frontmatter of a markdown document
+/// Ok(None) = no frontmatter, Err = frontmatter present but invalid
+pub fn parse_frontmatter(content: &str) -> Result<Option<(Frontmatter, &str)>> {
+    let (yaml_str, body) = match split_frontmatter(content) {
+        None => return Ok(None),
+        Some(split) => split?,
+    };
+
+    let value: Value = serde_yaml::from_str(yaml_str)
+        .map_err(|e| anyhow::anyhow!("Invalid YAML frontmatter: {}", e))?;
+
+    let mapping = match value {
+        Value::Mapping(m) => m,
+        Value::Null => Mapping::new(),
+        _ => anyhow::bail!("Frontmatter must be a YAML mapping"),
+    };
+
+    Ok(Some((Frontmatter::from_mapping(&mapping), body)))
+}
+
+/// Accept both YAML lists and comma-separated strings
+/// e.g., `tools: Read, Grep` or `tools: [Read, Grep]`
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items
+            .iter()
+            .filter_map(|v| match v {
+                Value::String(s) => Some(s.trim().to_string()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Some(Value::String(s)) => s
+            .split(',')
+            .map(|part| part.trim().to_string())
+            .filter(|part| !part.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_agent_frontmatter() {
+        let content = "---\nname: architect\ndescription: Software architecture specialist\ntools: Read, Grep, Glob\nmodel: opus\n---\n\nYou are a senior software architect.\n";
+
+        let (fm, body) = parse_frontmatter(content).unwrap().unwrap();
+        assert_eq!(fm.name.as_deref(), Some("architect"));
+        assert_eq!(fm.description.as_deref(), Some("Software architecture specialist"));
+        assert_eq!(fm.tools, vec!["Read", "Grep", "Glob"]);
+        assert_eq!(fm.model.as_deref(), Some("opus"));
+        assert!(fm.keywords.is_empty());
+        assert_eq!(body.trim(), "You are a senior software architect.");
+    }
+
+    #[test]
+    fn test_parse_keyword_list() {
+        let content = "---\nname: Code Standards\nkeywords: [코드 스타일, code style, lint]\n---\nBody";
+
+        let (fm, body) = parse_frontmatter(content).unwrap().unwrap();
+        assert_eq!(fm.keywords, vec!["코드 스타일", "code style", "lint"]);
+        assert_eq!(body, "Body");
+    }
+
+    #[test]
+    fn test_no_frontmatter() {
+        assert!(parse_frontmatter("# Title\n\ntext").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalid_frontmatter() {
+        assert!(parse_frontmatter("---\nname: [unclosed\n---\nbody").is_err());
+        assert!(parse_frontmatter("---\nname: test\nbody without end").is_err());
+        assert!(parse_frontmatter("---\n- just\n- a list\n---\nbody").is_err());
+    }
+}
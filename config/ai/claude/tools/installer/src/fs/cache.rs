@@ -0,0 +1,145 @@
+//! Content hashes of scanned files, kept between runs
+//!
+//! Entries are keyed by path and reused while the file's mtime and size are unchanged,
+//! so a rescan only reads files that were touched. A file hashed within a second of its
+//! last write could be rewritten at the same size without its mtime moving (coarse
+//! filesystem timestamps), so such entries are hashed again on the next scan. Stored in
+//! `~/.cache/claude-installer/scan-cache.json`.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry {
+    mtime: u128, // Nanoseconds since the epoch
+    size: u64,
+    hash: u64,
+    #[serde(default)]
+    hashed: u128, // When the hash was taken, nanoseconds since the epoch
+}
+
+/// Writes closer than this to the hashing time may not show in the mtime
+const RACY: Duration = Duration::from_secs(1);
+
+impl Entry {
+    fn matches(&self, mtime: u128, size: u64) -> bool {
+        mtime != 0 && self.mtime == mtime && self.size == size && self.hashed >= mtime + RACY.as_nanos()
+    }
+}
+
+fn nanos(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0)
+}
+
+#[derive(Default)]
+pub struct ScanCache {
+    path: Option<PathBuf>,
+    old: HashMap<PathBuf, Entry>,
+    seen: Mutex<HashMap<PathBuf, Entry>>, // Entries used by this scan (what gets saved)
+}
+
+pub fn cache_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|d| d.join("claude-installer").join("scan-cache.json"))
+}
+
+impl ScanCache {
+    /// Load the cache file (missing or unreadable = empty)
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let old = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, old, seen: Mutex::default() }
+    }
+
+    /// Content hash of a file, read only when it is not cached for its mtime and size
+    pub fn hash(&self, path: &Path) -> Result<u64> {
+        let meta = std::fs::metadata(path)?;
+        let mtime = meta.modified().map(nanos).unwrap_or(0);
+
+        let entry = match self.old.get(path) {
+            Some(entry) if entry.matches(mtime, meta.len()) => *entry,
+            _ => Entry { mtime, size: meta.len(), hash: hash_file(path)?, hashed: nanos(SystemTime::now()) },
+        };
+        let hash = entry.hash;
+        self.seen.lock().unwrap_or_else(|e| e.into_inner()).insert(path.to_path_buf(), entry);
+        Ok(hash)
+    }
+
+    /// Write the entries used by this scan (files no longer scanned drop out)
+    pub fn save(self) -> Result<()> {
+        let Some(path) = self.path else {
+            return Ok(());
+        };
+        let seen = self.seen.into_inner().unwrap_or_else(|e| e.into_inner());
+        if seen == self.old {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string(&seen)?)?;
+        Ok(())
+    }
+}
+
+/// 64-bit FNV-1a of the file contents (stable across builds, unlike `DefaultHasher`)
+fn hash_file(path: &Path) -> Result<u64> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for &byte in &buf[..n] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_cache_reuses_and_refreshes() {
+        let dir = temp_dir("cache-reuse");
+        let (file, cache_file) = (dir.join("a.md"), dir.join("cache.json"));
+        std::fs::write(&file, "one").unwrap();
+
+        let cache = ScanCache::load(Some(cache_file.clone()));
+        let first = cache.hash(&file).unwrap();
+        cache.save().unwrap();
+
+        // Hashed right after the write: the file could change again without its mtime moving
+        let cache = ScanCache::load(Some(cache_file.clone()));
+        let entry = cache.old[&file];
+        let racy = ScanCache { old: HashMap::from([(file.clone(), Entry { hash: 42, ..entry })]), ..ScanCache::default() };
+        assert_eq!(racy.hash(&file).unwrap(), first);
+
+        // Same mtime and size, hashed well after the write: the cached hash is used even
+        // though the bytes differ
+        let settled = Entry { hash: 42, hashed: entry.mtime + 2 * RACY.as_nanos(), ..entry };
+        let stored = ScanCache { old: HashMap::from([(file.clone(), settled)]), ..cache };
+        assert_eq!(stored.hash(&file).unwrap(), 42);
+
+        // A different size is re-read
+        std::fs::write(&file, "three").unwrap();
+        assert_ne!(stored.hash(&file).unwrap(), first);
+        assert_ne!(stored.hash(&file).unwrap(), 42);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
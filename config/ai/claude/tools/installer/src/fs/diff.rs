@@ -62,11 +62,16 @@ pub fn compare_files(source: &Path, dest: &Path) -> Result<String> {
         return Ok(output);
     }
 
-    let diff = TextDiff::from_lines(&dest_content, &source_content);
+    Ok(diff_text(&dest_content, &source_content, &dest.display().to_string(), &source.display().to_string()))
+}
+
+/// Line diff of two texts in the format of `compare_files`
+pub fn diff_text(old: &str, new: &str, old_label: &str, new_label: &str) -> String {
+    let diff = TextDiff::from_lines(old, new);
 
     let mut output = String::new();
-    output.push_str(&format!("--- {}\n", dest.display()));
-    output.push_str(&format!("+++ {}\n", source.display()));
+    output.push_str(&format!("--- {}\n", old_label));
+    output.push_str(&format!("+++ {}\n", new_label));
 
     for change in diff.iter_all_changes() {
         let sign = match change.tag() {
@@ -78,5 +83,5 @@ pub fn compare_files(source: &Path, dest: &Path) -> Result<String> {
         output.push_str(change.value());
     }
 
-    Ok(output)
+    output
 }
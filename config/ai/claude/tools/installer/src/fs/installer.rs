@@ -1,20 +1,57 @@
 use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
 use anyhow::Result;
 use serde_json::Value;
 
 use crate::app::TargetCli;
 use crate::component::{Component, ComponentType, HookConfig};
+use crate::config_file::MergeStrategy;
+use crate::conflict;
 use crate::mcp::{McpServer, McpScope};
-use crate::plugin::Plugin;
-use super::{create_claude_command, create_cli_command};
+use crate::plugin::{parse_marketplace_list, Plugin};
+use super::{create_claude_command, create_cli_command, merge};
+
+/// Serializes settings.json and Codex config.toml read-modify-write cycles across install
+/// workers, including `codex mcp add/remove`, which rewrites config.toml
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// `claude mcp add/remove` reads and rewrites all of ~/.claude.json (or the project's
+/// .mcp.json), so two at once lose a registration; they stay one at a time, while file
+/// copies and plugin installs run alongside
+static CLAUDE_JSON_LOCK: Mutex<()> = Mutex::new(());
+
+/// Registering a marketplace lists, then adds: two plugins of a new marketplace would both add it
+static MARKETPLACE_LOCK: Mutex<()> = Mutex::new(());
+
+fn lock(mutex: &'static Mutex<()>) -> MutexGuard<'static, ()> {
+    // A panicked worker cannot leave a file half-written (writes are whole-file)
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn lock_settings() -> MutexGuard<'static, ()> {
+    lock(&SETTINGS_LOCK)
+}
+
+/// Lock for a `mcp add/remove` of `target_cli`, held only while the command runs
+fn lock_mcp_config(target_cli: TargetCli) -> MutexGuard<'static, ()> {
+    match target_cli {
+        TargetCli::Claude => lock(&CLAUDE_JSON_LOCK),
+        TargetCli::Codex => lock_settings(),
+    }
+}
 
 pub fn install_component(component: &Component, _source_dir: &Path, dest_dir: &Path) -> Result<()> {
     match &component.component_type {
         ComponentType::Hooks => {
+            // Placeholder for a hook.yaml that did not parse (reported as a lint error)
+            if component.hook_config.is_none() && component.source_path.ends_with("hook.yaml") {
+                anyhow::bail!("Invalid hook.yaml (see lint)");
+            }
             // Copy hook binary
             copy_file(component)?;
             // Register hook in settings.json using hook_config
             if let Some(config) = &component.hook_config {
+                let _settings = lock_settings();
                 register_hook_in_settings(dest_dir, config)?;
             }
         }
@@ -22,17 +59,34 @@ pub fn install_component(component: &Component, _source_dir: &Path, dest_dir: &P
             // Copy output style file
             copy_file(component)?;
             // Auto-register in settings.json if no style is currently set
+            let _settings = lock_settings();
             register_output_style_in_settings(dest_dir, &component.name)?;
         }
         ComponentType::Statusline => {
             // Copy statusline file
             copy_file(component)?;
             // Auto-register in settings.json if no statusline is currently set
+            let _settings = lock_settings();
             register_statusline_in_settings(dest_dir, &component.name)?;
         }
-        ComponentType::ConfigFile if component.name == "settings.json" => {
-            // Merge settings.json instead of overwriting
-            merge_settings_json(&component.source_path, &component.dest_path)?;
+        ComponentType::ConfigFile => {
+            // Per-file merge strategy (settings.json is deep-merged by default)
+            let _settings = lock_settings();
+            let def = component.config_file_def();
+            let mut choices = conflict::load(dest_dir, &component.name);
+            let reviewed = choices.len();
+            // Conflicts nobody reviewed keep the local value; remember that so the file reads as converged
+            for c in merge::conflicts(&def, &component.source_path, &component.dest_path)? {
+                choices.entry(c.key).or_default();
+            }
+            let mut ledger = conflict::load_ledger(dest_dir, &component.name).unwrap_or_default();
+            merge::install(&def, &component.source_path, &component.dest_path, &choices, &mut ledger)?;
+            if choices.len() != reviewed {
+                conflict::save(dest_dir, &component.name, &choices)?;
+            }
+            if matches!(def.strategy, MergeStrategy::JsonMerge | MergeStrategy::TomlMerge) {
+                conflict::save_ledger(dest_dir, &component.name, Some(ledger))?;
+            }
         }
         _ => {
             copy_file(component)?;
@@ -46,6 +100,7 @@ pub fn remove_component(component: &Component, dest_dir: &Path) -> Result<()> {
         ComponentType::Hooks => {
             // Unregister hook from settings.json using hook_config
             if let Some(config) = &component.hook_config {
+                let _settings = lock_settings();
                 unregister_hook_from_settings(dest_dir, config)?;
             }
             // Remove hook binary file
@@ -53,9 +108,14 @@ pub fn remove_component(component: &Component, dest_dir: &Path) -> Result<()> {
                 std::fs::remove_file(&component.dest_path)?;
             }
         }
-        ComponentType::ConfigFile if component.name == "settings.json" => {
-            // Remove installer-managed sections from settings.json instead of deleting the file
-            remove_managed_settings_sections(dest_dir)?;
+        ComponentType::ConfigFile => {
+            // Take out only what the source contributed
+            let _settings = lock_settings();
+            let ledger = conflict::load_ledger(dest_dir, &component.name);
+            merge::remove(&component.config_file_def(), &component.source_path, &component.dest_path, ledger.as_ref())?;
+            if ledger.is_some() {
+                conflict::save_ledger(dest_dir, &component.name, None)?;
+            }
         }
         _ => {
             // Remove file
@@ -153,7 +213,11 @@ pub fn install_mcp_server(server: &McpServer, scope: McpScope, project_path: Opt
     }
 
     // Execute command (capture output to avoid TUI corruption)
-    let output = command.output()?;
+    super::log_command(&command);
+    let output = {
+        let _config = lock_mcp_config(target_cli);
+        super::run_command(&mut command)?
+    };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -169,7 +233,11 @@ pub fn remove_mcp_server(server: &McpServer, target_cli: TargetCli) -> Result<()
     command.args(["mcp", "remove", &server.def.name]);
 
     // Capture output to avoid TUI corruption
-    let output = command.output()?;
+    super::log_command(&command);
+    let output = {
+        let _config = lock_mcp_config(target_cli);
+        super::run_command(&mut command)?
+    };
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
@@ -178,20 +246,47 @@ pub fn remove_mcp_server(server: &McpServer, target_cli: TargetCli) -> Result<()
     Ok(())
 }
 
+/// claude mcp remove --scope <scope> <name>; local scope runs in the project dir
+pub fn remove_mcp_server_from(server: &McpServer, scope: McpScope, project_path: Option<&str>) -> Result<()> {
+    let mut command = create_claude_command();
+    command.args(["mcp", "remove", "--scope", scope.display(), &server.def.name]);
+    if let Some(path) = project_path {
+        command.current_dir(path);
+    }
+
+    super::log_command(&command);
+    let output = {
+        let _config = lock_mcp_config(TargetCli::Claude);
+        super::run_command(&mut command)?
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("Failed to remove MCP server {} ({} scope): {}", server.def.name, scope.display(), stderr.trim());
+    }
+    Ok(())
+}
+
 pub fn install_plugin(plugin: &Plugin) -> Result<String> {
     // First, ensure the marketplace is added
-    ensure_marketplace_added(plugin)?;
+    {
+        let _marketplaces = lock(&MARKETPLACE_LOCK);
+        ensure_marketplace_added(plugin)?;
+    }
+
+    // The install itself (the slow clone) runs in parallel with other workers
 
     // Build command: claude plugin install plugin@marketplace
-    let plugin_ref = format!("{}@{}", plugin.def.name, plugin.def.marketplace);
+    let plugin_ref = plugin.def.id();
     let mut command = create_claude_command();
     command.args(["plugin", "install", &plugin_ref]);
 
-    let output = command.output()?;
+    super::log_command(&command);
+    let output = super::run_command(&mut command)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to install plugin {}: {}", plugin.def.name, stderr.trim());
+        anyhow::bail!("Failed to install plugin {}: {}", plugin_ref, stderr.trim());
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -202,41 +297,73 @@ fn ensure_marketplace_added(plugin: &Plugin) -> Result<()> {
     // Check if marketplace is already added
     let mut list_cmd = create_claude_command();
     list_cmd.args(["plugin", "marketplace", "list"]);
-    let list_output = list_cmd.output()?;
+    let list_output = super::run_command(&mut list_cmd)?;
 
     if list_output.status.success() {
         let stdout = String::from_utf8_lossy(&list_output.stdout);
-        // Check if marketplace name appears in the list
-        if stdout.contains(&plugin.def.marketplace) {
+        // Check if marketplace name appears in the list (exact match)
+        if parse_marketplace_list(&stdout).contains(&plugin.def.marketplace) {
             return Ok(()); // Already added
         }
     }
 
-    // Add the marketplace: claude plugin marketplace add <source-url>
+    // Checkouts found only on disk have no source to add them from
+    if plugin.def.source.is_empty() {
+        anyhow::bail!(
+            "Marketplace {} is not registered and has no source (add it to plugins.yaml)",
+            plugin.def.marketplace
+        );
+    }
+
+    add_marketplace(&plugin.def.source)
+        .map_err(|e| anyhow::anyhow!("Failed to add marketplace {}: {}", plugin.def.marketplace, e))
+}
+
+/// claude plugin marketplace add <source-url>
+pub fn add_marketplace(source: &str) -> Result<()> {
+    let mut command = create_claude_command();
+    command.args(["plugin", "marketplace", "add", source]);
+
+    super::log_command(&command);
+    let output = super::run_command(&mut command)?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.trim());
+    }
+
+    Ok(())
+}
+
+/// claude plugin marketplace update <name> (re-clones a missing checkout)
+pub fn update_marketplace(name: &str) -> Result<()> {
     let mut command = create_claude_command();
-    command.args(["plugin", "marketplace", "add", &plugin.def.source]);
+    command.args(["plugin", "marketplace", "update", name]);
 
-    let output = command.output()?;
+    super::log_command(&command);
+    let output = super::run_command(&mut command)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to add marketplace {}: {}", plugin.def.marketplace, stderr.trim());
+        anyhow::bail!("Failed to update marketplace {}: {}", name, stderr.trim());
     }
 
     Ok(())
 }
 
 pub fn remove_plugin(plugin: &Plugin) -> Result<()> {
-    // Build command: claude plugin uninstall <name>
+    // Build command: claude plugin uninstall plugin@marketplace
+    let plugin_ref = plugin.def.id();
     let mut command = create_claude_command();
-    command.args(["plugin", "uninstall", &plugin.def.name]);
+    command.args(["plugin", "uninstall", &plugin_ref]);
 
     // Capture output to avoid TUI corruption
-    let output = command.output()?;
+    super::log_command(&command);
+    let output = super::run_command(&mut command)?;
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("Failed to remove plugin {}: {}", plugin.def.name, stderr.trim());
+        anyhow::bail!("Failed to remove plugin {}: {}", plugin_ref, stderr.trim());
     }
     Ok(())
 }
@@ -338,80 +465,6 @@ pub fn unset_statusline(dest_dir: &Path) -> Result<()> {
     Ok(())
 }
 
-fn merge_settings_json(source: &Path, dest: &Path) -> Result<()> {
-    let source_content = std::fs::read_to_string(source)?;
-    let source_json: Value = serde_json::from_str(&source_content)?;
-
-    let merged = if dest.exists() {
-        let dest_content = std::fs::read_to_string(dest)?;
-        let mut dest_json: Value = serde_json::from_str(&dest_content)?;
-
-        // Deep merge source into dest
-        merge_json_values(&mut dest_json, &source_json);
-        dest_json
-    } else {
-        source_json
-    };
-
-    // Create parent directory if needed
-    if let Some(parent) = dest.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    let output = serde_json::to_string_pretty(&merged)?;
-    std::fs::write(dest, output)?;
-
-    Ok(())
-}
-
-fn merge_json_values(dest: &mut Value, source: &Value) {
-    match (dest, source) {
-        (Value::Object(dest_map), Value::Object(source_map)) => {
-            for (key, source_value) in source_map {
-                match dest_map.get_mut(key) {
-                    Some(dest_value) => {
-                        // Special handling for hooks array - append instead of replace
-                        if key == "hooks" {
-                            merge_hooks(dest_value, source_value);
-                        } else {
-                            merge_json_values(dest_value, source_value);
-                        }
-                    }
-                    None => {
-                        dest_map.insert(key.clone(), source_value.clone());
-                    }
-                }
-            }
-        }
-        (dest, source) => {
-            *dest = source.clone();
-        }
-    }
-}
-
-fn merge_hooks(dest: &mut Value, source: &Value) {
-    if let (Value::Object(dest_hooks), Value::Object(source_hooks)) = (dest, source) {
-        for (hook_type, source_hook_array) in source_hooks {
-            match dest_hooks.get_mut(hook_type) {
-                Some(Value::Array(dest_array)) => {
-                    if let Value::Array(source_array) = source_hook_array {
-                        // Append source hooks that don't already exist
-                        for source_item in source_array {
-                            if !dest_array.contains(source_item) {
-                                dest_array.push(source_item.clone());
-                            }
-                        }
-                    }
-                }
-                None => {
-                    dest_hooks.insert(hook_type.clone(), source_hook_array.clone());
-                }
-                _ => {}
-            }
-        }
-    }
-}
-
 fn register_hook_in_settings(dest_dir: &Path, config: &HookConfig) -> Result<()> {
     let settings_path = dest_dir.join("settings.json");
 
@@ -540,41 +593,6 @@ pub fn unregister_hook_from_settings(dest_dir: &Path, config: &HookConfig) -> Re
     Ok(())
 }
 
-/// Removes installer-managed sections from settings.json
-/// This includes: hooks, outputStyle (if it's a known installed style), statusLine (if it's a known installed statusline)
-/// Preserves user settings like env, model, enabledPlugins, etc.
-pub fn remove_managed_settings_sections(dest_dir: &Path) -> Result<()> {
-    let settings_path = dest_dir.join("settings.json");
-
-    if !settings_path.exists() {
-        return Ok(());
-    }
-
-    let content = std::fs::read_to_string(&settings_path)?;
-    let mut settings: Value = serde_json::from_str(&content)?;
-
-    if let Value::Object(ref mut map) = settings {
-        // 1. Remove all hooks (installer-managed)
-        map.remove("hooks");
-
-        // 2. Remove outputStyle if it exists
-        // TODO: In the future, we could check if the style is actually installed
-        // by cross-referencing with the output-styles directory
-        map.remove("outputStyle");
-
-        // 3. Remove statusLine if it exists
-        // TODO: In the future, we could check if the statusline is actually installed
-        // by cross-referencing with the statusline directory
-        map.remove("statusLine");
-    }
-
-    // Write settings back
-    let output = serde_json::to_string_pretty(&settings)?;
-    std::fs::write(&settings_path, output)?;
-
-    Ok(())
-}
-
 /// Auto-register an output style in settings.json if no style is currently set
 fn register_output_style_in_settings(dest_dir: &Path, style_name: &str) -> Result<()> {
     let settings_path = dest_dir.join("settings.json");
@@ -0,0 +1,690 @@
+//! Install, diff and removal of config files per merge strategy (see config_file.rs)
+//!
+//! Merge strategies only ever add what the source contributes, and removal takes out
+//! exactly those contributions: JSON and TOML keys (and JSON array items) the install
+//! added, recorded in a ledger (see conflict.rs), while they still hold the installed
+//! value, or the managed markdown block. Anything the user changed stays. Keys where the
+//! local value differs are conflicts, resolved by `choices`; removal puts back the local
+//! value a "take repo" choice replaced. TOML values are recorded as their JSON equivalent.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use toml_edit::{DocumentMut, InlineTable, Item, Table};
+
+use super::diff;
+use crate::component::InstallStatus;
+use crate::config_file::{ConfigFileDef, MergeStrategy};
+use crate::conflict::{self, Choices, Conflict, Contribution, Ledger, Resolution};
+
+const BLOCK_BEGIN: &str = "<!-- BEGIN installer-managed -->";
+const BLOCK_END: &str = "<!-- END installer-managed -->";
+
+pub fn status(def: &ConfigFileDef, source: &Path, dest: &Path, choices: &Choices) -> Result<InstallStatus> {
+    let Some(current) = read_dest(dest)? else {
+        return Ok(InstallStatus::New);
+    };
+
+    let status = match def.strategy {
+        MergeStrategy::Overwrite => {
+            if std::fs::read(source)? == current.as_bytes() {
+                InstallStatus::Unchanged
+            } else {
+                InstallStatus::Modified
+            }
+        }
+        MergeStrategy::JsonMerge => {
+            let current = parse_json(&current, dest)?;
+            let source = parse_json(&read_source(source)?, source)?;
+            let mut merged = current.clone();
+            merge_json_values(&mut merged, &source, choices);
+            if conflict::find(&current, &source).iter().any(|c| !choices.contains_key(&c.key)) {
+                InstallStatus::Modified // Needs review
+            } else if merged == current {
+                InstallStatus::Unchanged
+            } else {
+                InstallStatus::Managed
+            }
+        }
+        MergeStrategy::TomlMerge => {
+            if conflicts(def, source, dest)?.iter().any(|c| !choices.contains_key(&c.key)) {
+                InstallStatus::Modified // Needs review
+            } else if render(def.strategy, source, Some(&current), choices, None)? == current {
+                InstallStatus::Unchanged
+            } else {
+                InstallStatus::Managed
+            }
+        }
+        MergeStrategy::MarkdownBlock => match find_block(&current) {
+            None => InstallStatus::New,
+            Some(_) if render(def.strategy, source, Some(&current), choices, None)? == current => InstallStatus::Unchanged,
+            Some(_) => InstallStatus::Modified,
+        },
+    };
+    Ok(status)
+}
+
+/// Diff between the dest file now and after install
+pub fn diff(def: &ConfigFileDef, source: &Path, dest: &Path, choices: &Choices) -> Result<String> {
+    if def.strategy == MergeStrategy::Overwrite {
+        return diff::compare_files(source, dest);
+    }
+
+    let current = read_dest(dest)?;
+    let after = render(def.strategy, source, current.as_deref(), choices, None)?;
+
+    // JSON is compared in its written form so key order and spacing do not show up
+    let before = match (def.strategy, &current) {
+        (MergeStrategy::JsonMerge, Some(text)) => serde_json::to_string_pretty(&parse_json(text, dest)?)?,
+        (_, Some(text)) => text.clone(),
+        (_, None) => String::new(),
+    };
+
+    let label = format!("{} ({})", dest.display(), def.strategy.display());
+    if before == after {
+        return Ok(format!("=== {} (no changes) ===\n\n{}", label, after));
+    }
+    Ok(diff::diff_text(&before, &after, &dest.display().to_string(), &label))
+}
+
+/// Install `source` into `dest`; JSON and TOML changes are added to `ledger`
+pub fn install(def: &ConfigFileDef, source: &Path, dest: &Path, choices: &Choices, ledger: &mut Ledger) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    if def.strategy == MergeStrategy::Overwrite {
+        std::fs::copy(source, dest)?;
+        return Ok(());
+    }
+
+    let current = read_dest(dest)?;
+    std::fs::write(dest, render(def.strategy, source, current.as_deref(), choices, Some(ledger))?)?;
+    Ok(())
+}
+
+/// Remove what `source` contributed; the file is deleted when nothing else is left.
+/// Without a ledger nothing is known to be the installer's, so merged keys all stay.
+pub fn remove(def: &ConfigFileDef, source: &Path, dest: &Path, ledger: Option<&Ledger>) -> Result<()> {
+    let Some(current) = read_dest(dest)? else {
+        return Ok(());
+    };
+    let ledger = ledger.cloned().unwrap_or_default();
+
+    let remaining = match def.strategy {
+        MergeStrategy::Overwrite => None,
+        MergeStrategy::JsonMerge => {
+            let mut value = parse_json(&current, dest)?;
+            unmerge_recorded(&mut value, &parse_json(&read_source(source)?, source)?, &ledger);
+            match value.as_object() {
+                Some(map) if map.is_empty() => None,
+                _ => Some(serde_json::to_string_pretty(&value)?),
+            }
+        }
+        MergeStrategy::TomlMerge => {
+            let mut doc = parse_toml(&current, dest)?;
+            unmerge_toml(doc.as_table_mut(), parse_toml(&read_source(source)?, source)?.as_table(), &ledger);
+            let text = doc.to_string();
+            (!text.trim().is_empty()).then_some(text)
+        }
+        MergeStrategy::MarkdownBlock => remove_block(&current),
+    };
+
+    match remaining {
+        Some(text) => std::fs::write(dest, text)?,
+        None => std::fs::remove_file(dest)?,
+    }
+    Ok(())
+}
+
+/// JSON and TOML keys where the dest file differs from the source (other strategies have none)
+pub fn conflicts(def: &ConfigFileDef, source: &Path, dest: &Path) -> Result<Vec<Conflict>> {
+    let Some(current) = read_dest(dest)? else {
+        return Ok(Vec::new());
+    };
+    match def.strategy {
+        MergeStrategy::JsonMerge => Ok(conflict::find(&parse_json(&current, dest)?, &parse_json(&read_source(source)?, source)?)),
+        MergeStrategy::TomlMerge => {
+            let mut found = Vec::new();
+            let source = parse_toml(&read_source(source)?, source)?;
+            collect_toml_conflicts(parse_toml(&current, dest)?.as_table(), source.as_table(), "", &mut found);
+            Ok(found)
+        }
+        MergeStrategy::Overwrite | MergeStrategy::MarkdownBlock => Ok(Vec::new()),
+    }
+}
+
+fn collect_toml_conflicts(local: &Table, repo: &Table, prefix: &str, found: &mut Vec<Conflict>) {
+    for (key, repo_item) in repo.iter() {
+        let path = conflict::key_path(prefix, key);
+        match (local.get(key), repo_item) {
+            (None, _) => {}
+            (Some(Item::Table(local_table)), Item::Table(repo_table)) => {
+                collect_toml_conflicts(local_table, repo_table, &path, found)
+            }
+            (Some(local_item), _) => {
+                let (local, repo) = (toml_to_json(local_item), toml_to_json(repo_item));
+                if local != repo {
+                    found.push(Conflict { key: path, local, repo });
+                }
+            }
+        }
+    }
+}
+
+/// A source key whose installed value differs (`local` is None when the key is missing;
+/// for arrays `repo` holds only the items missing locally)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Drift {
+    pub key: String,
+    pub repo: Value,
+    pub local: Option<Value>,
+}
+
+/// Managed JSON keys not matching the source (other strategies have none)
+pub fn drift(def: &ConfigFileDef, source: &Path, dest: &Path) -> Result<Vec<Drift>> {
+    if def.strategy != MergeStrategy::JsonMerge {
+        return Ok(Vec::new());
+    }
+    let local = match read_dest(dest)? {
+        Some(text) => parse_json(&text, dest)?,
+        None => serde_json::json!({}),
+    };
+    let mut found = Vec::new();
+    collect_drift(&local, &parse_json(&read_source(source)?, source)?, "", &mut found);
+    Ok(found)
+}
+
+fn collect_drift(local: &Value, repo: &Value, prefix: &str, found: &mut Vec<Drift>) {
+    let (Value::Object(local_map), Value::Object(repo_map)) = (local, repo) else {
+        return;
+    };
+
+    for (key, repo_value) in repo_map {
+        let path = conflict::key_path(prefix, key);
+        match (local_map.get(key), repo_value) {
+            (None, _) => found.push(Drift { key: path, repo: repo_value.clone(), local: None }),
+            (Some(local_value @ Value::Object(_)), Value::Object(_)) => {
+                collect_drift(local_value, repo_value, &path, found)
+            }
+            (Some(local_value @ Value::Array(local_array)), Value::Array(repo_array)) => {
+                let missing: Vec<Value> = repo_array.iter().filter(|i| !local_array.contains(i)).cloned().collect();
+                if !missing.is_empty() {
+                    found.push(Drift { key: path, repo: Value::Array(missing), local: Some(local_value.clone()) });
+                }
+            }
+            (Some(local_value), _) if local_value == repo_value => {}
+            (Some(local_value), _) => found.push(Drift { key: path, repo: repo_value.clone(), local: Some(local_value.clone()) }),
+        }
+    }
+}
+
+/// Dest content after installing `source` (merge strategies)
+fn render(strategy: MergeStrategy, source: &Path, current: Option<&str>, choices: &Choices, mut ledger: Option<&mut Ledger>) -> Result<String> {
+    let source_text = read_source(source)?;
+
+    match strategy {
+        MergeStrategy::Overwrite => Ok(source_text),
+        MergeStrategy::JsonMerge => {
+            let mut merged = match current {
+                Some(text) => serde_json::from_str(text).context("Invalid JSON in the installed file")?,
+                None => serde_json::json!({}),
+            };
+            merge_json_at(&mut merged, &parse_json(&source_text, source)?, &[], choices, &mut ledger);
+            Ok(serde_json::to_string_pretty(&merged)?)
+        }
+        MergeStrategy::TomlMerge => {
+            let mut doc = parse_toml(current.unwrap_or(""), source)?;
+            merge_toml(doc.as_table_mut(), parse_toml(&source_text, source)?.as_table(), &[], choices, &mut ledger);
+            Ok(doc.to_string())
+        }
+        MergeStrategy::MarkdownBlock => Ok(insert_block(current.unwrap_or(""), &source_text)),
+    }
+}
+
+fn read_dest(dest: &Path) -> Result<Option<String>> {
+    if !dest.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(dest).with_context(|| format!("Cannot read {}", dest.display()))?;
+    Ok(Some(text))
+}
+
+fn read_source(source: &Path) -> Result<String> {
+    std::fs::read_to_string(source).with_context(|| format!("Cannot read {}", source.display()))
+}
+
+fn parse_json(text: &str, path: &Path) -> Result<Value> {
+    serde_json::from_str(text).with_context(|| format!("Invalid JSON: {}", path.display()))
+}
+
+fn parse_toml(text: &str, path: &Path) -> Result<DocumentMut> {
+    text.parse().with_context(|| format!("Invalid TOML: {}", path.display()))
+}
+
+/// Deep merge: objects recurse, arrays merge as sets, and other values differing
+/// from the source change only when `choices` says to take the repo's
+pub fn merge_json_values(dest: &mut Value, source: &Value, choices: &Choices) {
+    merge_json_at(dest, source, &[], choices, &mut None);
+}
+
+fn merge_json_at(dest: &mut Value, source: &Value, path: &[String], choices: &Choices, ledger: &mut Option<&mut Ledger>) {
+    let (Value::Object(dest_map), Value::Object(source_map)) = (dest, source) else {
+        return;
+    };
+
+    for (key, source_value) in source_map {
+        let path = [path, std::slice::from_ref(key)].concat();
+        match (dest_map.get_mut(key), source_value) {
+            (None, _) => {
+                dest_map.insert(key.clone(), source_value.clone());
+                record(ledger, |l| &mut l.added, &path, source_value);
+            }
+            (Some(dest_value @ Value::Object(_)), Value::Object(_)) => {
+                merge_json_at(dest_value, source_value, &path, choices, ledger);
+            }
+            (Some(Value::Array(dest_array)), Value::Array(source_array)) => {
+                for item in source_array {
+                    if !dest_array.contains(item) {
+                        dest_array.push(item.clone());
+                        record(ledger, |l| &mut l.items, &path, item);
+                    }
+                }
+            }
+            (Some(dest_value), _) => {
+                if dest_value != source_value && choices.get(&path.join(".")) == Some(&Resolution::TakeRepo) {
+                    // Only the first replaced value is the user's own
+                    if let Some(ledger) = ledger
+                        && !ledger.replaced.iter().any(|c| c.path == path)
+                    {
+                        ledger.replaced.push(Contribution { path: path.clone(), value: dest_value.clone() });
+                    }
+                    *dest_value = source_value.clone();
+                }
+            }
+        }
+    }
+}
+
+fn record(ledger: &mut Option<&mut Ledger>, list: fn(&mut Ledger) -> &mut Vec<Contribution>, path: &[String], value: &Value) {
+    if let Some(ledger) = ledger {
+        list(ledger).push(Contribution { path: path.to_vec(), value: value.clone() });
+    }
+}
+
+/// Inverse of an install recorded in `ledger`: drop added keys and array items that still
+/// hold what was written, and put back local values replaced by "take repo" (unless the
+/// user changed the value since)
+fn unmerge_recorded(dest: &mut Value, source: &Value, ledger: &Ledger) {
+    for c in &ledger.replaced {
+        if let Some(current) = value_at_mut(dest, &c.path)
+            && Some(&*current) == value_at(source, &c.path)
+        {
+            *current = c.value.clone();
+        }
+    }
+
+    for c in &ledger.items {
+        if let Some(Value::Array(array)) = value_at_mut(dest, &c.path)
+            && let Some(pos) = array.iter().position(|item| item == &c.value)
+        {
+            array.remove(pos);
+        }
+    }
+
+    // Parents of added keys existed before the install, so they stay even when empty
+    for c in ledger.added.iter().rev() {
+        let Some((key, parent)) = c.path.split_last() else {
+            continue;
+        };
+        if let Some(Value::Object(map)) = value_at_mut(dest, parent)
+            && let Some(current) = map.get_mut(key)
+            && remove_contribution(current, &c.value)
+        {
+            map.remove(key);
+        }
+    }
+}
+
+/// Take `added` out of `current`; true when nothing else is left
+fn remove_contribution(current: &mut Value, added: &Value) -> bool {
+    if current == added {
+        return true;
+    }
+    match (current, added) {
+        (Value::Object(map), Value::Object(added_map)) => {
+            for (key, value) in added_map {
+                if let Some(item) = map.get_mut(key)
+                    && remove_contribution(item, value)
+                {
+                    map.remove(key);
+                }
+            }
+            map.is_empty()
+        }
+        (Value::Array(array), Value::Array(added_items)) => {
+            array.retain(|item| !added_items.contains(item));
+            array.is_empty()
+        }
+        _ => false,
+    }
+}
+
+fn value_at<'a>(value: &'a Value, path: &[String]) -> Option<&'a Value> {
+    path.iter().try_fold(value, |v, key| v.get(key))
+}
+
+fn value_at_mut<'a>(value: &'a mut Value, path: &[String]) -> Option<&'a mut Value> {
+    path.iter().try_fold(value, |v, key| v.get_mut(key))
+}
+
+/// Merge tables recursively; other items differing from the source change only when
+/// `choices` says to take the repo's. Added tables are recorded as `{}` ahead of their
+/// keys, so removal drops them once nothing else is left in them.
+fn merge_toml(dest: &mut Table, source: &Table, path: &[String], choices: &Choices, ledger: &mut Option<&mut Ledger>) {
+    for (key, item) in source.iter() {
+        let path = [path, &[key.to_string()]].concat();
+        match (dest.get_mut(key), item) {
+            (None, _) => {
+                dest.insert(key, item.clone());
+                record_toml(ledger, &path, item);
+            }
+            (Some(Item::Table(dest_table)), Item::Table(source_table)) => {
+                merge_toml(dest_table, source_table, &path, choices, ledger);
+            }
+            (Some(dest_item), _) => {
+                let local = toml_to_json(dest_item);
+                if local != toml_to_json(item) && choices.get(&path.join(".")) == Some(&Resolution::TakeRepo) {
+                    // Only the first replaced value is the user's own
+                    if let Some(ledger) = ledger
+                        && !ledger.replaced.iter().any(|c| c.path == path)
+                    {
+                        ledger.replaced.push(Contribution { path: path.clone(), value: local });
+                    }
+                    replace_toml(dest_item, item.clone());
+                }
+            }
+        }
+    }
+}
+
+fn record_toml(ledger: &mut Option<&mut Ledger>, path: &[String], item: &Item) {
+    let Item::Table(table) = item else {
+        return record(ledger, |l| &mut l.added, path, &toml_to_json(item));
+    };
+    record(ledger, |l| &mut l.added, path, &Value::Object(Default::default()));
+    for (key, child) in table.iter() {
+        record_toml(ledger, &[path, &[key.to_string()]].concat(), child);
+    }
+}
+
+/// Inverse of an install recorded in `ledger` (see `unmerge_recorded`)
+fn unmerge_toml(dest: &mut Table, source: &Table, ledger: &Ledger) {
+    for c in &ledger.replaced {
+        let Some((key, parent)) = c.path.split_last() else {
+            continue;
+        };
+        let repo = toml_table_at(source, parent).and_then(|t| t.get(key)).map(toml_to_json);
+        if let Some(current) = toml_table_at_mut(dest, parent).and_then(|t| t.get_mut(key))
+            && Some(toml_to_json(current)) == repo
+        {
+            replace_toml(current, json_to_toml(&c.value));
+        }
+    }
+
+    // Keys of an added table come after it, so the table is empty by the time it is checked
+    for c in ledger.added.iter().rev() {
+        let Some((key, parent)) = c.path.split_last() else {
+            continue;
+        };
+        if let Some(table) = toml_table_at_mut(dest, parent)
+            && table.get(key).is_some_and(|current| toml_to_json(current) == c.value)
+        {
+            table.remove(key);
+        }
+    }
+}
+
+/// Put `new` in place of `item`, keeping the spacing and comment around a value
+fn replace_toml(item: &mut Item, mut new: Item) {
+    if let (Some(old), Some(value)) = (item.as_value(), new.as_value_mut()) {
+        *value.decor_mut() = old.decor().clone();
+    }
+    *item = new;
+}
+
+fn toml_table_at<'a>(table: &'a Table, path: &[String]) -> Option<&'a Table> {
+    path.iter().try_fold(table, |t, key| t.get(key)?.as_table())
+}
+
+fn toml_table_at_mut<'a>(table: &'a mut Table, path: &[String]) -> Option<&'a mut Table> {
+    path.iter().try_fold(table, |t, key| t.get_mut(key)?.as_table_mut())
+}
+
+/// An item as JSON, for comparisons and the ledger (datetimes become strings)
+fn toml_to_json(item: &Item) -> Value {
+    match item {
+        Item::None => Value::Null,
+        Item::Value(value) => toml_value_to_json(value),
+        Item::Table(table) => Value::Object(table.iter().map(|(k, v)| (k.to_string(), toml_to_json(v))).collect()),
+        Item::ArrayOfTables(tables) => Value::Array(
+            tables.iter().map(|t| Value::Object(t.iter().map(|(k, v)| (k.to_string(), toml_to_json(v))).collect())).collect(),
+        ),
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => Value::from(*f.value()),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(array) => Value::Array(array.iter().map(toml_value_to_json).collect()),
+        toml_edit::Value::InlineTable(table) => {
+            Value::Object(table.iter().map(|(k, v)| (k.to_string(), toml_value_to_json(v))).collect())
+        }
+    }
+}
+
+/// Inverse of `toml_to_json`: objects become tables, nested ones inline
+fn json_to_toml(value: &Value) -> Item {
+    match value {
+        Value::Object(map) => {
+            let mut table = Table::new();
+            for (key, value) in map {
+                table.insert(key, json_to_toml(value));
+            }
+            Item::Table(table)
+        }
+        other => json_to_toml_value(other).map(Item::Value).unwrap_or(Item::None),
+    }
+}
+
+fn json_to_toml_value(value: &Value) -> Option<toml_edit::Value> {
+    Some(match value {
+        Value::Null => return None,
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64()?.into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(items) => items.iter().filter_map(json_to_toml_value).collect::<toml_edit::Array>().into(),
+        Value::Object(map) => {
+            map.iter().filter_map(|(k, v)| Some((k.as_str(), json_to_toml_value(v)?))).collect::<InlineTable>().into()
+        }
+    })
+}
+
+/// Byte range of the managed block, including its trailing newline
+fn find_block(text: &str) -> Option<(usize, usize)> {
+    let start = text.find(BLOCK_BEGIN)?;
+    let mut end = start + text[start..].find(BLOCK_END)? + BLOCK_END.len();
+    if text[end..].starts_with("\r\n") {
+        end += 2;
+    } else if text[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some((start, end))
+}
+
+fn insert_block(current: &str, source: &str) -> String {
+    let block = format!("{}\n{}\n{}\n", BLOCK_BEGIN, source.trim_end(), BLOCK_END);
+    match find_block(current) {
+        Some((start, end)) => format!("{}{}{}", &current[..start], block, &current[end..]),
+        None if current.trim().is_empty() => block,
+        None => format!("{}\n\n{}", current.trim_end(), block),
+    }
+}
+
+/// File content without the managed block (None = nothing else in the file)
+fn remove_block(current: &str) -> Option<String> {
+    let Some((start, end)) = find_block(current) else {
+        return Some(current.to_string());
+    };
+
+    let before = current[..start].trim_end();
+    let after = current[end..].trim_start_matches(['\r', '\n']);
+    match (before.is_empty(), after.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(format!("{}\n", before)),
+        (true, false) => Some(after.to_string()),
+        (false, false) => Some(format!("{}\n\n{}", before, after)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    /// Install into an existing user file, then remove: the user's file comes back
+    fn round_trip(name: &str, strategy: MergeStrategy, source: &str, user: &str) -> String {
+        let dir = temp_dir(name);
+        let (source_path, dest_path) = (dir.join("source"), dir.join("dest"));
+        std::fs::write(&source_path, source).unwrap();
+        std::fs::write(&dest_path, user).unwrap();
+        let def = ConfigFileDef { source: "source".into(), dest: None, strategy, target: None };
+
+        let choices = Choices::new();
+        let mut ledger = Ledger::default();
+        assert_ne!(status(&def, &source_path, &dest_path, &choices).unwrap(), InstallStatus::Unchanged);
+        install(&def, &source_path, &dest_path, &choices, &mut ledger).unwrap();
+        assert_eq!(status(&def, &source_path, &dest_path, &choices).unwrap(), InstallStatus::Unchanged);
+        let installed = std::fs::read_to_string(&dest_path).unwrap();
+
+        remove(&def, &source_path, &dest_path, Some(&ledger)).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest_path).unwrap(), user.replace("\r\n", "\n"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        installed
+    }
+
+    #[test]
+    fn test_json_merge_round_trip() {
+        let source = r#"{"env":{"A":"1"},"hooks":{"Stop":[{"hooks":[{"command":"x"}]}]}}"#;
+        let user = "{\n  \"hooks\": {\n    \"Stop\": [\n      {\n        \"hooks\": [\n          {\n            \"command\": \"mine\"\n          }\n        ]\n      }\n    ]\n  },\n  \"model\": \"opus\"\n}";
+        let installed = round_trip("json", MergeStrategy::JsonMerge, source, user);
+        assert!(installed.contains("\"A\": \"1\"") && installed.contains("\"mine\"") && installed.contains("\"x\""));
+    }
+
+    #[test]
+    fn test_json_conflicts_follow_choices() {
+        let source = serde_json::json!({"env": {"MCP_TIMEOUT": "60000", "A": "1"}, "model": "opus", "permissions": {"allow": ["Read", "Bash(ls:*)"]}});
+        let local = serde_json::json!({"env": {"MCP_TIMEOUT": "30000"}, "model": "sonnet", "permissions": {"allow": ["Read", "Edit"]}});
+
+        let keys: Vec<String> = conflict::find(&local, &source).into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, ["env.MCP_TIMEOUT", "model"]);
+
+        let choices = Choices::from([("model".to_string(), Resolution::TakeRepo)]);
+        let mut merged = local.clone();
+        merge_json_values(&mut merged, &source, &choices);
+        assert_eq!(merged["env"], serde_json::json!({"MCP_TIMEOUT": "30000", "A": "1"}));
+        assert_eq!(merged["model"], "opus");
+        assert_eq!(merged["permissions"]["allow"], serde_json::json!(["Read", "Edit", "Bash(ls:*)"]));
+    }
+
+    #[test]
+    fn test_json_drift() {
+        let dir = temp_dir("merge-drift");
+        let (source_path, dest_path) = (dir.join("source"), dir.join("dest"));
+        std::fs::write(&source_path, r#"{"model":"opus","env":{"A":"1","B":"2"},"allow":["Read","Edit"]}"#).unwrap();
+        std::fs::write(&dest_path, r#"{"model":"sonnet","env":{"A":"1"},"allow":["Read"],"mine":true}"#).unwrap();
+        let def = ConfigFileDef { source: "source".into(), dest: None, strategy: MergeStrategy::JsonMerge, target: None };
+
+        let found = drift(&def, &source_path, &dest_path).unwrap();
+        let keys: Vec<&str> = found.iter().map(|d| d.key.as_str()).collect();
+        assert_eq!(keys, ["allow", "env.B", "model"]);
+        assert_eq!(found[0].repo, serde_json::json!(["Edit"]));
+        assert_eq!(found[1].local, None);
+
+        let choices = Choices::from([("model".to_string(), Resolution::TakeRepo)]);
+        install(&def, &source_path, &dest_path, &choices, &mut Ledger::default()).unwrap();
+        assert!(drift(&def, &source_path, &dest_path).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_toml_merge_keeps_user_formatting() {
+        let source = "model = \"o3\"\n\n[mcp_servers.docs]\ncommand = \"npx\"\n";
+        let user = "# my settings\napproval_policy = \"never\"  # keep\n\n[mcp_servers.mine]\ncommand = \"uvx\"\n";
+        let installed = round_trip("toml", MergeStrategy::TomlMerge, source, user);
+        assert!(installed.starts_with("# my settings\napproval_policy = \"never\"  # keep\n"));
+        assert!(installed.contains("[mcp_servers.docs]"));
+    }
+
+    #[test]
+    fn test_toml_conflicts_follow_choices() {
+        let dir = temp_dir("merge-toml-conflicts");
+        let (source_path, dest_path) = (dir.join("source"), dir.join("dest"));
+        let user = "model = \"o4-mini\"  # mine\napproval_policy = \"never\"\n\n[tools]\nweb_search = false\n";
+        std::fs::write(&source_path, "model = \"o3\"\napproval_policy = \"never\"\n\n[tools]\nweb_search = true\nview_image = true\n").unwrap();
+        std::fs::write(&dest_path, user).unwrap();
+        let def = ConfigFileDef { source: "source".into(), dest: None, strategy: MergeStrategy::TomlMerge, target: None };
+
+        let keys: Vec<String> = conflicts(&def, &source_path, &dest_path).unwrap().into_iter().map(|c| c.key).collect();
+        assert_eq!(keys, ["model", "tools.web_search"]);
+        assert_eq!(status(&def, &source_path, &dest_path, &Choices::new()).unwrap(), InstallStatus::Modified);
+
+        let choices = Choices::from([
+            ("model".to_string(), Resolution::TakeRepo),
+            ("tools.web_search".to_string(), Resolution::KeepLocal),
+        ]);
+        let mut ledger = Ledger::default();
+        install(&def, &source_path, &dest_path, &choices, &mut ledger).unwrap();
+        let installed = std::fs::read_to_string(&dest_path).unwrap();
+        assert!(installed.starts_with("model = \"o3\"  # mine\n"));
+        assert!(installed.contains("web_search = false\nview_image = true"));
+        assert_eq!(ledger.replaced, [Contribution { path: vec!["model".to_string()], value: "o4-mini".into() }]);
+
+        // approval_policy was the user's before the install, so it stays
+        remove(&def, &source_path, &dest_path, Some(&ledger)).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest_path).unwrap(), user);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_remove_without_ledger_keeps_user_keys() {
+        let dir = temp_dir("merge-no-ledger");
+        let (source_path, dest_path) = (dir.join("source"), dir.join("dest"));
+        std::fs::write(&source_path, r#"{"model":"opus"}"#).unwrap();
+        std::fs::write(&dest_path, r#"{"model":"opus","mine":true}"#).unwrap();
+        let def = ConfigFileDef { source: "source".into(), dest: None, strategy: MergeStrategy::JsonMerge, target: None };
+
+        remove(&def, &source_path, &dest_path, None).unwrap();
+        let kept: Value = serde_json::from_str(&std::fs::read_to_string(&dest_path).unwrap()).unwrap();
+        assert_eq!(kept, serde_json::json!({"model": "opus", "mine": true}));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_markdown_block_round_trip() {
+        let installed = round_trip("md", MergeStrategy::MarkdownBlock, "Team rules\n", "# Mine\n\nNotes\n");
+        assert_eq!(installed, format!("# Mine\n\nNotes\n\n{}\nTeam rules\n{}\n", BLOCK_BEGIN, BLOCK_END));
+    }
+}
@@ -1,44 +1,186 @@
 pub mod scanner;
+pub mod cache;
 pub mod diff;
 pub mod installer;
+pub mod merge;
+
+use std::cell::RefCell;
+use std::io::Read;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::Result;
 
-use std::process::{Command, Stdio};
 use crate::app::TargetCli;
 
-/// Create a Command to run CLI (Claude or Codex).
-/// On Windows, uses cmd.exe /c to properly execute .cmd files
-/// stdin is set to null to prevent blocking on interactive prompts.
-#[cfg(windows)]
-pub fn create_cli_command(target_cli: TargetCli) -> Command {
-    let cli_name = match target_cli {
-        TargetCli::Claude => "claude",
-        TargetCli::Codex => "codex",
+/// Mutating CLI invocations since the last `take_command_log` (for the batch journal)
+static COMMAND_LOG: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Record a CLI invocation. Values passed with -e/--env are redacted.
+pub fn log_command(cmd: &Command) {
+    let mut parts = vec![cmd.get_program().to_string_lossy().to_string()];
+    let mut redact_next = false;
+    for arg in cmd.get_args() {
+        let arg = arg.to_string_lossy();
+        if redact_next {
+            let key = arg.split_once('=').map(|(k, _)| k).unwrap_or(&arg);
+            parts.push(format!("{}=***", key));
+        } else {
+            parts.push(arg.to_string());
+        }
+        redact_next = arg == "-e" || arg == "--env";
+    }
+
+    COMMAND_LOG.lock().unwrap_or_else(|e| e.into_inner()).push(parts.join(" "));
+}
+
+pub fn take_command_log() -> Vec<String> {
+    std::mem::take(&mut *COMMAND_LOG.lock().unwrap_or_else(|e| e.into_inner()))
+}
+
+/// Executable for a CLI: `INSTALLER_CLAUDE_BIN` / `INSTALLER_CODEX_BIN` when set
+/// (a non-PATH install, or a stub in tests), else the name looked up on PATH
+pub fn cli_program(target_cli: TargetCli) -> String {
+    let (name, var) = match target_cli {
+        TargetCli::Claude => ("claude", "INSTALLER_CLAUDE_BIN"),
+        TargetCli::Codex => ("codex", "INSTALLER_CODEX_BIN"),
     };
-    let mut cmd = Command::new("cmd");
-    cmd.args(["/c", cli_name]);
-    cmd.stdin(Stdio::null());
-    cmd
+    std::env::var(var).ok().filter(|v| !v.is_empty()).unwrap_or_else(|| name.to_string())
+}
+
+/// Program and leading args to run `program`. On Windows the CLIs are npm `.cmd` shims,
+/// which only cmd.exe can start; an explicit `.exe` runs directly.
+fn invocation(program: &str, windows: bool) -> (String, Vec<String>) {
+    if windows && !program.to_ascii_lowercase().ends_with(".exe") {
+        ("cmd".to_string(), vec!["/c".to_string(), program.to_string()])
+    } else {
+        (program.to_string(), Vec::new())
+    }
 }
 
-#[cfg(not(windows))]
+/// Create a Command to run CLI (Claude or Codex).
+/// stdin is set to null to prevent blocking on interactive prompts.
 pub fn create_cli_command(target_cli: TargetCli) -> Command {
-    let cli_name = match target_cli {
-        TargetCli::Claude => "claude",
-        TargetCli::Codex => "codex",
-    };
-    let mut cmd = Command::new(cli_name);
+    let (program, args) = invocation(&cli_program(target_cli), cfg!(windows));
+    let mut cmd = Command::new(program);
+    cmd.args(args);
     cmd.stdin(Stdio::null());
     cmd
 }
 
 /// Deprecated: Use create_cli_command() instead
-#[cfg(windows)]
 pub fn create_claude_command() -> Command {
     create_cli_command(TargetCli::Claude)
 }
 
-/// Deprecated: Use create_cli_command() instead
-#[cfg(not(windows))]
-pub fn create_claude_command() -> Command {
-    create_cli_command(TargetCli::Claude)
+/// Kill switch for the CLI process an install worker is running (see `run_command`)
+#[derive(Clone, Debug, Default)]
+pub struct CancelHandle(Arc<Mutex<CancelState>>);
+
+#[derive(Debug, Default)]
+struct CancelState {
+    cancelled: bool,
+    child: Option<Child>,
+}
+
+impl CancelHandle {
+    /// Kill the running child, and make later `run_command` calls on this handle fail
+    pub fn cancel(&self) {
+        let mut state = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        state.cancelled = true;
+        if let Some(child) = state.child.as_mut() {
+            let _ = child.kill();
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).cancelled
+    }
+}
+
+thread_local! {
+    static CANCEL: RefCell<Option<CancelHandle>> = const { RefCell::new(None) };
+}
+
+/// Make `run_command` on this thread killable through `handle` (None = plain `output()`)
+pub fn set_cancel_handle(handle: Option<CancelHandle>) {
+    CANCEL.with(|c| *c.borrow_mut() = handle);
+}
+
+/// `Command::output`, except that a worker's child can be killed from the UI thread
+pub fn run_command(command: &mut Command) -> Result<Output> {
+    let Some(handle) = CANCEL.with(|c| c.borrow().clone()) else {
+        return Ok(command.output()?);
+    };
+    if handle.is_cancelled() {
+        anyhow::bail!("Cancelled");
+    }
+
+    let mut child = command.stdout(Stdio::piped()).stderr(Stdio::piped()).spawn()?;
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    handle.0.lock().unwrap_or_else(|e| e.into_inner()).child = Some(child);
+
+    let status = loop {
+        {
+            let mut state = handle.0.lock().unwrap_or_else(|e| e.into_inner());
+            let child = state.child.as_mut().expect("child is set until it exits");
+            if let Some(status) = child.try_wait()? {
+                state.child = None;
+                break status;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if handle.is_cancelled() {
+        anyhow::bail!("Cancelled");
+    }
+    Ok(Output { status, stdout, stderr })
+}
+
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> std::thread::JoinHandle<Vec<u8>> {
+    std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_invocation() {
+        assert_eq!(invocation("claude", false), ("claude".to_string(), vec![]));
+        assert_eq!(invocation("claude", true), ("cmd".to_string(), vec!["/c".to_string(), "claude".to_string()]));
+        assert_eq!(invocation(r"C:\stub\cli-stub.EXE", true), (r"C:\stub\cli-stub.EXE".to_string(), vec![]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cancel_kills_running_command() {
+        let handle = CancelHandle::default();
+        let worker = {
+            let handle = handle.clone();
+            std::thread::spawn(move || {
+                set_cancel_handle(Some(handle));
+                let started = std::time::Instant::now();
+                (run_command(Command::new("sleep").arg("30")), started.elapsed())
+            })
+        };
+        std::thread::sleep(Duration::from_millis(200));
+        handle.cancel();
+
+        let (result, elapsed) = worker.join().unwrap();
+        assert_eq!(result.unwrap_err().to_string(), "Cancelled");
+        assert!(elapsed < Duration::from_secs(10));
+        assert!(run_command(Command::new("true").stdin(Stdio::null())).unwrap().status.success());
+    }
 }
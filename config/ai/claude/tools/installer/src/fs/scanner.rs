@@ -1,75 +1,122 @@
-use std::path::Path;
-use anyhow::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
 use crate::app::TargetCli;
 use crate::component::{Component, ComponentType, HookConfig, InstallStatus};
-use crate::mcp::{McpCatalog, McpServer, McpStatus};
-use crate::plugin::{parse_plugins_yaml, Plugin, PluginDef, PluginStatus};
-use super::create_claude_command;
+use crate::config_file::load_manifest;
+use crate::conflict;
+use crate::lint::LintIssue;
+use crate::mcp::{McpCatalog, McpScope, McpServer, McpServerDef, McpStatus};
+use crate::plugin::{parse_plugins_yaml, MarketplaceManifest, Plugin, PluginCatalog, PluginDef, PluginStatus};
+use super::cache::ScanCache;
+use super::{create_claude_command, create_cli_command, merge};
+
+/// Scan all source layers. A later layer replaces an earlier layer's component
+/// with the same type and relative path. `cache_file` keeps content hashes between
+/// scans (`cache::cache_path()`; None hashes everything and keeps nothing).
+pub fn scan_components(sources: &[PathBuf], dest_dir: &Path, target_cli: TargetCli, cache_file: Option<&Path>) -> Result<Vec<Component>> {
+    let cache = ScanCache::load(cache_file.map(Path::to_path_buf));
+
+    // Layers are walked in parallel, then merged in order
+    let layers: Vec<Result<Vec<Component>>> = std::thread::scope(|s| {
+        let handles: Vec<_> = sources
+            .iter()
+            .map(|source_dir| s.spawn(|| scan_layer(source_dir, dest_dir, target_cli, &cache)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|_| Err(anyhow::anyhow!("Scan thread crashed"))))
+            .collect()
+    });
+
+    let mut components: Vec<Component> = Vec::new();
+    let mut positions: HashMap<(ComponentType, String), usize> = HashMap::new();
+    for (layer, scanned) in layers.into_iter().enumerate() {
+        for mut component in scanned? {
+            component.layer = layer;
+            match positions.get(&(component.component_type.clone(), component.name.clone())) {
+                Some(&pos) => {
+                    component.overrides = std::mem::take(&mut components[pos].overrides);
+                    component.overrides.push(components[pos].layer);
+                    components[pos] = component;
+                }
+                None => {
+                    positions.insert((component.component_type.clone(), component.name.clone()), components.len());
+                    components.push(component);
+                }
+            }
+        }
+    }
 
-pub fn scan_components(source_dir: &Path, dest_dir: &Path, target_cli: TargetCli) -> Result<Vec<Component>> {
-    let mut components = Vec::new();
+    // Detail pane cache key (see App::component_detail)
+    for component in components.iter_mut().filter(|c| c.source_path.is_file()) {
+        component.source_hash = cache.hash(&component.source_path).ok();
+    }
 
-    match target_cli {
-        TargetCli::Claude => {
-            // Scan all components for Claude Code
-            scan_directory(
-                &source_dir.join("agents"),
-                &dest_dir.join("agents"),
-                ComponentType::Agents,
-                &mut components,
-            )?;
-
-            scan_directory(
-                &source_dir.join("commands"),
-                &dest_dir.join("commands"),
-                ComponentType::Commands,
-                &mut components,
-            )?;
-
-            scan_directory(
-                &source_dir.join("contexts"),
-                &dest_dir.join("contexts"),
-                ComponentType::Contexts,
-                &mut components,
-            )?;
-
-            scan_directory(
-                &source_dir.join("rules"),
-                &dest_dir.join("rules"),
-                ComponentType::Rules,
-                &mut components,
-            )?;
-
-            scan_directory(
-                &source_dir.join("skills"),
-                &dest_dir.join("skills"),
-                ComponentType::Skills,
-                &mut components,
-            )?;
-
-            scan_directory(
-                &source_dir.join("output-styles"),
-                &dest_dir.join("output-styles"),
-                ComponentType::OutputStyles,
-                &mut components,
-            )?;
-
-            scan_statusline(source_dir, dest_dir, &mut components)?;
-            scan_hooks(source_dir, dest_dir, &mut components)?;
-            add_config_files(source_dir, dest_dir, &mut components)?;
-        }
-        TargetCli::Codex => {
-            // Only scan skills for Codex CLI
-            scan_directory(
-                &source_dir.join("skills"),
-                &dest_dir.join("skills"),
-                ComponentType::Skills,
-                &mut components,
-            )?;
-        }
+    // A cache that cannot be written only costs speed next time
+    let _ = cache.save();
+
+    // Validate source files (frontmatter, SKILL.md, hook events, ...)
+    crate::lint::validate(&mut components);
+
+    // Resolve `requires:` declarations (unknown references are lint errors)
+    crate::deps::load_requires(&mut components);
+    crate::deps::validate(&mut components);
+
+    Ok(components)
+}
+
+fn scan_layer(source_dir: &Path, dest_dir: &Path, target_cli: TargetCli, cache: &ScanCache) -> Result<Vec<Component>> {
+    let directories: &[(&str, ComponentType)] = match target_cli {
+        // All components for Claude Code
+        TargetCli::Claude => &[
+            ("agents", ComponentType::Agents),
+            ("commands", ComponentType::Commands),
+            ("contexts", ComponentType::Contexts),
+            ("rules", ComponentType::Rules),
+            ("skills", ComponentType::Skills),
+            ("output-styles", ComponentType::OutputStyles),
+        ],
+        // Only skills and config files for Codex CLI
+        TargetCli::Codex => &[("skills", ComponentType::Skills)],
+    };
+
+    // Each component directory is walked on its own thread
+    let scanned: Vec<Result<Vec<Component>>> = std::thread::scope(|s| {
+        let handles: Vec<_> = directories
+            .iter()
+            .map(|(dir, component_type)| {
+                s.spawn(move || {
+                    let mut components = Vec::new();
+                    scan_directory(
+                        &source_dir.join(dir),
+                        &dest_dir.join(dir),
+                        component_type.clone(),
+                        cache,
+                        &mut components,
+                    )?;
+                    Ok(components)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|_| Err(anyhow::anyhow!("Scan thread crashed"))))
+            .collect()
+    });
+
+    let mut components = Vec::new();
+    for result in scanned {
+        components.extend(result?);
     }
+    if target_cli == TargetCli::Claude {
+        scan_statusline(source_dir, dest_dir, cache, &mut components)?;
+        scan_hooks(source_dir, dest_dir, cache, &mut components)?;
+    }
+    add_config_files(source_dir, dest_dir, target_cli, &mut components)?;
 
     Ok(components)
 }
@@ -78,6 +125,7 @@ fn scan_directory(
     source_dir: &Path,
     dest_dir: &Path,
     component_type: ComponentType,
+    cache: &ScanCache,
     components: &mut Vec<Component>,
 ) -> Result<()> {
     if !source_dir.exists() {
@@ -90,7 +138,7 @@ fn scan_directory(
         .filter_map(|e| e.ok())
     {
         let path = entry.path();
-        if !path.is_file() {
+        if !entry.file_type().is_file() && !path.is_file() {
             continue;
         }
 
@@ -105,7 +153,7 @@ fn scan_directory(
         let dest_path = dest_dir.join(relative);
         let name = relative.to_string_lossy().to_string();
 
-        let status = determine_status(path, &dest_path)?;
+        let status = determine_status(path, &dest_path, cache)?;
 
         components.push(Component::new(
             component_type.clone(),
@@ -119,7 +167,7 @@ fn scan_directory(
     Ok(())
 }
 
-fn scan_statusline(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Component>) -> Result<()> {
+fn scan_statusline(source_dir: &Path, dest_dir: &Path, cache: &ScanCache, components: &mut Vec<Component>) -> Result<()> {
     let statusline_dir = source_dir.join("statusline");
     if !statusline_dir.exists() {
         return Ok(());
@@ -140,7 +188,7 @@ fn scan_statusline(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Comp
     }
 
     let dest_path = dest_dir.join("statusline").join(binary_name);
-    let status = determine_status(&binary_path, &dest_path)?;
+    let status = determine_status(&binary_path, &dest_path, cache)?;
 
     components.push(Component::new(
         ComponentType::Statusline,
@@ -153,7 +201,7 @@ fn scan_statusline(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Comp
     Ok(())
 }
 
-fn scan_hooks(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Component>) -> Result<()> {
+fn scan_hooks(source_dir: &Path, dest_dir: &Path, cache: &ScanCache, components: &mut Vec<Component>) -> Result<()> {
     let hooks_dir = source_dir.join("hooks");
     if !hooks_dir.exists() {
         return Ok(());
@@ -173,15 +221,31 @@ fn scan_hooks(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Component
             continue;
         }
 
-        // Read hook.yaml
-        let config_content = std::fs::read_to_string(&hook_yaml)?;
-        let config: HookConfig = serde_yaml::from_str(&config_content)?;
-
-        // Find binary in the hook directory
         let hook_name = path.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
 
+        // Read hook.yaml (a broken one is a lint error on the hook, not a failed scan)
+        let config_content = std::fs::read_to_string(&hook_yaml)?;
+        let config: HookConfig = match serde_yaml::from_str(&config_content) {
+            Ok(config) => config,
+            Err(e) => {
+                let mut component = Component::new(
+                    ComponentType::Hooks,
+                    hook_name.to_string(),
+                    hook_yaml,
+                    dest_dir.join("hooks").join(hook_name),
+                    InstallStatus::New,
+                );
+                component.selected = false;
+                component.issues.push(LintIssue::error(format!("Invalid hook.yaml: {}", e)));
+                components.push(component);
+                continue;
+            }
+        };
+
+        // Find binary in the hook directory
+
         // Select OS-specific binary using HookConfig method
         let binary_name = config.binary_name();
 
@@ -191,7 +255,7 @@ fn scan_hooks(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Component
         }
 
         let dest_path = dest_dir.join("hooks").join(&binary_name);
-        let status = determine_status(&binary_path, &dest_path)?;
+        let status = determine_status(&binary_path, &dest_path, cache)?;
 
         let component = Component::new(
             ComponentType::Hooks,
@@ -207,39 +271,40 @@ fn scan_hooks(source_dir: &Path, dest_dir: &Path, components: &mut Vec<Component
     Ok(())
 }
 
+/// Config files from the layer's manifest (see config_file.rs)
 fn add_config_files(
     source_dir: &Path,
     dest_dir: &Path,
+    target_cli: TargetCli,
     components: &mut Vec<Component>,
 ) -> Result<()> {
-    let config_files = ["settings.json"];
+    for def in load_manifest(source_dir)? {
+        let source_path = source_dir.join(&def.source);
+        if !def.applies_to(target_cli) || !source_path.is_file() {
+            continue;
+        }
 
-    for file in config_files {
-        let source_path = source_dir.join(file);
-        if source_path.exists() {
-            let dest_path = dest_dir.join(file);
+        let dest_path = dest_dir.join(def.dest());
+        // Unparsable dest files show up as modified; the diff view reports the error
+        let choices = conflict::load(dest_dir, def.dest());
+        let status = merge::status(&def, &source_path, &dest_path, &choices).unwrap_or(InstallStatus::Modified);
 
-            // settings.json is always Managed (auto-merged)
-            let status = if file == "settings.json" && dest_path.exists() {
-                InstallStatus::Managed
-            } else {
-                determine_status(&source_path, &dest_path)?
-            };
-
-            components.push(Component::new(
+        components.push(
+            Component::new(
                 ComponentType::ConfigFile,
-                file.to_string(),
+                def.dest().to_string(),
                 source_path,
                 dest_path,
                 status,
-            ));
-        }
+            )
+            .with_config_file(def),
+        );
     }
 
     Ok(())
 }
 
-fn determine_status(source: &Path, dest: &Path) -> Result<InstallStatus> {
+fn determine_status(source: &Path, dest: &Path, cache: &ScanCache) -> Result<InstallStatus> {
     if !dest.exists() {
         return Ok(InstallStatus::New);
     }
@@ -253,48 +318,166 @@ fn determine_status(source: &Path, dest: &Path) -> Result<InstallStatus> {
         return Ok(InstallStatus::Modified);
     }
 
-    // Sizes match - compare contents for accuracy
-    let source_content = std::fs::read(source)?;
-    let dest_content = std::fs::read(dest)?;
-
-    if source_content == dest_content {
+    // Sizes match - compare content hashes (only files changed since the last scan are read)
+    if cache.hash(source)? == cache.hash(dest)? {
         Ok(InstallStatus::Unchanged)
     } else {
         Ok(InstallStatus::Modified)
     }
 }
 
-pub fn scan_mcp_servers(source_dir: &Path, target_cli: TargetCli, _dest_dir: &Path) -> Result<Vec<McpServer>> {
-    // Both CLIs use the same catalog
-    let catalog_path = source_dir.join("mcps/mcps.yaml");
+pub fn scan_mcp_servers(sources: &[PathBuf], target_cli: TargetCli, _dest_dir: &Path) -> Result<Vec<McpServer>> {
+    let mut servers = catalog_mcp_servers(sources)?;
+
+    // Get installed MCP servers based on CLI
+    let installed = installed_mcp_servers(target_cli);
+    for server in &mut servers {
+        if installed.contains(&server.def.name) {
+            server.status = McpStatus::Installed;
+        }
+    }
+
+    Ok(servers)
+}
+
+/// MCP catalog without asking the CLI what is installed (everything reads as not installed)
+pub fn catalog_mcp_servers(sources: &[PathBuf]) -> Result<Vec<McpServer>> {
+    // Both CLIs use the same catalog; later layers replace servers by name
+    let mut defs: Vec<McpServerDef> = Vec::new();
+    for source_dir in sources {
+        let catalog_path = source_dir.join("mcps/mcps.yaml");
+        if !catalog_path.exists() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&catalog_path)?;
+        let catalog: McpCatalog = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid MCP catalog: {}", catalog_path.display()))?;
 
-    if !catalog_path.exists() {
-        return Ok(Vec::new());
+        for def in catalog.servers {
+            match defs.iter_mut().find(|d| d.name == def.name) {
+                Some(existing) => *existing = def,
+                None => defs.push(def),
+            }
+        }
     }
 
-    let content = std::fs::read_to_string(&catalog_path)?;
-    let catalog: McpCatalog = serde_yaml::from_str(&content)?;
+    Ok(defs.into_iter().map(|def| McpServer::new(def, McpStatus::NotInstalled)).collect())
+}
 
-    // Get installed MCP servers based on CLI
-    let installed = match target_cli {
-        TargetCli::Claude => get_installed_claude_mcp_servers(),
-        TargetCli::Codex => get_installed_codex_mcp_servers(),
+/// An MCP server entry in the CLI config: user scope, or local to a project dir (Claude only)
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct McpRegistration {
+    pub name: String,
+    pub scope: McpScope,
+    pub project: Option<String>,
+    #[serde(default)]
+    pub env: Vec<(String, String)>, // Env vars the server was added with (sorted by name)
+    #[serde(default)]
+    pub launch: Option<String>, // URL, or command and args joined by spaces
+}
+
+/// What a registered server runs: its `url`, or its `command` followed by `args`
+fn registered_launch(url: Option<&str>, command: Option<&str>, args: Vec<&str>) -> Option<String> {
+    if let Some(url) = url {
+        return Some(url.to_string());
+    }
+    command.map(|command| std::iter::once(command).chain(args).collect::<Vec<_>>().join(" "))
+}
+
+/// Registrations of the CLI whose config dir is `dest_dir` (~/.claude or ~/.codex)
+pub fn mcp_registrations(target_cli: TargetCli, dest_dir: &Path) -> Vec<McpRegistration> {
+    match target_cli {
+        TargetCli::Claude => dest_dir
+            .parent()
+            .map(|home| claude_mcp_registrations(&home.join(".claude.json")))
+            .unwrap_or_default(),
+        TargetCli::Codex => codex_mcp_registrations(&dest_dir.join("config.toml")),
+    }
+}
+
+/// Every MCP server registration in ~/.claude.json (`claude_json`), across all projects
+pub fn claude_mcp_registrations(claude_json: &Path) -> Vec<McpRegistration> {
+    let Some(json) = std::fs::read_to_string(claude_json)
+        .ok()
+        .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+    else {
+        return Vec::new();
     };
 
-    let servers = catalog
-        .servers
-        .into_iter()
-        .map(|def| {
-            let status = if installed.contains(&def.name) {
-                McpStatus::Installed
-            } else {
-                McpStatus::NotInstalled
-            };
-            McpServer::new(def, status)
+    let entries = |servers: Option<&serde_json::Value>, scope: McpScope, project: Option<&String>| -> Vec<McpRegistration> {
+        let Some(servers) = servers.and_then(|s| s.as_object()) else {
+            return Vec::new();
+        };
+        servers
+            .iter()
+            .map(|(name, server)| {
+                let mut env: Vec<(String, String)> = server
+                    .get("env")
+                    .and_then(|e| e.as_object())
+                    .map(|e| e.iter().map(|(k, v)| (k.clone(), v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()))).collect())
+                    .unwrap_or_default();
+                env.sort();
+                let launch = registered_launch(
+                    server.get("url").and_then(|u| u.as_str()),
+                    server.get("command").and_then(|c| c.as_str()),
+                    server.get("args").and_then(|a| a.as_array()).map(|a| a.iter().filter_map(|v| v.as_str()).collect()).unwrap_or_default(),
+                );
+                McpRegistration { name: name.clone(), scope, project: project.cloned(), env, launch }
+            })
+            .collect()
+    };
+
+    let mut registrations = entries(json.get("mcpServers"), McpScope::User, None);
+    if let Some(projects) = json.get("projects").and_then(|p| p.as_object()) {
+        for (dir, project) in projects {
+            registrations.extend(entries(project.get("mcpServers"), McpScope::Local, Some(dir)));
+        }
+    }
+    registrations
+}
+
+/// `[mcp_servers.<name>]` tables of Codex's config.toml (all user scope)
+pub fn codex_mcp_registrations(config_toml: &Path) -> Vec<McpRegistration> {
+    let Some(doc) = std::fs::read_to_string(config_toml)
+        .ok()
+        .and_then(|content| content.parse::<toml_edit::DocumentMut>().ok())
+    else {
+        return Vec::new();
+    };
+    let Some(servers) = doc.get("mcp_servers").and_then(|s| s.as_table_like()) else {
+        return Vec::new();
+    };
+
+    servers
+        .iter()
+        .map(|(name, server)| {
+            let mut env: Vec<(String, String)> = server
+                .get("env")
+                .and_then(|e| e.as_table_like())
+                .map(|e| {
+                    e.iter()
+                        .filter_map(|(k, v)| v.as_str().map(|v| (k.to_string(), v.to_string())))
+                        .collect()
+                })
+                .unwrap_or_default();
+            env.sort();
+            let launch = registered_launch(
+                server.get("url").and_then(|u| u.as_str()),
+                server.get("command").and_then(|c| c.as_str()),
+                server.get("args").and_then(|a| a.as_array()).map(|a| a.iter().filter_map(|v| v.as_str()).collect()).unwrap_or_default(),
+            );
+            McpRegistration { name: name.to_string(), scope: McpScope::User, project: None, env, launch }
         })
-        .collect();
+        .collect()
+}
 
-    Ok(servers)
+/// Names of MCP servers registered in the CLI (including ones outside the catalog)
+pub fn installed_mcp_servers(target_cli: TargetCli) -> Vec<String> {
+    match target_cli {
+        TargetCli::Claude => get_installed_claude_mcp_servers(),
+        TargetCli::Codex => get_installed_codex_mcp_servers(),
+    }
 }
 
 fn get_installed_claude_mcp_servers() -> Vec<String> {
@@ -330,9 +513,7 @@ fn get_installed_claude_mcp_servers() -> Vec<String> {
 }
 
 fn get_installed_codex_mcp_servers() -> Vec<String> {
-    use std::process::Command;
-
-    let mut cmd = Command::new("codex");
+    let mut cmd = create_cli_command(TargetCli::Codex);
     cmd.args(["mcp", "list"]);
     let output = cmd.output();
 
@@ -361,54 +542,188 @@ fn get_installed_codex_mcp_servers() -> Vec<String> {
     }
 }
 
-pub fn scan_plugins(source_dir: &Path) -> Result<Vec<Plugin>> {
-    let catalog_path = source_dir.join("plugins/plugins.yaml");
+/// plugins.yaml of all layers; later layers replace entries by marketplace and name
+fn read_plugin_catalog(sources: &[PathBuf]) -> Result<PluginCatalog> {
+    let mut catalog = PluginCatalog::new();
+    for source_dir in sources {
+        let catalog_path = source_dir.join("plugins/plugins.yaml");
+        if !catalog_path.exists() {
+            continue;
+        }
 
-    if !catalog_path.exists() {
-        return Ok(Vec::new());
+        for entry in parse_plugins_yaml(&std::fs::read_to_string(&catalog_path)?) {
+            match catalog.iter_mut().find(|(m, _, n, _)| *m == entry.0 && *n == entry.2) {
+                Some(existing) => *existing = entry,
+                None => catalog.push(entry),
+            }
+        }
     }
+    Ok(catalog)
+}
 
-    let content = std::fs::read_to_string(&catalog_path)?;
-    let catalog = parse_plugins_yaml(&content);
+pub fn scan_plugins(sources: &[PathBuf]) -> Result<Vec<Plugin>> {
+    let mut plugins = catalog_plugins(sources)?;
 
-    // Get installed plugins
+    // Match on "name@marketplace" so same-named plugins from different marketplaces don't collide
     let installed = get_installed_plugins();
+    for plugin in &mut plugins {
+        if installed.contains(&plugin.def.id()) {
+            plugin.status = PluginStatus::Installed;
+        }
+    }
+
+    Ok(plugins)
+}
+
+/// Plugin catalog without install status (everything reads as not installed)
+pub fn catalog_plugins(sources: &[PathBuf]) -> Result<Vec<Plugin>> {
+    let catalog = read_plugin_catalog(sources)?;
+
+    let plugins = catalog
+        .into_iter()
+        .map(|(marketplace, source, name, comment)| {
+            let def = PluginDef {
+                name,
+                marketplace,
+                source,
+                comment,
+            };
+            Plugin::new(def, PluginStatus::NotInstalled)
+        })
+        .collect();
 
+    Ok(plugins)
+}
+
+/// Scan every plugin offered by the locally cloned marketplaces under `<dest_dir>/plugins`
+/// (reads `.claude-plugin/marketplace.json` from each marketplace checkout)
+pub fn scan_marketplace_plugins(sources: &[PathBuf], dest_dir: &Path) -> Result<Vec<Plugin>> {
+    let plugins_dir = dest_dir.join("plugins");
+
+    // Marketplace sources: plugins.yaml first, then Claude's own registry
+    let catalog = read_plugin_catalog(sources)?;
+
+    // (marketplace name, source url, checkout dir)
+    let mut marketplaces: Vec<(String, String, PathBuf)> = Vec::new();
+    for (name, source, location) in read_known_marketplaces(&plugins_dir) {
+        let source = catalog
+            .iter()
+            .find(|(m, _, _, _)| *m == name)
+            .map(|(_, s, _, _)| s.clone())
+            .unwrap_or(source);
+        marketplaces.push((name, source, location));
+    }
+
+    for (name, source, _, _) in &catalog {
+        if !marketplaces.iter().any(|(m, _, _)| m == name) {
+            marketplaces.push((name.clone(), source.clone(), plugins_dir.join("marketplaces").join(name)));
+        }
+    }
+
+    // Checkouts that neither list knows about
+    if let Ok(entries) = std::fs::read_dir(plugins_dir.join("marketplaces")) {
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if entry.path().is_dir() && !marketplaces.iter().any(|(m, _, _)| *m == name) {
+                marketplaces.push((name, String::new(), entry.path()));
+            }
+        }
+    }
+
+    let installed = get_installed_plugins();
     let mut plugins = Vec::new();
-    for (marketplace, source, name, comment) in catalog {
-        let status = if installed.contains(&name) {
-            PluginStatus::Installed
-        } else {
-            PluginStatus::NotInstalled
-        };
 
-        let def = PluginDef {
-            name,
-            marketplace,
-            source,
-            comment,
+    for (marketplace, source, location) in marketplaces {
+        let manifest_path = location.join(".claude-plugin/marketplace.json");
+        let manifest: MarketplaceManifest = match std::fs::read_to_string(&manifest_path)
+            .ok()
+            .and_then(|c| serde_json::from_str(&c).ok())
+        {
+            Some(m) => m,
+            None => continue, // Not cloned yet or unreadable manifest
         };
 
-        plugins.push(Plugin::new(def, status));
+        for entry in manifest.plugins {
+            let def = PluginDef {
+                name: entry.name,
+                marketplace: marketplace.clone(),
+                source: source.clone(),
+                comment: entry.description,
+            };
+
+            let status = if installed.contains(&def.id()) {
+                PluginStatus::Installed
+            } else {
+                PluginStatus::NotInstalled
+            };
+
+            plugins.push(Plugin::new(def, status));
+        }
     }
 
+    plugins.sort_by(|a, b| (&a.def.marketplace, &a.def.name).cmp(&(&b.def.marketplace, &b.def.name)));
     Ok(plugins)
 }
 
-fn get_installed_plugins() -> Vec<String> {
+/// Read `known_marketplaces.json` maintained by the claude CLI
+/// Returns (name, source url, checkout dir) for each registered marketplace
+pub fn read_known_marketplaces(plugins_dir: &Path) -> Vec<(String, String, PathBuf)> {
     use serde_json::Value;
 
-    // Read from ~/.claude/settings.json
-    let settings_path = dirs::home_dir()
-        .map(|h| h.join(".claude/settings.json"))
-        .filter(|p| p.exists());
+    let content = match std::fs::read_to_string(plugins_dir.join("known_marketplaces.json")) {
+        Ok(c) => c,
+        Err(_) => return Vec::new(),
+    };
 
-    let settings_path = match settings_path {
-        Some(p) => p,
-        None => return Vec::new(),
+    let known: Value = match serde_json::from_str(&content) {
+        Ok(v) => v,
+        Err(_) => return Vec::new(),
+    };
+
+    let Some(map) = known.as_object() else {
+        return Vec::new();
     };
 
-    let content = match std::fs::read_to_string(&settings_path) {
+    map.iter()
+        .map(|(name, entry)| {
+            let location = entry
+                .get("installLocation")
+                .and_then(|v| v.as_str())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| plugins_dir.join("marketplaces").join(name));
+
+            // { "source": "github", "repo": "owner/repo" } or { "source": "git", "url": "..." }
+            let source = entry.get("source");
+            let source_url = match source.and_then(|s| s.get("source")).and_then(|s| s.as_str()) {
+                Some("github") => source
+                    .and_then(|s| s.get("repo"))
+                    .and_then(|r| r.as_str())
+                    .map(|repo| format!("https://github.com/{}.git", repo)),
+                _ => source
+                    .and_then(|s| s.get("url").or_else(|| s.get("path")))
+                    .and_then(|u| u.as_str())
+                    .map(|u| u.to_string()),
+            }
+            .unwrap_or_default();
+
+            (name.clone(), source_url, location)
+        })
+        .collect()
+}
+
+fn get_installed_plugins() -> Vec<String> {
+    // Read from ~/.claude/settings.json
+    match dirs::home_dir() {
+        Some(home) => enabled_plugins(&home.join(".claude/settings.json")),
+        None => Vec::new(),
+    }
+}
+
+/// Enabled plugin ids ("plugin@marketplace") from a settings.json file
+pub fn enabled_plugins(settings_path: &Path) -> Vec<String> {
+    use serde_json::Value;
+
+    let content = match std::fs::read_to_string(settings_path) {
         Ok(c) => c,
         Err(_) => return Vec::new(),
     };
@@ -424,18 +739,53 @@ fn get_installed_plugins() -> Vec<String> {
         _ => return Vec::new(),
     };
 
-    // Extract plugin names from "plugin@marketplace" format
+    // Keep full "plugin@marketplace" keys
+    // e.g., "document-skills@anthropic-agent-skills"
     enabled_plugins
         .iter()
-        .filter_map(|(key, value)| {
-            // Only include if value is true
-            if value.as_bool() == Some(true) {
-                // Extract plugin name before '@'
-                // e.g., "document-skills@anthropic-agent-skills" -> "document-skills"
-                key.split('@').next().map(|s| s.to_string())
-            } else {
-                None
-            }
-        })
+        .filter(|(_, value)| value.as_bool() == Some(true))
+        .map(|(key, _)| key.clone())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{temp_dir, write};
+
+    #[test]
+    fn test_later_layer_overrides_by_relative_path() {
+        let root = temp_dir("scanner-layers");
+        let (base, overlay, dest) = (root.join("base"), root.join("overlay"), root.join("dest"));
+        write(&base, "rules/shared.md", "team");
+        write(&base, "rules/team-only.md", "team");
+        write(&base, "skills/demo/SKILL.md", "---\nname: demo\ndescription: d\n---\n");
+        write(&overlay, "rules/shared.md", "mine");
+        write(&overlay, "skills/demo/notes.md", "extra file for the team skill");
+        let plugins = |entries: &str| format!("marketplaces:\n  market:\n    source: https://example.com/a.git\n    plugins:\n{}", entries);
+        write(&base, "plugins/plugins.yaml", &plugins("      - name: one\n        description: team\n"));
+        write(&overlay, "plugins/plugins.yaml", &plugins("      - name: one\n        description: mine\n      - two\n"));
+        write(&overlay, "hooks/broken/hook.yaml", "name: [oops");
+
+        let sources = vec![base.clone(), overlay.clone()];
+        let components = scan_components(&sources, &dest, TargetCli::Claude, Some(&root.join("scan-cache.json"))).unwrap();
+        let find = |name: &str| components.iter().find(|c| c.name == name).unwrap();
+
+        assert_eq!(components.iter().filter(|c| c.name == "shared.md").count(), 1);
+        assert_eq!(find("shared.md").layer, 1);
+        assert_eq!(find("shared.md").overrides, vec![0]);
+        assert_eq!(find("shared.md").source_path, overlay.join("rules/shared.md"));
+        assert_eq!(find("team-only.md").layer, 0);
+        // SKILL.md from the base layer covers the overlay's file
+        assert!(find("demo/notes.md").issues.is_empty());
+        // A broken hook.yaml is reported on the hook instead of failing the scan
+        assert!(find("broken").issues.iter().any(|i| i.message.starts_with("Invalid hook.yaml")));
+        assert!(!find("broken").selected);
+
+        let catalog = read_plugin_catalog(&sources).unwrap();
+        assert_eq!(catalog.len(), 2);
+        assert_eq!(catalog[0].3.as_deref(), Some("mine"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
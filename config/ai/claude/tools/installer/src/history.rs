@@ -0,0 +1,442 @@
+//! Persistent journal of install/remove batches
+//!
+//! Each batch is stored under `<dest_dir>/.installer/history/<id>/`:
+//! - `journal.json` items, touched files, settings.json before/after, CLI commands,
+//!   MCP registrations (scope, project, env var names) before/after; env values such as
+//!   API keys are never written here
+//! - `files/<n>` backups of files that existed before the batch touched them
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::conflict::key_path;
+use crate::fs::scanner::McpRegistration;
+
+pub fn history_dir(dest_dir: &Path) -> PathBuf {
+    dest_dir.join(".installer").join("history")
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+    Component,
+    Mcp,
+    Plugin,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchItem {
+    pub kind: ItemKind,
+    pub name: String, // Component display name, MCP server name or plugin id
+    pub ok: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub path: PathBuf,
+    pub backup: Option<String>, // Backup file name under files/ (None = file did not exist)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Batch {
+    pub id: String,
+    pub timestamp: u64,
+    pub action: String, // "install" or "remove"
+    pub target: String,
+    pub items: Vec<BatchItem>,
+    pub files: Vec<FileRecord>,
+    pub settings_before: Option<String>,
+    pub settings_after: Option<String>,
+    pub commands: Vec<String>,
+    #[serde(default)]
+    pub mcp_before: Option<Vec<McpRegistration>>, // Registrations of the batch's servers, env values blank (None = old journal)
+    #[serde(default)]
+    pub mcp_after: Vec<McpRegistration>,
+    #[serde(default)]
+    pub undone: bool,
+}
+
+impl Batch {
+    pub fn failed_count(&self) -> usize {
+        self.items.iter().filter(|i| !i.ok).count()
+    }
+
+    pub fn settings_changed(&self) -> bool {
+        self.settings_before != self.settings_after
+    }
+
+    /// Registrations to remove and to re-add so the batch's MCP servers are back
+    /// where they were (same scope, project and env var names)
+    pub fn mcp_undo(&self) -> Option<(Vec<McpRegistration>, Vec<McpRegistration>)> {
+        let before = self.mcp_before.as_ref()?;
+        let remove = self.mcp_after.iter().filter(|r| !before.contains(r)).cloned().collect();
+        let restore = before.iter().filter(|r| !self.mcp_after.contains(r)).cloned().collect();
+        Some((remove, restore))
+    }
+}
+
+/// Records one batch while it runs
+pub struct Journal {
+    dir: PathBuf,
+    pub batch: Batch,
+}
+
+impl Journal {
+    pub fn begin(dest_dir: &Path, action: &str, target: &str) -> Result<Self> {
+        let timestamp = unix_now();
+
+        let root = history_dir(dest_dir);
+        let mut id = format!("{}-{}", timestamp, action);
+        let mut n = 1;
+        while root.join(&id).exists() {
+            n += 1;
+            id = format!("{}-{}-{}", timestamp, action, n);
+        }
+
+        let dir = root.join(&id);
+        std::fs::create_dir_all(dir.join("files"))
+            .with_context(|| format!("Cannot create {}", dir.display()))?;
+
+        // Drop commands left over from earlier activity
+        crate::fs::take_command_log();
+
+        Ok(Self {
+            dir,
+            batch: Batch {
+                id,
+                timestamp,
+                action: action.to_string(),
+                target: target.to_string(),
+                items: Vec::new(),
+                files: Vec::new(),
+                settings_before: read_settings(dest_dir),
+                settings_after: None,
+                commands: Vec::new(),
+                mcp_before: None,
+                mcp_after: Vec::new(),
+                undone: false,
+            },
+        })
+    }
+
+    /// Back up a file before the batch writes or deletes it
+    pub fn record_file(&mut self, path: &Path) -> Result<()> {
+        if self.batch.files.iter().any(|f| f.path == path) {
+            return Ok(());
+        }
+
+        let backup = if path.is_file() {
+            let name = self.batch.files.len().to_string();
+            std::fs::copy(path, self.dir.join("files").join(&name))
+                .with_context(|| format!("Cannot back up {}", path.display()))?;
+            Some(name)
+        } else {
+            None
+        };
+
+        self.batch.files.push(FileRecord { path: path.to_path_buf(), backup });
+        Ok(())
+    }
+
+    /// Record the batch's MCP registrations before it runs (env values are dropped)
+    pub fn record_mcp_before(&mut self, registrations: Vec<McpRegistration>) {
+        self.batch.mcp_before = Some(without_env_values(registrations));
+    }
+
+    pub fn record_mcp_after(&mut self, registrations: Vec<McpRegistration>) {
+        self.batch.mcp_after = without_env_values(registrations);
+    }
+
+    /// Record an item result (a retried item replaces its earlier result)
+    pub fn record_item(&mut self, kind: ItemKind, name: String, ok: bool) {
+        match self.batch.items.iter_mut().find(|i| i.kind == kind && i.name == name) {
+            Some(item) => item.ok = ok,
+            None => self.batch.items.push(BatchItem { kind, name, ok }),
+        }
+    }
+
+    /// Write journal.json (called when the batch finishes, again after retries)
+    pub fn save(&mut self, dest_dir: &Path) -> Result<()> {
+        self.batch.commands.extend(crate::fs::take_command_log());
+        self.batch.settings_after = read_settings(dest_dir);
+        write_batch(&self.dir, &self.batch)
+    }
+}
+
+/// Keep env var names only: the values are secrets the command log redacts as well
+fn without_env_values(mut registrations: Vec<McpRegistration>) -> Vec<McpRegistration> {
+    for registration in &mut registrations {
+        for (_, value) in &mut registration.env {
+            value.clear();
+        }
+    }
+    registrations
+}
+
+fn read_settings(dest_dir: &Path) -> Option<String> {
+    std::fs::read_to_string(dest_dir.join("settings.json")).ok()
+}
+
+fn write_batch(dir: &Path, batch: &Batch) -> Result<()> {
+    let content = serde_json::to_string_pretty(batch)?;
+    std::fs::write(dir.join("journal.json"), content)?;
+    Ok(())
+}
+
+/// All recorded batches, newest first
+pub fn list_batches(dest_dir: &Path) -> Vec<Batch> {
+    let Ok(entries) = std::fs::read_dir(history_dir(dest_dir)) else {
+        return Vec::new();
+    };
+
+    let mut batches: Vec<Batch> = entries
+        .flatten()
+        .filter_map(|e| std::fs::read_to_string(e.path().join("journal.json")).ok())
+        .filter_map(|content| serde_json::from_str(&content).ok())
+        .collect();
+
+    batches.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+    batches
+}
+
+/// Revert the files and settings.json touched by a batch
+/// Returns log lines. MCP servers and plugins are reverted by the caller (CLI).
+pub fn undo(dest_dir: &Path, batch: &Batch) -> Result<Vec<String>> {
+    let dir = history_dir(dest_dir).join(&batch.id);
+    let mut log = Vec::new();
+
+    for record in &batch.files {
+        match &record.backup {
+            Some(name) => {
+                if let Some(parent) = record.path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(dir.join("files").join(name), &record.path)
+                    .with_context(|| format!("Cannot restore {}", record.path.display()))?;
+                log.push(format!("[OK] Restored {}", record.path.display()));
+            }
+            None if record.path.exists() => {
+                std::fs::remove_file(&record.path)
+                    .with_context(|| format!("Cannot delete {}", record.path.display()))?;
+                log.push(format!("[OK] Deleted {}", record.path.display()));
+            }
+            None => {}
+        }
+    }
+
+    if batch.settings_changed() {
+        revert_settings(dest_dir, batch, &mut log)?;
+    }
+
+    let mut undone = batch.clone();
+    undone.undone = true;
+    write_batch(&dir, &undone)?;
+
+    Ok(log)
+}
+
+/// Revert only what the batch changed in settings.json: keys edited since (Permissions or
+/// Env tab, doctor fixes, snapshot restores) keep their current value and are reported
+fn revert_settings(dest_dir: &Path, batch: &Batch, log: &mut Vec<String>) -> Result<()> {
+    let settings_path = dest_dir.join("settings.json");
+    let current = read_settings(dest_dir);
+    if current == batch.settings_after {
+        match &batch.settings_before {
+            Some(content) => std::fs::write(&settings_path, content)?,
+            None if settings_path.exists() => std::fs::remove_file(&settings_path)?,
+            None => {}
+        }
+        log.push("[OK] Reverted settings.json".to_string());
+        return Ok(());
+    }
+
+    let parse = |text: &Option<String>| match text {
+        Some(text) => serde_json::from_str::<Value>(text).ok().map(Some),
+        None => Some(None),
+    };
+    let (Some(before), Some(after), Some(now)) = (parse(&batch.settings_before), parse(&batch.settings_after), parse(&current))
+    else {
+        log.push("[SKIP] settings.json changed after this batch and is not valid JSON; left as is".to_string());
+        return Ok(());
+    };
+
+    let mut kept = Vec::new();
+    let reverted = revert_value(before.as_ref(), after.as_ref(), now.as_ref(), "", &mut kept);
+    if reverted != now {
+        match &reverted {
+            Some(value) => std::fs::write(&settings_path, serde_json::to_string_pretty(value)?)?,
+            None => std::fs::remove_file(&settings_path)?,
+        }
+        log.push("[OK] Reverted the batch's changes to settings.json".to_string());
+    }
+    if !kept.is_empty() {
+        log.push(format!("[SKIP] settings.json: kept {} (changed after this batch)", kept.join(", ")));
+    }
+    Ok(())
+}
+
+/// Three-way merge of one value (None = key missing): the batch's change is reverted where
+/// `now` still matches `after`, objects recurse key by key and arrays as sets. Keys changed
+/// since the batch are left and added to `kept`.
+fn revert_value(before: Option<&Value>, after: Option<&Value>, now: Option<&Value>, path: &str, kept: &mut Vec<String>) -> Option<Value> {
+    if before == after {
+        return now.cloned();
+    }
+    if now == after {
+        return before.cloned();
+    }
+
+    match (before, after, now) {
+        (Some(Value::Object(before)), Some(Value::Object(after)), Some(Value::Object(now))) => {
+            let mut merged = now.clone();
+            let keys: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+            for key in keys {
+                let path = key_path(path, key);
+                match revert_value(before.get(key), after.get(key), now.get(key), &path, kept) {
+                    Some(value) => merged.insert(key.clone(), value),
+                    None => merged.remove(key),
+                };
+            }
+            Some(Value::Object(merged))
+        }
+        (Some(Value::Array(before)), Some(Value::Array(after)), Some(Value::Array(now))) => {
+            let mut merged: Vec<Value> = now.iter().filter(|item| before.contains(item) || !after.contains(item)).cloned().collect();
+            for item in before {
+                if !after.contains(item) && !merged.contains(item) {
+                    merged.push(item.clone());
+                }
+            }
+            Some(Value::Array(merged))
+        }
+        _ => {
+            kept.push(if path.is_empty() { "the whole file".to_string() } else { path.to_string() });
+            now.cloned()
+        }
+    }
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Format a unix timestamp as `YYYY-MM-DD HH:MM` (UTC)
+pub fn format_timestamp(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, rem / 3600, rem % 3600 / 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_undo_restores_files_and_settings() {
+        let dest = temp_dir("history-undo");
+        let existing = dest.join("agents/existing.md");
+        let created = dest.join("agents/created.md");
+        std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        std::fs::write(&existing, "old").unwrap();
+        std::fs::write(dest.join("settings.json"), "{}").unwrap();
+
+        let mut journal = Journal::begin(&dest, "install", "Claude").unwrap();
+        journal.record_file(&existing).unwrap();
+        journal.record_file(&created).unwrap();
+
+        // Simulate the batch
+        std::fs::write(&existing, "new").unwrap();
+        std::fs::write(&created, "created").unwrap();
+        std::fs::write(dest.join("settings.json"), "{\"hooks\":{}}").unwrap();
+        journal.record_item(ItemKind::Component, "agents/existing.md".to_string(), true);
+        journal.save(&dest).unwrap();
+
+        let batches = list_batches(&dest);
+        assert_eq!(batches.len(), 1);
+        assert!(batches[0].settings_changed());
+
+        undo(&dest, &batches[0]).unwrap();
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "old");
+        assert!(!created.exists());
+        assert_eq!(std::fs::read_to_string(dest.join("settings.json")).unwrap(), "{}");
+        assert!(list_batches(&dest)[0].undone);
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_undo_keeps_settings_edited_after_the_batch() {
+        let dest = temp_dir("history-undo-edited");
+        std::fs::write(dest.join("settings.json"), r#"{"model":"opus","permissions":{"allow":["Read"]}}"#).unwrap();
+        let mut journal = Journal::begin(&dest, "install", "Claude").unwrap();
+
+        // The batch adds a hook and a permission, then the Permissions tab and the user edit on
+        std::fs::write(dest.join("settings.json"), r#"{"model":"opus","hooks":{"Stop":[]},"permissions":{"allow":["Read","Edit"]}}"#).unwrap();
+        journal.save(&dest).unwrap();
+        std::fs::write(dest.join("settings.json"), r#"{"model":"sonnet","hooks":{"Stop":["mine"]},"permissions":{"allow":["Read","Edit","Bash"]}}"#).unwrap();
+
+        let log = undo(&dest, &list_batches(&dest)[0]).unwrap();
+        let settings: Value = serde_json::from_str(&std::fs::read_to_string(dest.join("settings.json")).unwrap()).unwrap();
+        assert_eq!(settings, serde_json::json!({"model": "sonnet", "hooks": {"Stop": ["mine"]}, "permissions": {"allow": ["Read", "Bash"]}}));
+        assert!(log.contains(&"[SKIP] settings.json: kept hooks (changed after this batch)".to_string()), "{:?}", log);
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_mcp_undo_replays_scope_project_and_env() {
+        let reg = |scope, project: Option<&str>, env: &[(&str, &str)]| McpRegistration {
+            name: "docs".into(),
+            scope,
+            project: project.map(str::to_string),
+            env: env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            launch: None,
+        };
+        let local = reg(crate::mcp::McpScope::Local, Some("/work/app"), &[("TOKEN", "abc")]);
+        let user = reg(crate::mcp::McpScope::User, None, &[]);
+
+        let dest = temp_dir("history-mcp");
+        let mut journal = Journal::begin(&dest, "install", "Claude").unwrap();
+        assert_eq!(journal.batch.mcp_undo(), None); // Nothing recorded
+
+        // Reinstalled at user scope over a local registration with env
+        journal.record_mcp_before(vec![local.clone()]);
+        journal.record_mcp_after(vec![local.clone(), user.clone()]);
+        assert_eq!(journal.batch.mcp_undo(), Some((vec![user.clone()], Vec::new())));
+
+        // Removed: re-add at the same scope and project with the same env var names
+        journal.record_mcp_after(Vec::new());
+        let journaled = reg(crate::mcp::McpScope::Local, Some("/work/app"), &[("TOKEN", "")]);
+        assert_eq!(journal.batch.mcp_undo(), Some((Vec::new(), vec![journaled])));
+
+        // The value itself never reaches the journal
+        journal.save(&dest).unwrap();
+        let journal_json = std::fs::read_to_string(history_dir(&dest).join(&journal.batch.id).join("journal.json")).unwrap();
+        assert!(journal_json.contains("TOKEN") && !journal_json.contains("abc"));
+
+        let _ = std::fs::remove_dir_all(&dest);
+    }
+
+    #[test]
+    fn test_format_timestamp() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00");
+        assert_eq!(format_timestamp(1_709_210_096), "2024-02-29 12:34");
+    }
+}
@@ -0,0 +1,693 @@
+//! Named actions and the keys bound to them, per view
+//!
+//! Defaults can be changed in `~/.config/claude-installer/keymap.yaml`. Listing an action
+//! replaces all of its default keys:
+//!
+//! ```yaml
+//! list:
+//!   next-tab: [tab, "l"]
+//!   collapse-folder: left       # h is free for something else
+//!   tab-13: "+"                 # Env has no number key by default
+//! diff:
+//!   close: [q, esc, enter]
+//! ```
+//!
+//! Keys are a character (`a`, `H`, `?`), a name (`space`, `enter`, `esc`, `tab`, `backspace`,
+//! `delete`, `up`, `down`, `left`, `right`, `home`, `end`, `pageup`, `pagedown`) or either with
+//! `ctrl+`/`alt+`/`shift+` in front. `shift+h` is the same key as `H`; other characters are
+//! written shifted (`?`, not `shift+/`). A key bound to two actions of a view is reported.
+//! Text input (env edits, profile names, MCP env vars) is not remappable.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyModifiers};
+use serde::Deserialize;
+
+use crate::app::{Tab, View};
+
+/// Views with remappable keys (a section of keymap.yaml)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum KeyView {
+    List,
+    Diff,
+    History,
+    Snapshots,
+    Doctor,
+    Purge,
+    Profiles,
+    MergeReview,
+    Installing,
+}
+
+impl KeyView {
+    const ALL: [KeyView; 9] = [
+        KeyView::List,
+        KeyView::Diff,
+        KeyView::History,
+        KeyView::Snapshots,
+        KeyView::Doctor,
+        KeyView::Purge,
+        KeyView::Profiles,
+        KeyView::MergeReview,
+        KeyView::Installing,
+    ];
+
+    pub fn of(view: View) -> Option<Self> {
+        match view {
+            View::List => Some(Self::List),
+            View::Diff => Some(Self::Diff),
+            View::History => Some(Self::History),
+            View::Snapshots => Some(Self::Snapshots),
+            View::Doctor => Some(Self::Doctor),
+            View::Purge => Some(Self::Purge),
+            View::Profiles => Some(Self::Profiles),
+            View::MergeReview => Some(Self::MergeReview),
+            View::Installing => Some(Self::Installing),
+            View::CliSelection | View::Loading | View::EnvInput | View::ProjectPath => None,
+        }
+    }
+
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::List => "list",
+            Self::Diff => "diff",
+            Self::History => "history",
+            Self::Snapshots => "snapshots",
+            Self::Doctor => "doctor",
+            Self::Purge => "purge",
+            Self::Profiles => "profiles",
+            Self::MergeReview => "merge-review",
+            Self::Installing => "installing",
+        }
+    }
+
+    pub fn display(&self) -> &'static str {
+        match self {
+            Self::List => "List",
+            Self::Diff => "Diff",
+            Self::History => "History",
+            Self::Snapshots => "Snapshots",
+            Self::Doctor => "Doctor",
+            Self::Purge => "Purge",
+            Self::Profiles => "Profiles",
+            Self::MergeReview => "Merge review",
+            Self::Installing => "Installing",
+        }
+    }
+
+    /// Actions of the view with their default keys. When a key is bound to several actions,
+    /// the first one that applies wins, so tab-specific actions come before general ones.
+    fn defaults(&self) -> Vec<(Action, &'static [&'static str])> {
+        use Action::*;
+        match self {
+            Self::List => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (NextTab, &["tab"]),
+                (PrevTab, &["shift+tab"]),
+                (Tab(1), &["1"]),
+                (Tab(2), &["2"]),
+                (Tab(3), &["3"]),
+                (Tab(4), &["4"]),
+                (Tab(5), &["5"]),
+                (Tab(6), &["6"]),
+                (Tab(7), &["7"]),
+                (Tab(8), &["8"]),
+                (Tab(9), &["9"]),
+                (Tab(10), &["0"]),
+                (Tab(11), &["-"]),
+                (Tab(12), &["="]),
+                (Tab(13), &[]),
+                (CollapseFolder, &["h", "left"]),
+                (ExpandFolder, &["l", "right"]),
+                (Toggle, &["space"]),
+                (SelectAll, &["a"]),
+                (DeselectAll, &["n"]),
+                (EnvEdit, &["enter", "e"]),
+                (EnvReset, &["r"]),
+                (EnvUnset, &["u"]),
+                (Open, &["enter"]),
+                (Diff, &["d"]),
+                (Install, &["i"]),
+                (Remove, &["r"]),
+                (SetDefault, &["s"]),
+                (UnsetDefault, &["u"]),
+                (CycleScope, &["o"]),
+                (Browse, &["b"]),
+                (AddToCatalog, &["w"]),
+                (Conflicts, &["m"]),
+                (Profiles, &["P"]),
+                (History, &["H"]),
+                (Snapshots, &["S"]),
+                (Doctor, &["D"]),
+                (Purge, &["X"]),
+                (CycleTheme, &["t"]),
+                (Help, &["?"]),
+                (Quit, &["q"]),
+            ],
+            Self::Diff => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc"]),
+            ],
+            Self::History => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Undo, &["u"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc"]),
+            ],
+            Self::Snapshots => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Create, &["c"]),
+                (Restore, &["r"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc"]),
+            ],
+            Self::Doctor => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Fix, &["enter", "f"]),
+                (FixAll, &["F"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc"]),
+            ],
+            Self::Purge => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Confirm, &["y"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc", "n"]),
+            ],
+            Self::Profiles => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Load, &["enter"]),
+                (Save, &["s"]),
+                (Help, &["?"]),
+                (Close, &["q", "esc"]),
+            ],
+            Self::MergeReview => vec![
+                (Down, &["j", "down"]),
+                (Up, &["k", "up"]),
+                (Toggle, &["space"]),
+                (KeepLocal, &["l"]),
+                (TakeRepo, &["r"]),
+                (KeepLocalAll, &["L"]),
+                (TakeRepoAll, &["R"]),
+                (Apply, &["enter"]),
+                (Help, &["?"]),
+                (Close, &["esc", "q"]),
+            ],
+            Self::Installing => vec![
+                (Up, &["k", "up"]),
+                (Down, &["j", "down"]),
+                (Close, &["enter", "q", "esc"]),
+                (CancelPending, &["esc"]),
+                (CancelItem, &["x", "delete"]),
+                (Retry, &["r"]),
+                (Help, &["?"]),
+            ],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Help,
+    CycleTheme,
+    Up,
+    Down,
+    NextTab,
+    PrevTab,
+    Tab(u8), // Position in the tab bar, from 1
+    CollapseFolder,
+    ExpandFolder,
+    Toggle,
+    SelectAll,
+    DeselectAll,
+    Open,
+    Diff,
+    Install,
+    Remove,
+    SetDefault,
+    UnsetDefault,
+    CycleScope,
+    Browse,
+    AddToCatalog,
+    Conflicts,
+    EnvEdit,
+    EnvReset,
+    EnvUnset,
+    Profiles,
+    History,
+    Snapshots,
+    Doctor,
+    Purge,
+    Close,
+    Undo,
+    Create,
+    Restore,
+    Fix,
+    FixAll,
+    Confirm,
+    Load,
+    Save,
+    KeepLocal,
+    TakeRepo,
+    KeepLocalAll,
+    TakeRepoAll,
+    Apply,
+    CancelPending,
+    CancelItem,
+    Retry,
+}
+
+impl Action {
+    /// Name used in keymap.yaml
+    pub fn name(&self) -> String {
+        let name = match self {
+            Self::Tab(n) => return format!("tab-{}", n),
+            Self::Quit => "quit",
+            Self::Help => "help",
+            Self::CycleTheme => "cycle-theme",
+            Self::Up => "up",
+            Self::Down => "down",
+            Self::NextTab => "next-tab",
+            Self::PrevTab => "prev-tab",
+            Self::CollapseFolder => "collapse-folder",
+            Self::ExpandFolder => "expand-folder",
+            Self::Toggle => "toggle",
+            Self::SelectAll => "select-all",
+            Self::DeselectAll => "deselect-all",
+            Self::Open => "open",
+            Self::Diff => "diff",
+            Self::Install => "install",
+            Self::Remove => "remove",
+            Self::SetDefault => "set-default",
+            Self::UnsetDefault => "unset-default",
+            Self::CycleScope => "cycle-scope",
+            Self::Browse => "browse",
+            Self::AddToCatalog => "add-to-catalog",
+            Self::Conflicts => "conflicts",
+            Self::EnvEdit => "env-edit",
+            Self::EnvReset => "env-reset",
+            Self::EnvUnset => "env-unset",
+            Self::Profiles => "profiles",
+            Self::History => "history",
+            Self::Snapshots => "snapshots",
+            Self::Doctor => "doctor",
+            Self::Purge => "purge",
+            Self::Close => "close",
+            Self::Undo => "undo",
+            Self::Create => "create",
+            Self::Restore => "restore",
+            Self::Fix => "fix",
+            Self::FixAll => "fix-all",
+            Self::Confirm => "confirm",
+            Self::Load => "load",
+            Self::Save => "save",
+            Self::KeepLocal => "keep-local",
+            Self::TakeRepo => "take-repo",
+            Self::KeepLocalAll => "keep-local-all",
+            Self::TakeRepoAll => "take-repo-all",
+            Self::Apply => "apply",
+            Self::CancelPending => "cancel-pending",
+            Self::CancelItem => "cancel-item",
+            Self::Retry => "retry",
+        };
+        name.to_string()
+    }
+
+    /// What the action does, for the help overlay
+    pub fn description(&self) -> String {
+        let text = match self {
+            Self::Tab(n) => return format!("Go to tab {}", n),
+            Self::Quit => "Quit",
+            Self::Help => "Show this help",
+            Self::CycleTheme => "Next theme",
+            Self::Up => "Move up",
+            Self::Down => "Move down",
+            Self::NextTab => "Next tab",
+            Self::PrevTab => "Previous tab",
+            Self::CollapseFolder => "Collapse folder (or its parent)",
+            Self::ExpandFolder => "Expand folder",
+            Self::Toggle => "Toggle selection",
+            Self::SelectAll => "Select all",
+            Self::DeselectAll => "Deselect all",
+            Self::Open => "Expand/collapse folder or show diff",
+            Self::Diff => "Show diff",
+            Self::Install => "Install selected",
+            Self::Remove => "Remove selected",
+            Self::SetDefault => "Set as default",
+            Self::UnsetDefault => "Unset default",
+            Self::CycleScope => "Switch scope / settings file",
+            Self::Browse => "Browse marketplaces",
+            Self::AddToCatalog => "Add browsed plugins to plugins.yaml",
+            Self::Conflicts => "Review settings conflicts",
+            Self::EnvEdit => "Edit value",
+            Self::EnvReset => "Reset to repo value",
+            Self::EnvUnset => "Unset value",
+            Self::Profiles => "Profiles",
+            Self::History => "History",
+            Self::Snapshots => "Snapshots",
+            Self::Doctor => "Doctor",
+            Self::Purge => "Purge everything the installer manages",
+            Self::Close => "Close",
+            Self::Undo => "Undo batch",
+            Self::Create => "Create snapshot",
+            Self::Restore => "Restore snapshot",
+            Self::Fix => "Fix finding",
+            Self::FixAll => "Fix all findings",
+            Self::Confirm => "Confirm",
+            Self::Load => "Load profile",
+            Self::Save => "Save selection as profile",
+            Self::KeepLocal => "Keep local value",
+            Self::TakeRepo => "Take repo value",
+            Self::KeepLocalAll => "Keep all local values",
+            Self::TakeRepoAll => "Take all repo values",
+            Self::Apply => "Apply choices",
+            Self::CancelPending => "Cancel pending items and stop running ones",
+            Self::CancelItem => "Cancel or stop item",
+            Self::Retry => "Retry failed",
+        };
+        text.to_string()
+    }
+
+    /// Whether a List action does anything on `tab`
+    pub fn applies_on(&self, tab: Tab) -> bool {
+        match self {
+            Self::EnvEdit | Self::EnvReset | Self::EnvUnset => tab == Tab::Env,
+            Self::SetDefault | Self::UnsetDefault => matches!(tab, Tab::OutputStyles | Tab::Statusline),
+            Self::CycleScope => matches!(tab, Tab::McpServers | Tab::Permissions),
+            Self::Browse | Self::AddToCatalog => tab == Tab::Plugins,
+            Self::Conflicts => tab == Tab::Config,
+            _ => true,
+        }
+    }
+}
+
+/// A key with modifiers
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = spec;
+        // "+" alone is a key, "ctrl++" is ctrl and "+"
+        while let Some((prefix, tail)) = rest.split_once('+')
+            && !tail.is_empty()
+        {
+            modifiers |= match prefix.to_lowercase().as_str() {
+                "ctrl" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(anyhow!("Unknown modifier '{}' in '{}'", prefix, spec)),
+            };
+            rest = tail;
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_lowercase().as_str() {
+                "space" => KeyCode::Char(' '),
+                "enter" => KeyCode::Enter,
+                "esc" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                "backspace" => KeyCode::Backspace,
+                "delete" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" => KeyCode::PageUp,
+                "pagedown" => KeyCode::PageDown,
+                _ => return Err(anyhow!("Unknown key '{}'", spec)),
+            },
+        };
+        // The terminal reports the shifted character, not shift and the base key
+        if let KeyCode::Char(c) = code
+            && modifiers.contains(KeyModifiers::SHIFT)
+            && !c.is_alphabetic()
+        {
+            return Err(anyhow!("Write the shifted character instead of '{}'", spec));
+        }
+        Ok(Self::new(code, modifiers))
+    }
+
+    /// Normalized key: characters carry their own case (shift+h is H), BackTab is Shift+Tab
+    pub fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => Self {
+                code: KeyCode::Char(c.to_uppercase().next().unwrap_or(c)),
+                modifiers: modifiers - KeyModifiers::SHIFT,
+            },
+            KeyCode::BackTab => Self { code: KeyCode::Tab, modifiers: modifiers | KeyModifiers::SHIFT },
+            _ => Self { code, modifiers },
+        }
+    }
+
+    pub fn display(&self) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => "↑".to_string(),
+            KeyCode::Down => "↓".to_string(),
+            KeyCode::Left => "←".to_string(),
+            KeyCode::Right => "→".to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            other => format!("{:?}", other),
+        };
+        let mut prefix = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            prefix.push_str("C-");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            prefix.push_str("M-");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            prefix.push_str("S-");
+        }
+        prefix + &key
+    }
+}
+
+/// keymap.yaml entry: one key or several
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Clone, Debug)]
+pub struct Keymap {
+    views: BTreeMap<KeyView, Vec<(Action, Vec<Key>)>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let views = KeyView::ALL
+            .iter()
+            .map(|view| {
+                let bindings = view
+                    .defaults()
+                    .into_iter()
+                    .map(|(action, keys)| (action, keys.iter().map(|k| Key::parse(k).expect("default key")).collect()))
+                    .collect();
+                (*view, bindings)
+            })
+            .collect();
+        Self { views }
+    }
+}
+
+pub fn keymap_path() -> Option<PathBuf> {
+    crate::config::config_path().and_then(|p| p.parent().map(|d| d.join("keymap.yaml")))
+}
+
+impl Keymap {
+    /// Defaults with keymap.yaml applied. Also returns the entries that could not be used.
+    pub fn load() -> (Self, Vec<String>) {
+        let mut keymap = Self::default();
+        let Some(content) = keymap_path().and_then(|p| std::fs::read_to_string(p).ok()) else {
+            return (keymap, Vec::new());
+        };
+        let errors = keymap.apply(&content);
+        (keymap, errors)
+    }
+
+    /// Apply overrides in keymap.yaml format
+    fn apply(&mut self, content: &str) -> Vec<String> {
+        let sections: BTreeMap<String, BTreeMap<String, KeySpec>> = match serde_yaml::from_str(content) {
+            Ok(sections) => sections,
+            Err(e) => return vec![format!("Invalid keymap.yaml: {}", e)],
+        };
+
+        let mut errors = Vec::new();
+        for (section, entries) in sections {
+            let Some(view) = KeyView::ALL.iter().find(|v| v.key() == section) else {
+                errors.push(format!("keymap.yaml: unknown view '{}'", section));
+                continue;
+            };
+            let bindings = self.views.entry(*view).or_default();
+            let mut changed = Vec::new();
+            for (name, spec) in entries {
+                let Some((action, keys)) = bindings.iter_mut().find(|(a, _)| a.name() == name) else {
+                    errors.push(format!("keymap.yaml: {} has no action '{}'", section, name));
+                    continue;
+                };
+                let specs = match spec {
+                    KeySpec::One(key) => vec![key],
+                    KeySpec::Many(keys) => keys,
+                };
+                match specs.iter().map(|k| Key::parse(k)).collect::<Result<Vec<_>>>() {
+                    Ok(parsed) => {
+                        *keys = parsed;
+                        changed.push(*action);
+                    }
+                    Err(e) => errors.push(format!("keymap.yaml: {}.{}: {}", section, name, e)),
+                }
+            }
+            errors.extend(self.clashes(*view, &changed));
+        }
+        errors
+    }
+
+    /// Keys of `changed` actions that another action of the view also uses on some tab.
+    /// Overloads the defaults have on purpose (`r` is env reset on Env, remove elsewhere) are fine.
+    fn clashes(&self, view: KeyView, changed: &[Action]) -> Vec<String> {
+        let defaults = Self::default();
+        let default_keys = |action: &Action| defaults.bindings(view).iter().find(|(a, _)| a == action).map(|(_, keys)| keys.clone());
+        let bindings = self.bindings(view);
+        let mut clashes = Vec::new();
+        for (i, (first, first_keys)) in bindings.iter().enumerate() {
+            for (second, second_keys) in &bindings[i + 1..] {
+                if !changed.contains(first) && !changed.contains(second) {
+                    continue;
+                }
+                if !Tab::all().iter().any(|tab| first.applies_on(*tab) && second.applies_on(*tab)) {
+                    continue;
+                }
+                let (first_defaults, second_defaults) = (default_keys(first).unwrap_or_default(), default_keys(second).unwrap_or_default());
+                let overloaded = |key: &&Key| first_defaults.contains(key) && second_defaults.contains(key);
+                for key in first_keys.iter().filter(|k| second_keys.contains(k) && !overloaded(k)) {
+                    clashes.push(format!(
+                        "keymap.yaml: {}: {} is bound to {} and {} ({} wins)",
+                        view.key(),
+                        key.display(),
+                        first.name(),
+                        second.name(),
+                        first.name()
+                    ));
+                }
+            }
+        }
+        clashes
+    }
+
+    /// First action bound to the key that `applies` accepts
+    pub fn action(
+        &self,
+        view: KeyView,
+        code: KeyCode,
+        modifiers: KeyModifiers,
+        applies: impl Fn(Action) -> bool,
+    ) -> Option<Action> {
+        let key = Key::new(code, modifiers);
+        self.bindings(view)
+            .iter()
+            .find(|(action, keys)| keys.contains(&key) && applies(*action))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn bindings(&self, view: KeyView) -> &[(Action, Vec<Key>)] {
+        self.views.get(&view).map(|b| b.as_slice()).unwrap_or_default()
+    }
+
+    /// Keys of an action joined with "/" (empty when unbound)
+    pub fn keys(&self, view: KeyView, action: Action) -> String {
+        self.bindings(view)
+            .iter()
+            .filter(|(a, _)| *a == action)
+            .flat_map(|(_, keys)| keys.iter().map(|k| k.display()))
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// Status bar hints like "[j/↓/k/↑] Move  [i] Install". Unbound actions are left out.
+    pub fn hints(&self, view: KeyView, hints: &[(&[Action], &str)]) -> String {
+        hints
+            .iter()
+            .filter_map(|(actions, label)| {
+                let keys: Vec<String> = actions
+                    .iter()
+                    .map(|a| self.keys(view, *a))
+                    .filter(|k| !k.is_empty())
+                    .collect();
+                (!keys.is_empty()).then(|| format!("[{}] {}", keys.join("/"), label))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_and_lookup() {
+        let mut keymap = Keymap::default();
+        let any = |_| true;
+        assert_eq!(keymap.action(KeyView::List, KeyCode::BackTab, KeyModifiers::SHIFT, any), Some(Action::PrevTab));
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('H'), KeyModifiers::SHIFT, any), Some(Action::History));
+
+        // Overloaded keys resolve to the first action that applies on the tab
+        let on = |tab: Tab| move |a: Action| a.applies_on(tab);
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('r'), KeyModifiers::NONE, on(Tab::Env)), Some(Action::EnvReset));
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('r'), KeyModifiers::NONE, on(Tab::Agents)), Some(Action::Remove));
+
+        let errors = keymap.apply("list:\n  collapse-folder: left\n  tab-13: \"+\"\n  fly: x\ndiff:\n  close: [ctrl+c, hyper+q]\nnope: {}\n");
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('h'), KeyModifiers::NONE, any), None);
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('+'), KeyModifiers::NONE, any), Some(Action::Tab(13)));
+        assert_eq!(keymap.keys(KeyView::Diff, Action::Close), "q/Esc"); // Invalid entry leaves the defaults
+
+        assert_eq!(Key::parse("ctrl+d").unwrap().display(), "C-d");
+        assert_eq!(Key::parse("shift+h").unwrap(), Key::parse("H").unwrap());
+        assert!(Key::parse("shift+1").is_err());
+        assert_eq!(keymap.hints(KeyView::History, &[(&[Action::Down, Action::Up], "Move"), (&[Action::Undo], "Undo")]), "[j/↓/k/↑] Move  [u] Undo");
+    }
+
+    #[test]
+    fn test_duplicate_bindings_are_reported() {
+        let mut keymap = Keymap::default();
+        let errors = keymap.apply("list:\n  install: x\n  remove: [x, R]\n  history: shift+h\n");
+        assert_eq!(errors, vec!["keymap.yaml: list: x is bound to install and remove (install wins)"]);
+        assert_eq!(keymap.action(KeyView::List, KeyCode::Char('H'), KeyModifiers::SHIFT, |_| true), Some(Action::History));
+
+        // Default overloads on different tabs are not clashes
+        assert!(Keymap::default().apply("list:\n  env-reset: r\n").is_empty());
+    }
+}
@@ -0,0 +1,350 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::component::{Component, ComponentType};
+use crate::frontmatter::parse_frontmatter;
+
+/// Hook events accepted by Claude Code in settings.json
+pub const HOOK_EVENTS: &[&str] = &[
+    "PreToolUse",
+    "PostToolUse",
+    "PostToolUseFailure",
+    "PermissionRequest",
+    "Notification",
+    "UserPromptSubmit",
+    "Stop",
+    "SubagentStart",
+    "SubagentStop",
+    "PreCompact",
+    "SessionStart",
+    "SessionEnd",
+];
+
+/// Hook types accepted by Claude Code
+const HOOK_TYPES: &[&str] = &["command", "prompt"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    Warning,
+    Error,
+}
+
+impl LintLevel {
+    pub fn display(&self) -> &str {
+        match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LintIssue {
+    pub level: LintLevel,
+    pub message: String,
+}
+
+impl LintIssue {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self { level: LintLevel::Error, message: message.into() }
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self { level: LintLevel::Warning, message: message.into() }
+    }
+}
+
+/// Validate scanned components and attach issues to each of them
+pub fn validate(components: &mut [Component]) {
+    // Frontmatter name -> component indices, per type (duplicate detection)
+    let mut names: HashMap<(String, String), Vec<usize>> = HashMap::new();
+    // Agent keyword (lowercased) -> component indices (collision detection)
+    let mut keywords: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+
+    // Skill entry files, relative to skills/ with forward slashes
+    let skill_files: HashSet<String> = components
+        .iter()
+        .filter(|c| c.component_type == ComponentType::Skills)
+        .map(|c| c.name.replace('\\', "/"))
+        .filter(|name| name.ends_with("SKILL.md"))
+        .collect();
+
+    for (idx, c) in components.iter_mut().enumerate() {
+        let mut issues = std::mem::take(&mut c.issues); // Scan errors (broken hook.yaml)
+
+        match c.component_type {
+            ComponentType::Hooks => validate_hook(c, &mut issues),
+            ComponentType::Skills => validate_skill_folder(c, &skill_files, &mut issues),
+            _ => {}
+        }
+
+        if is_markdown(&c.source_path)
+            && let Some((name, agent_keywords)) = validate_markdown(c, &mut issues)
+        {
+            names
+                .entry((c.component_type.display_name().to_string(), name))
+                .or_default()
+                .push(idx);
+            for keyword in agent_keywords {
+                keywords.entry(keyword.to_lowercase()).or_default().push(idx);
+            }
+        }
+
+        c.issues = issues;
+    }
+
+    for ((type_name, name), indices) in names {
+        if indices.len() < 2 {
+            continue;
+        }
+        for &idx in &indices {
+            let others = other_names(components, &indices, idx);
+            components[idx].issues.push(LintIssue::error(format!(
+                "Duplicate {} name '{}' (also in {})",
+                type_name.trim_end_matches('s'),
+                name,
+                others
+            )));
+        }
+    }
+
+    for (keyword, indices) in keywords {
+        let mut indices = indices;
+        indices.dedup();
+        if indices.len() < 2 {
+            continue;
+        }
+        for &idx in &indices {
+            let others = other_names(components, &indices, idx);
+            components[idx].issues.push(LintIssue::warning(format!(
+                "Keyword '{}' also used by {}",
+                keyword, others
+            )));
+        }
+    }
+}
+
+/// Count errors and warnings across components
+pub fn summarize(components: &[Component]) -> (usize, usize) {
+    components.iter().flat_map(|c| &c.issues).fold((0, 0), |(e, w), issue| match issue.level {
+        LintLevel::Error => (e + 1, w),
+        LintLevel::Warning => (e, w + 1),
+    })
+}
+
+fn other_names(components: &[Component], indices: &[usize], current: usize) -> String {
+    indices
+        .iter()
+        .filter(|&&i| i != current)
+        .map(|&i| components[i].display_name())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn is_markdown(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("md")
+}
+
+/// Returns the frontmatter name (for duplicate checks) and agent keywords
+fn validate_markdown(c: &Component, issues: &mut Vec<LintIssue>) -> Option<(String, Vec<String>)> {
+    let content = match std::fs::read_to_string(&c.source_path) {
+        Ok(content) => content,
+        Err(e) => {
+            issues.push(LintIssue::error(format!("Cannot read file: {}", e)));
+            return None;
+        }
+    };
+
+    let is_skill_entry = c.component_type == ComponentType::Skills
+        && c.source_path.file_name().and_then(|n| n.to_str()) == Some("SKILL.md");
+
+    let frontmatter = match parse_frontmatter(&content) {
+        Ok(Some((fm, _))) => fm,
+        Ok(None) => {
+            match c.component_type {
+                ComponentType::Agents => issues.push(LintIssue::error(
+                    "Missing frontmatter (name, description, keywords)",
+                )),
+                ComponentType::Skills if is_skill_entry => issues.push(LintIssue::error(
+                    "SKILL.md is missing frontmatter (name, description)",
+                )),
+                _ => {}
+            }
+            return None;
+        }
+        Err(e) => {
+            issues.push(LintIssue::error(e.to_string()));
+            return None;
+        }
+    };
+
+    let required: &[&str] = match c.component_type {
+        ComponentType::Agents => &["name", "description"],
+        ComponentType::Skills if is_skill_entry => &["name", "description"],
+        ComponentType::OutputStyles => &["name"],
+        _ => &[],
+    };
+
+    for key in required {
+        let present = match *key {
+            "name" => frontmatter.name.is_some(),
+            "description" => frontmatter.description.is_some(),
+            _ => true,
+        };
+        if !present {
+            issues.push(LintIssue::error(format!("Missing required frontmatter key '{}'", key)));
+        }
+    }
+
+    let mut agent_keywords = Vec::new();
+    if c.component_type == ComponentType::Agents {
+        if frontmatter.keywords.is_empty() {
+            issues.push(LintIssue::warning("No keywords: inject_guide will never inject this agent"));
+        }
+        agent_keywords = frontmatter.keywords.clone();
+    }
+
+    // Only agents and skills are addressed by frontmatter name
+    let name = match c.component_type {
+        ComponentType::Agents => frontmatter.name,
+        ComponentType::Skills if is_skill_entry => frontmatter.name,
+        _ => None,
+    };
+
+    name.map(|n| (n, agent_keywords))
+}
+
+fn validate_hook(c: &Component, issues: &mut Vec<LintIssue>) {
+    let Some(config) = &c.hook_config else {
+        return;
+    };
+
+    if !HOOK_EVENTS.contains(&config.event.as_str()) {
+        issues.push(LintIssue::error(format!(
+            "Unknown hook event '{}' (expected one of: {})",
+            config.event,
+            HOOK_EVENTS.join(", ")
+        )));
+    }
+
+    if !HOOK_TYPES.contains(&config.hook_type.as_str()) {
+        issues.push(LintIssue::error(format!("Unknown hook type '{}'", config.hook_type)));
+    }
+
+    match config.timeout {
+        Some(0) => issues.push(LintIssue::warning("Hook timeout is 0")),
+        Some(t) if u64::from(t) > crate::doctor::MAX_HOOK_TIMEOUT => {
+            issues.push(LintIssue::warning(format!("Hook timeout is {}s (timeouts are in seconds)", t)))
+        }
+        _ => {}
+    }
+}
+
+/// A directory under skills/ that holds files must have a SKILL.md in itself or an ancestor.
+/// Checked against the scanned skills so a SKILL.md from another source layer counts.
+fn validate_skill_folder(c: &Component, skill_files: &HashSet<String>, issues: &mut Vec<LintIssue>) {
+    let parts: Vec<&str> = c.name.split(['/', '\\']).collect();
+    if parts.len() < 2 {
+        return; // Loose file at skills/ root
+    }
+
+    for depth in 1..parts.len() {
+        if skill_files.contains(&format!("{}/SKILL.md", parts[..depth].join("/"))) {
+            return;
+        }
+    }
+
+    let folder = parts[..parts.len() - 1].join("/");
+    issues.push(LintIssue::error(format!("Skill folder '{}' has no SKILL.md", folder)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{temp_dir, write};
+    use crate::component::{HookConfig, InstallStatus};
+    use std::path::PathBuf;
+
+    fn component(component_type: ComponentType, root: &Path, rel: &str) -> Component {
+        Component::new(component_type, rel.to_string(), root.join(rel), PathBuf::from(rel), InstallStatus::New)
+    }
+
+    #[test]
+    fn test_agent_checks() {
+        let dir = temp_dir("lint-agents");
+        write(&dir, "good.md", "---\nname: good\ndescription: ok\nkeywords: [rust, cli]\n---\nbody");
+        write(&dir, "broken.md", "---\nname: [oops\n---\nbody");
+        write(&dir, "dup.md", "---\nname: good\ndescription: dup\nkeywords: [Rust]\n---\nbody");
+        write(&dir, "bare.md", "no frontmatter");
+
+        let mut components = vec![
+            component(ComponentType::Agents, &dir, "good.md"),
+            component(ComponentType::Agents, &dir, "broken.md"),
+            component(ComponentType::Agents, &dir, "dup.md"),
+            component(ComponentType::Agents, &dir, "bare.md"),
+        ];
+        validate(&mut components);
+
+        let has = |idx: usize, level: LintLevel, needle: &str| {
+            components[idx].issues.iter().any(|i| i.level == level && i.message.contains(needle))
+        };
+
+        assert!(has(0, LintLevel::Error, "Duplicate agent name 'good'"));
+        assert!(has(0, LintLevel::Warning, "Keyword 'rust'"));
+        assert!(has(1, LintLevel::Error, "Invalid YAML"));
+        assert!(has(2, LintLevel::Error, "Duplicate agent name"));
+        assert!(has(3, LintLevel::Error, "Missing frontmatter"));
+        assert!(!has(0, LintLevel::Warning, "Keyword 'cli'"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_skill_folder_without_skill_md() {
+        let dir = temp_dir("lint-skills");
+        write(&dir, "with-skill/SKILL.md", "---\nname: with-skill\ndescription: ok\n---\n");
+        write(&dir, "with-skill/references/a.md", "ref");
+        write(&dir, "no-skill/notes.md", "notes");
+
+        let mut components = vec![
+            component(ComponentType::Skills, &dir, "with-skill/SKILL.md"),
+            component(ComponentType::Skills, &dir, "with-skill/references/a.md"),
+            component(ComponentType::Skills, &dir, "no-skill/notes.md"),
+        ];
+        validate(&mut components);
+
+        assert!(components[0].issues.is_empty());
+        assert!(components[1].issues.is_empty());
+        assert_eq!(components[2].issues.len(), 1);
+        assert!(components[2].issues[0].message.contains("'no-skill' has no SKILL.md"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unknown_hook_event() {
+        let config = HookConfig {
+            name: "my-hook".to_string(),
+            event: "OnPrompt".to_string(),
+            hook_type: "command".to_string(),
+            timeout: Some(5000),  // Milliseconds by mistake
+            description: None,
+            requires: Vec::new(),
+        };
+        let mut components = vec![
+            Component::new(
+                ComponentType::Hooks,
+                "my-hook".to_string(),
+                PathBuf::from("my-hook_linux"),
+                PathBuf::from("my-hook_linux"),
+                InstallStatus::New,
+            )
+            .with_hook_config(config),
+        ];
+        validate(&mut components);
+
+        assert_eq!(summarize(&components), (1, 1));
+        assert!(components[0].issues[0].message.contains("Unknown hook event 'OnPrompt'"));
+        assert!(components[0].issues[1].message.contains("timeouts are in seconds"));
+    }
+}
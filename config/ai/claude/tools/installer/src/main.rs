@@ -1,18 +1,41 @@
 mod app;
+mod cli;
 mod component;
+mod config;
+mod config_file;
+mod conflict;
+mod deps;
+mod doctor;
+mod env_var;
+mod frontmatter;
+mod history;
+mod keymap;
+mod lint;
 mod mcp;
+mod permission;
 mod plugin;
+mod profile;
+mod purge;
+mod remote;
+mod report;
+mod snapshot;
 mod fs;
 mod tree;
 mod ui;
 mod theme;
+mod watch;
+#[cfg(test)]
+mod test_util;
 
 use std::io;
 use std::thread;
 use anyhow::Result;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers, poll},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton,
+        MouseEvent, MouseEventKind, poll,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, Clear, ClearType},
     cursor::MoveTo,
@@ -20,12 +43,23 @@ use crossterm::{
 use ratatui::{backend::CrosstermBackend, Terminal};
 
 use app::App;
+use keymap::{Action, KeyView};
+
+/// Parallel install/remove workers (file copies and CLI invocations)
+const MAX_WORKERS: usize = 4;
 
 fn main() -> Result<()> {
+    // Subcommands run headless (no TUI)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        let code = cli::run(&args)?;
+        std::process::exit(code);
+    }
+
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, Clear(ClearType::All), MoveTo(0, 0))?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture, Clear(ClearType::All), MoveTo(0, 0))?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
@@ -113,7 +147,7 @@ fn main() -> Result<()> {
 
     // Restore terminal
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableMouseCapture, LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
     if let Err(err) = result {
@@ -130,99 +164,200 @@ where
     use std::sync::mpsc::{self, TryRecvError};
     use std::thread;
 
-    // Channel for process results
-    let (process_tx, process_rx) = mpsc::channel::<Result<String>>();
-    let mut processing_active = false;
+    // Channel for worker results: (item slot, result, duration)
+    let (process_tx, process_rx) = mpsc::channel::<(usize, Result<String>, Duration)>();
+    let mut running_workers = 0;
 
     // Channel for refresh results
     type RefreshResult = (Vec<component::Component>, Vec<mcp::McpServer>, Vec<plugin::Plugin>);
     let (refresh_tx, refresh_rx) = mpsc::channel::<Result<RefreshResult>>();
 
+    // Live refresh after changes on disk (see watch.rs) and lazy MCP/plugin status;
+    // uses the refresh channel when no batch refresh runs
+    let mut watcher: Option<watch::Watcher> = None;
+    let mut watch_started = false;
+    let mut pending = watch::Changes::default();
+    let mut live_refresh: Option<watch::Changes> = None; // Scans of the refresh in flight
+    let mut failed_load: Option<app::Tab> = None; // Lazy load that failed; retried when the tab is shown again
+
     loop {
+        app.poll_remote_fetch();
         terminal.draw(|f| ui::draw(f, app))?;
 
+        if !watch_started && app.current_view == app::View::List {
+            watch_started = true;
+            let managed: Vec<std::path::PathBuf> = app.components.iter().map(|c| c.dest_path.clone()).collect();
+            match watch::Watcher::start(&app.source_layers, &app.dest_dir, &managed) {
+                Ok(w) => watcher = Some(w),
+                Err(e) => app.status_message = Some(format!("Live refresh off: {}", e)),
+            }
+        }
+        if let Some(w) = watcher.as_mut() {
+            // A batch rescans everything when it finishes, so its own writes are not queued
+            let changes = w.changes();
+            if app.current_view != app::View::Installing {
+                pending.merge(changes);
+            }
+        }
+
+        if failed_load.is_some_and(|tab| tab != app.tab) {
+            failed_load = None;
+        }
+        if app.current_view == app::View::List && failed_load.is_none() {
+            pending.mcp |= app.tab == app::Tab::McpServers && !app.mcp_loaded;
+            pending.plugins |= app.tab == app::Tab::Plugins && !app.plugins_loaded;
+        }
+
+        if let Some(scanned) = live_refresh
+            && !app.refreshing
+        {
+            match refresh_rx.try_recv() {
+                Ok(_) if app.current_view == app::View::Installing => {
+                    // The batch indexes into the current lists; scan again once it is closed
+                    pending.merge(scanned);
+                    live_refresh = None;
+                }
+                Ok(Ok((components, mcp_servers, plugins))) => {
+                    app.apply_live_refresh(components, mcp_servers, plugins);
+                    app.mcp_loaded |= scanned.mcp;
+                    app.plugins_loaded |= scanned.plugins;
+                    live_refresh = None;
+                }
+                Ok(Err(e)) => {
+                    // Not retried until the next change on disk, or until the tab is shown again
+                    // when its status was never loaded
+                    let lazy = (scanned.mcp && !app.mcp_loaded) || (scanned.plugins && !app.plugins_loaded);
+                    app.status_message = Some(if lazy {
+                        format!("Loading status failed: {}", e)
+                    } else {
+                        format!("Refresh failed: {}", e)
+                    });
+                    if lazy {
+                        failed_load = Some(app.tab);
+                    }
+                    live_refresh = None;
+                }
+                Err(TryRecvError::Empty) => {}
+                Err(TryRecvError::Disconnected) => live_refresh = None,
+            }
+        } else if pending.any() && !app.refreshing && !matches!(app.current_view, app::View::Installing | app::View::Loading) {
+            // Re-run only the scans the changes affect
+            let changes = std::mem::take(&mut pending);
+            live_refresh = Some(changes);
+
+            let tx_clone = refresh_tx.clone();
+            let source_layers = app.source_layers.clone();
+            let dest_dir = app.dest_dir.clone();
+            let scan_cache = app.scan_cache.clone();
+            let target_cli = app.target_cli.unwrap_or(app::TargetCli::Claude);
+            let (components, mcp_servers, plugins) = (
+                (!changes.components).then(|| app.components.clone()),
+                (!changes.mcp).then(|| app.mcp_servers.clone()),
+                (!changes.plugins).then(|| app.plugins.clone()),
+            );
+
+            thread::spawn(move || {
+                use crate::fs;
+
+                let result = (|| -> Result<RefreshResult> {
+                    let components = match components {
+                        Some(components) => components,
+                        None => fs::scanner::scan_components(&source_layers, &dest_dir, target_cli, scan_cache.as_deref())?,
+                    };
+                    let mcp_servers = match mcp_servers {
+                        Some(mcp_servers) => mcp_servers,
+                        None => fs::scanner::scan_mcp_servers(&source_layers, target_cli, &dest_dir)?,
+                    };
+                    let plugins = match plugins {
+                        Some(plugins) => plugins,
+                        None => fs::scanner::scan_plugins(&source_layers)?,
+                    };
+                    Ok((components, mcp_servers, plugins))
+                })();
+
+                let _ = tx_clone.send(result);
+            });
+        }
+
         match app.current_view {
             app::View::Installing => {
                 // Check for input (non-blocking with short timeout)
                 if poll(Duration::from_millis(100))? {
-                    if let Event::Key(key) = event::read()? {
-                        if key.kind == KeyEventKind::Press {
-                            handle_installing_input(app, key.code)?;
+                    match event::read()? {
+                        Event::Key(key) if key.kind == KeyEventKind::Press && app.show_help => app.show_help = false,
+                        Event::Key(key) if key.kind == KeyEventKind::Press => {
+                            handle_installing_input(app, key.code, key.modifiers)?;
                         }
+                        Event::Mouse(mouse) => handle_mouse_input(app, mouse)?,
+                        _ => {}
                     }
                 }
 
                 // Update animation
                 app.tick();
 
-                // Check if a processing thread completed
-                if processing_active {
-                    match process_rx.try_recv() {
-                        Ok(result) => {
-                            processing_active = false;
-                            match result {
-                                Ok(msg) => app.processing_log.push(msg),
-                                Err(e) => app.processing_log.push(format!("[ERR] {}", e)),
-                            }
-                            // Update progress
-                            let progress = app.processing_progress.unwrap_or(0) + 1;
-                            app.processing_progress = Some(progress);
-
-                            // Check if all done
-                            if app.processing_queue.is_empty() {
-                                app.start_finish_processing();
-                            }
-                        }
-                        Err(TryRecvError::Empty) => {
-                            // Still processing, continue
-                        }
-                        Err(TryRecvError::Disconnected) => {
-                            // Thread crashed, mark as error
-                            processing_active = false;
-                            app.processing_log.push("[ERR] Process thread crashed".to_string());
-                            if app.processing_queue.is_empty() {
-                                app.start_finish_processing();
-                            }
-                        }
-                    }
+                // Collect finished workers
+                while let Ok((slot, result, elapsed)) = process_rx.try_recv() {
+                    running_workers -= 1;
+                    let msg = result.unwrap_or_else(|e| format!("[ERR] {}", e));
+                    app.finish_process_item(slot, msg, elapsed);
                 }
 
-                // Start next item if not currently processing
-                if !processing_active && !app.processing_queue.is_empty() {
-                    let idx = app.processing_queue.remove(0);
-                    processing_active = true;
+                // Fill free workers. Only the writes to shared config files are serialized
+                // (see the locks in fs::installer); items wait for their batch dependencies.
+                while running_workers < MAX_WORKERS
+                    && let Some(slot) = app.next_pending_item()
+                {
+                    running_workers += 1;
 
-                    // Add "Installing/Removing..." message
-                    let item_name = get_item_name(app, idx);
+                    let idx = app.processing_items[slot].idx;
                     let action = if app.is_removing { "Removing" } else { "Installing" };
-                    app.processing_log.push(format!("{} {}...", action, item_name));
+                    app.processing_log.push(format!("{} {}...", action, app.processing_items[slot].name));
 
                     let tx_clone = process_tx.clone();
                     let is_removing = app.is_removing;
                     let tab = app.tab;
                     let target_cli = app.target_cli.unwrap_or(app::TargetCli::Claude);
                     let process_data = prepare_process_data(app, idx);
+                    let cancel = app.processing_items[slot].cancel.clone();
 
                     thread::spawn(move || {
-                        let result = execute_process_step(process_data, is_removing, tab, target_cli);
-                        let _ = tx_clone.send(result);
+                        fs::set_cancel_handle(Some(cancel));
+                        let started = Instant::now();
+                        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                            execute_process_step(process_data, is_removing, tab, target_cli)
+                        }))
+                        .unwrap_or_else(|_| Err(anyhow::anyhow!("Process thread crashed")));
+                        let _ = tx_clone.send((slot, result, started.elapsed()));
                     });
-                } else if !processing_active && app.processing_queue.is_empty() && app.needs_refresh && !app.refreshing {
+                }
+
+                if running_workers == 0
+                    && app.all_items_finished()
+                    && !app.needs_refresh
+                    && !app.refreshing
+                    && !app.processing_complete
+                {
+                    app.start_finish_processing();
+                }
+
+                if app.needs_refresh && !app.refreshing && live_refresh.is_none() {
                     // Start background refresh thread
                     app.refreshing = true;
 
                     let tx_clone = refresh_tx.clone();
-                    let source_dir = app.source_dir.clone();
+                    let source_layers = app.source_layers.clone();
                     let dest_dir = app.dest_dir.clone();
+                    let scan_cache = app.scan_cache.clone();
                     let target_cli = app.target_cli.unwrap_or(app::TargetCli::Claude);
 
                     thread::spawn(move || {
                         use crate::fs;
 
                         let result = (|| -> Result<RefreshResult> {
-                            let components = fs::scanner::scan_components(&source_dir, &dest_dir, target_cli)?;
-                            let mcp_servers = fs::scanner::scan_mcp_servers(&source_dir, target_cli, &dest_dir)?;
-                            let plugins = fs::scanner::scan_plugins(&source_dir)?;
+                            let components = fs::scanner::scan_components(&source_layers, &dest_dir, target_cli, scan_cache.as_deref())?;
+                            let mcp_servers = fs::scanner::scan_mcp_servers(&source_layers, target_cli, &dest_dir)?;
+                            let plugins = fs::scanner::scan_plugins(&source_layers)?;
                             Ok((components, mcp_servers, plugins))
                         })();
 
@@ -314,14 +449,28 @@ where
                 }
             }
             _ => {
-                if let Event::Key(key) = event::read()? {
-                    if key.kind == KeyEventKind::Press {
+                // Wake up regularly for live refresh results
+                if !poll(Duration::from_millis(100))? {
+                    continue;
+                }
+                match event::read()? {
+                    // Any key closes the help overlay
+                    Event::Key(key) if key.kind == KeyEventKind::Press && app.show_help => app.show_help = false,
+                    Event::Key(key) if key.kind == KeyEventKind::Press => {
                         match app.current_view {
                             app::View::List => handle_list_input(app, key.code, key.modifiers)?,
-                            app::View::Diff => handle_diff_input(app, key.code)?,
+                            app::View::Diff => handle_diff_input(app, key.code, key.modifiers)?,
+                            app::View::History => handle_history_input(app, key.code, key.modifiers)?,
+                            app::View::Snapshots => handle_snapshots_input(app, key.code, key.modifiers)?,
+                            app::View::Doctor => handle_doctor_input(app, key.code, key.modifiers)?,
+                            app::View::Purge => handle_purge_input(app, key.code, key.modifiers)?,
+                            app::View::Profiles => handle_profiles_input(app, key.code, key.modifiers)?,
+                            app::View::MergeReview => handle_merge_review_input(app, key.code, key.modifiers)?,
                             app::View::CliSelection | app::View::Loading | app::View::EnvInput | app::View::ProjectPath | app::View::Installing => {} // Handled above
                         }
                     }
+                    Event::Mouse(mouse) => handle_mouse_input(app, mouse)?,
+                    _ => {}
                 }
             }
         }
@@ -333,23 +482,31 @@ where
 }
 
 fn handle_list_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
-    match key {
-        KeyCode::Char('q') => app.should_quit = true,
-        KeyCode::Char('t') => {
-            app.theme.toggle();
-            app.status_message = Some(format!("Theme: {}", app.theme.mode().name()));
-        }
-        // Tab navigation: Tab = next, Shift+Tab = prev
-        KeyCode::Tab => {
-            if modifiers.contains(KeyModifiers::SHIFT) {
-                app.prev_tab();
-            } else {
-                app.next_tab();
-            }
+    // Typing a new value on the Env tab
+    if app.env_edit.is_some() {
+        match key {
+            KeyCode::Esc => app.env_edit_cancel(),
+            KeyCode::Enter => app.submit_env_edit()?,
+            KeyCode::Backspace => app.env_edit_backspace(),
+            KeyCode::Char(c) => app.env_edit_char(c),
+            _ => {}
         }
-        KeyCode::BackTab => app.prev_tab(), // Some terminals send BackTab for Shift+Tab
-        // h/l keys and Left/Right arrow keys: folder navigation
-        KeyCode::Char('h') | KeyCode::Left => {
+        return Ok(());
+    }
+
+    let tab = app.tab;
+    let Some(action) = app.keymap.action(KeyView::List, key, modifiers, |a| a.applies_on(tab)) else {
+        return Ok(());
+    };
+    match action {
+        Action::Quit => app.should_quit = true,
+        Action::Help => app.show_help = true,
+        Action::CycleTheme => app.cycle_theme(),
+        Action::NextTab => app.next_tab(),
+        Action::PrevTab => app.prev_tab(),
+        // Direct tab selection (1-0, -, = by default)
+        Action::Tab(n) => app.set_tab(n as usize - 1),
+        Action::CollapseFolder => {
             if app.is_cursor_on_folder() && app.is_current_folder_expanded() {
                 // On expanded folder: collapse it
                 app.collapse_folder();
@@ -358,34 +515,26 @@ fn handle_list_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Re
                 app.collapse_parent_folder();
             }
         }
-        KeyCode::Char('l') | KeyCode::Right => {
+        Action::ExpandFolder => {
             if app.is_cursor_on_folder() {
                 // On folder: expand it
                 app.expand_folder();
             }
             // On file: do nothing
         }
-        // Number keys 1-0 for direct tab selection
-        KeyCode::Char('1') => app.set_tab(0),  // Agents
-        KeyCode::Char('2') => app.set_tab(1),  // Commands
-        KeyCode::Char('3') => app.set_tab(2),  // Contexts
-        KeyCode::Char('4') => app.set_tab(3),  // Rules
-        KeyCode::Char('5') => app.set_tab(4),  // Skills
-        KeyCode::Char('6') => app.set_tab(5),  // Hooks
-        KeyCode::Char('7') => app.set_tab(6),  // OutputStyles
-        KeyCode::Char('8') => app.set_tab(7),  // Statusline
-        KeyCode::Char('9') => app.set_tab(8),  // Config
-        KeyCode::Char('0') => app.set_tab(9),  // MCP
-        KeyCode::Char('-') => app.set_tab(10), // Plugins
         // List navigation
-        KeyCode::Down | KeyCode::Char('j') => app.next_item(),
-        KeyCode::Up | KeyCode::Char('k') => app.prev_item(),
+        Action::Down => app.next_item(),
+        Action::Up => app.prev_item(),
         // Selection
-        KeyCode::Char(' ') => app.toggle_selected(),
-        KeyCode::Char('a') => app.select_all(),
-        KeyCode::Char('n') => app.deselect_all(),
+        Action::Toggle => app.toggle_selected(),
+        Action::SelectAll => app.select_all(),
+        Action::DeselectAll => app.deselect_all(),
+        // Env tab: edit, reset to the repo value, unset
+        Action::EnvEdit => app.start_env_edit(),
+        Action::EnvReset => app.reset_env_var()?,
+        Action::EnvUnset => app.unset_env_var()?,
         // Actions
-        KeyCode::Enter => {
+        Action::Open => {
             if app.is_cursor_on_folder() {
                 // On folder: toggle expand/collapse
                 app.toggle_folder_expand();
@@ -394,54 +543,211 @@ fn handle_list_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Re
                 app.show_diff()?;
             }
         }
-        KeyCode::Char('d') => app.show_diff()?,
-        KeyCode::Char('i') => app.install_selected()?,
-        KeyCode::Char('r') => app.remove_selected()?,
-        KeyCode::Char('s') => {
-            // 's' sets default for OutputStyles or Statusline tabs
-            if app.tab == app::Tab::OutputStyles {
-                app.set_default_style()?;
-            } else if app.tab == app::Tab::Statusline {
-                app.set_statusline()?;
-            }
-        }
-        KeyCode::Char('u') => {
-            // 'u' unsets default for OutputStyles or Statusline tabs
-            if app.tab == app::Tab::OutputStyles {
-                app.unset_default_style()?;
-            } else if app.tab == app::Tab::Statusline {
-                app.unset_statusline()?;
-            }
-        }
-        KeyCode::Char('o') => {
-            // 'o' toggles MCP scope (user/local) when on MCP tab
-            if app.tab == app::Tab::McpServers {
-                app.toggle_mcp_scope();
-            }
+        Action::Diff => app.show_diff()?,
+        Action::Install => app.install_selected()?,
+        Action::Remove => app.remove_selected()?,
+        // Default output style or statusline
+        Action::SetDefault if tab == app::Tab::OutputStyles => app.set_default_style()?,
+        Action::SetDefault => app.set_statusline()?,
+        Action::UnsetDefault if tab == app::Tab::OutputStyles => app.unset_default_style()?,
+        Action::UnsetDefault => app.unset_statusline()?,
+        // MCP scope (user/local) or the settings file permissions are written to
+        Action::CycleScope if tab == app::Tab::McpServers => app.toggle_mcp_scope(),
+        Action::CycleScope => app.toggle_permission_scope(),
+        // Marketplace browse mode on Plugins tab, and writing browsed plugins into plugins.yaml
+        Action::Browse => app.toggle_plugin_browse(),
+        Action::AddToCatalog => app.add_browsed_to_catalog()?,
+        // Settings conflicts of the config file under the cursor
+        Action::Conflicts => app.open_merge_review()?,
+        Action::History => app.open_history(),
+        Action::Snapshots => app.open_snapshots(),
+        Action::Doctor => app.open_doctor()?,
+        Action::Purge => app.open_purge()?,
+        Action::Profiles => app.open_profiles(),
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_diff_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::Diff, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_diff(),
+        Some(Action::Down) => app.scroll_diff_down(),
+        Some(Action::Up) => app.scroll_diff_up(),
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_history_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::History, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_history(),
+        Some(Action::Down) => app.next_history(),
+        Some(Action::Up) => app.prev_history(),
+        Some(Action::Undo) => app.undo_selected_batch()?,
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_doctor_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::Doctor, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_doctor(),
+        Some(Action::Down) => app.next_finding(),
+        Some(Action::Up) => app.prev_finding(),
+        Some(Action::Fix) => app.fix_findings(false)?,
+        Some(Action::FixAll) => app.fix_findings(true)?,
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_purge_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::Purge, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_purge(),
+        Some(Action::Down) => app.next_purge_item(),
+        Some(Action::Up) => app.prev_purge_item(),
+        Some(Action::Confirm) => app.confirm_purge()?,
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_merge_review_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::MergeReview, key, modifiers, |_| true) {
+        Some(Action::Close) => app.cancel_merge_review(),
+        Some(Action::Down) => app.next_review_item(),
+        Some(Action::Up) => app.prev_review_item(),
+        Some(Action::Toggle) => app.toggle_review_item(),
+        Some(Action::KeepLocal) => app.resolve_review(conflict::Resolution::KeepLocal, false),
+        Some(Action::TakeRepo) => app.resolve_review(conflict::Resolution::TakeRepo, false),
+        Some(Action::KeepLocalAll) => app.resolve_review(conflict::Resolution::KeepLocal, true),
+        Some(Action::TakeRepoAll) => app.resolve_review(conflict::Resolution::TakeRepo, true),
+        Some(Action::Apply) => app.confirm_merge_review()?,
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_snapshots_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    match app.keymap.action(KeyView::Snapshots, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_snapshots(),
+        Some(Action::Down) => app.next_snapshot(),
+        Some(Action::Up) => app.prev_snapshot(),
+        Some(Action::Create) => app.create_snapshot()?,
+        Some(Action::Restore) => app.restore_selected_snapshot()?,
+        Some(Action::Help) => app.show_help = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn handle_profiles_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    // Typing a name to save the current selection
+    if app.profile_name_input.is_some() {
+        match key {
+            KeyCode::Esc => app.profile_input_cancel(),
+            KeyCode::Enter => app.save_profile()?,
+            KeyCode::Backspace => app.profile_input_backspace(),
+            KeyCode::Char(c) => app.profile_input_char(c),
+            _ => {}
         }
+        return Ok(());
+    }
+
+    match app.keymap.action(KeyView::Profiles, key, modifiers, |_| true) {
+        Some(Action::Close) => app.close_profiles(),
+        Some(Action::Down) => app.next_profile(),
+        Some(Action::Up) => app.prev_profile(),
+        Some(Action::Load) => app.load_selected_profile(),
+        Some(Action::Save) => app.start_profile_save(),
+        Some(Action::Help) => app.show_help = true,
         _ => {}
     }
     Ok(())
 }
 
-fn handle_diff_input(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Char('q') | KeyCode::Esc => app.close_diff(),
-        KeyCode::Down | KeyCode::Char('j') => app.scroll_diff_down(),
-        KeyCode::Up | KeyCode::Char('k') => app.scroll_diff_up(),
+fn handle_installing_input(app: &mut App, key: KeyCode, modifiers: KeyModifiers) -> Result<()> {
+    // Close and retry only once everything has finished
+    let complete = app.processing_complete;
+    let applies = |a: Action| !matches!(a, Action::Close | Action::Retry) || complete;
+    match app.keymap.action(KeyView::Installing, key, modifiers, applies) {
+        Some(Action::Close) => app.close_processing(),
+        // Cancel everything that has not started yet
+        Some(Action::CancelPending) => app.cancel_processing(),
+        Some(Action::CancelItem) => app.cancel_processing_item(),
+        Some(Action::Retry) => app.retry_failed(),
+        Some(Action::Up) => app.move_processing_cursor(-1),
+        Some(Action::Down) => app.move_processing_cursor(1),
+        Some(Action::Help) => app.show_help = true,
         _ => {}
     }
     Ok(())
 }
 
-fn handle_installing_input(app: &mut App, key: KeyCode) -> Result<()> {
-    match key {
-        KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => {
-            // Only allow exit when processing is fully complete
-            if app.processing_complete {
-                app.close_processing();
+fn handle_mouse_input(app: &mut App, mouse: MouseEvent) -> Result<()> {
+    // Clicking anywhere closes the help overlay; text input keeps the keyboard focus
+    if app.show_help {
+        if let MouseEventKind::Down(_) = mouse.kind {
+            app.show_help = false;
+        }
+        return Ok(());
+    }
+    if app.env_edit.is_some() || app.profile_name_input.is_some() {
+        return Ok(());
+    }
+
+    match (app.current_view, mouse.kind) {
+        (app::View::List, MouseEventKind::ScrollDown) => app.scroll_rows(1),
+        (app::View::List, MouseEventKind::ScrollUp) => app.scroll_rows(-1),
+        (app::View::List, MouseEventKind::Down(MouseButton::Left)) => {
+            let hit_map = app.hit_map.borrow().clone();
+            let double = app.register_click(mouse.column, mouse.row);
+
+            if let Some((_, tab)) = hit_map.tabs.iter().find(|(area, _)| {
+                area.contains(ratatui::layout::Position::new(mouse.column, mouse.row))
+            }) {
+                app.set_tab(*tab);
+                return Ok(());
+            }
+
+            let Some(rows) = hit_map.rows else {
+                return Ok(());
+            };
+            let Some(item) = rows.item_at(mouse.column, mouse.row) else {
+                return Ok(());
+            };
+            app.select_row(item);
+
+            // "> " highlight symbol, tree indent, then the three-column checkbox
+            let checkbox = rows.area.x + 2 + app.row_indent(item);
+            if app.tab != app::Tab::Env && (checkbox..checkbox + 3).contains(&mouse.column) {
+                app.toggle_selected();
+            } else if double && app.is_cursor_on_folder() {
+                app.toggle_folder_expand();
             }
         }
+        (app::View::Diff, MouseEventKind::ScrollDown) => (0..3).for_each(|_| app.scroll_diff_down()),
+        (app::View::Diff, MouseEventKind::ScrollUp) => (0..3).for_each(|_| app.scroll_diff_up()),
+        (app::View::History, MouseEventKind::ScrollDown) => app.next_history(),
+        (app::View::History, MouseEventKind::ScrollUp) => app.prev_history(),
+        (app::View::Snapshots, MouseEventKind::ScrollDown) => app.next_snapshot(),
+        (app::View::Snapshots, MouseEventKind::ScrollUp) => app.prev_snapshot(),
+        (app::View::Doctor, MouseEventKind::ScrollDown) => app.next_finding(),
+        (app::View::Doctor, MouseEventKind::ScrollUp) => app.prev_finding(),
+        (app::View::Purge, MouseEventKind::ScrollDown) => app.next_purge_item(),
+        (app::View::Purge, MouseEventKind::ScrollUp) => app.prev_purge_item(),
+        (app::View::Profiles, MouseEventKind::ScrollDown) => app.next_profile(),
+        (app::View::Profiles, MouseEventKind::ScrollUp) => app.prev_profile(),
+        (app::View::MergeReview, MouseEventKind::ScrollDown) => app.next_review_item(),
+        (app::View::MergeReview, MouseEventKind::ScrollUp) => app.prev_review_item(),
+        (app::View::Installing, MouseEventKind::ScrollDown) => app.move_processing_cursor(1),
+        (app::View::Installing, MouseEventKind::ScrollUp) => app.move_processing_cursor(-1),
         _ => {}
     }
     Ok(())
@@ -472,14 +778,16 @@ fn start_loading_thread(
     refresh_tx: &std::sync::mpsc::Sender<Result<(Vec<component::Component>, Vec<mcp::McpServer>, Vec<plugin::Plugin>)>>,
 ) {
     let tx_clone = refresh_tx.clone();
-    let source_dir = app.source_dir.clone();
+    let source_layers = app.source_layers.clone();
     let dest_dir = app.dest_dir.clone();
+    let scan_cache = app.scan_cache.clone();
     let target_cli = app.target_cli.unwrap_or(app::TargetCli::Claude);
 
+    // Only catalogs for MCP servers and plugins: their status is loaded when the tab is first shown
     thread::spawn(move || {
-        let components = fs::scanner::scan_components(&source_dir, &dest_dir, target_cli);
-        let mcp_servers = fs::scanner::scan_mcp_servers(&source_dir, target_cli, &dest_dir);
-        let plugins = fs::scanner::scan_plugins(&source_dir);
+        let components = fs::scanner::scan_components(&source_layers, &dest_dir, target_cli, scan_cache.as_deref());
+        let mcp_servers = fs::scanner::catalog_mcp_servers(&source_layers);
+        let plugins = fs::scanner::catalog_plugins(&source_layers);
 
         match (components, mcp_servers, plugins) {
             (Ok(c), Ok(m), Ok(p)) => {
@@ -522,6 +830,7 @@ enum ProcessData {
         dest_path: std::path::PathBuf,
         component_type: component::ComponentType,
         hook_config: Option<component::HookConfig>,
+        config_file: Option<config_file::ConfigFileDef>,
         source_dir: std::path::PathBuf,
         dest_dir: std::path::PathBuf,
     },
@@ -534,10 +843,16 @@ enum ProcessData {
     Plugin {
         plugin: plugin::Plugin,
     },
+    PurgeStep {
+        batch: std::sync::Arc<purge::Batch>,
+        step: usize,
+    },
 }
 
 fn prepare_process_data(app: &App, idx: usize) -> ProcessData {
-    if app.tab == app::Tab::McpServers {
+    if let Some(batch) = &app.purge_batch {
+        ProcessData::PurgeStep { batch: batch.clone(), step: idx }
+    } else if app.tab == app::Tab::McpServers {
         let server = app.mcp_servers[idx].clone();
         let env_values = if app.env_input_server_idx == Some(idx) {
             app.env_input_values.clone()
@@ -557,7 +872,7 @@ fn prepare_process_data(app: &App, idx: usize) -> ProcessData {
         }
     } else if app.tab == app::Tab::Plugins {
         ProcessData::Plugin {
-            plugin: app.plugins[idx].clone(),
+            plugin: app.active_plugins()[idx].clone(),
         }
     } else {
         let c = &app.components[idx];
@@ -567,24 +882,16 @@ fn prepare_process_data(app: &App, idx: usize) -> ProcessData {
             dest_path: c.dest_path.clone(),
             component_type: c.component_type.clone(),
             hook_config: c.hook_config.clone(),
+            config_file: c.config_file.clone(),
             source_dir: app.source_dir.clone(),
             dest_dir: app.dest_dir.clone(),
         }
     }
 }
 
-fn get_item_name(app: &App, idx: usize) -> String {
-    if app.tab == app::Tab::McpServers {
-        app.mcp_servers.get(idx).map(|s| s.def.name.clone()).unwrap_or_default()
-    } else if app.tab == app::Tab::Plugins {
-        app.plugins.get(idx).map(|p| p.def.name.clone()).unwrap_or_default()
-    } else {
-        app.components.get(idx).map(|c| c.name.clone()).unwrap_or_default()
-    }
-}
-
 fn execute_process_step(data: ProcessData, is_removing: bool, _tab: app::Tab, target_cli: app::TargetCli) -> Result<String> {
     match data {
+        ProcessData::PurgeStep { batch, step } => Ok(batch.run_step(step)),
         ProcessData::McpServer { server, scope, project_path, env_values } => {
             let name = server.def.name.clone();
             if is_removing {
@@ -600,7 +907,7 @@ fn execute_process_step(data: ProcessData, is_removing: bool, _tab: app::Tab, ta
             }
         }
         ProcessData::Plugin { plugin } => {
-            let name = plugin.def.name.clone();
+            let name = plugin.def.id();
             if is_removing {
                 match fs::installer::remove_plugin(&plugin) {
                     Ok(_) => Ok(format!("[OK] Removed {}", name)),
@@ -613,7 +920,7 @@ fn execute_process_step(data: ProcessData, is_removing: bool, _tab: app::Tab, ta
                 }
             }
         }
-        ProcessData::Component { name, source_path, dest_path, component_type, hook_config, source_dir, dest_dir } => {
+        ProcessData::Component { name, source_path, dest_path, component_type, hook_config, config_file, source_dir, dest_dir } => {
             if is_removing {
                 // Create a temporary Component for removal
                 let comp = component::Component {
@@ -624,6 +931,12 @@ fn execute_process_step(data: ProcessData, is_removing: bool, _tab: app::Tab, ta
                     hook_config,
                     status: component::InstallStatus::Unchanged,
                     selected: false,
+                    issues: Vec::new(),
+                    requires: Vec::new(),
+                    layer: 0,
+                    overrides: Vec::new(),
+                    config_file,
+                    source_hash: None,
                 };
                 match fs::installer::remove_component(&comp, &dest_dir) {
                     Ok(_) => Ok(format!("[OK] Removed {}", name)),
@@ -639,6 +952,12 @@ fn execute_process_step(data: ProcessData, is_removing: bool, _tab: app::Tab, ta
                     hook_config,
                     status: component::InstallStatus::New,
                     selected: false,
+                    issues: Vec::new(),
+                    requires: Vec::new(),
+                    layer: 0,
+                    overrides: Vec::new(),
+                    config_file,
+                    source_hash: None,
                 };
                 match fs::installer::install_component(&comp, &source_dir, &dest_dir) {
                     Ok(_) => Ok(format!("[OK] Installed {}", name)),
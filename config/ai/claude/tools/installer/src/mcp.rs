@@ -1,6 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum McpScope {
     User,
     Local,
@@ -85,9 +86,26 @@ impl McpServer {
         }
     }
 
+    /// Required env vars that are set in the current environment
+    pub fn env_from_environment(&self) -> Vec<(String, String)> {
+        self.def.env
+            .iter()
+            .filter_map(|key| std::env::var(key).ok().map(|value| (key.clone(), value)))
+            .collect()
+    }
+
     pub fn is_http(&self) -> bool {
         matches!(self.def.r#type, Some(McpType::Http))
     }
+
+    /// What `mcp add` registers the server to run (compare `McpRegistration::launch`)
+    pub fn launch(&self) -> Option<String> {
+        if self.is_http() {
+            self.def.url.clone()
+        } else {
+            self.def.command.as_ref().map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
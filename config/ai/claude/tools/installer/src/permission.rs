@@ -0,0 +1,376 @@
+//! settings.json `permissions` rules across the user, project and local settings files
+//!
+//! The curated catalog lives in `<source>/permissions/permissions.yaml`:
+//!
+//! ```yaml
+//! rules:
+//!   - rule: Bash(git push:*)
+//!     kind: ask                 # allow (default), deny, ask or directory
+//!     description: Confirm before pushing
+//!   - rule: ~/src/shared
+//!     kind: directory           # additionalDirectories entry
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Rule list in the `permissions` block. Declared in precedence order (deny wins).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleKind {
+    Deny,
+    Ask,
+    #[default]
+    Allow,
+    Directory,
+}
+
+impl RuleKind {
+    const ALL: [RuleKind; 4] = [Self::Deny, Self::Ask, Self::Allow, Self::Directory];
+
+    /// Key under `permissions`
+    pub fn key(&self) -> &str {
+        match self {
+            Self::Deny => "deny",
+            Self::Ask => "ask",
+            Self::Allow => "allow",
+            Self::Directory => "additionalDirectories",
+        }
+    }
+
+    pub fn display(&self) -> &str {
+        match self {
+            Self::Deny => "deny",
+            Self::Ask => "ask",
+            Self::Allow => "allow",
+            Self::Directory => "dir",
+        }
+    }
+}
+
+/// Settings file a rule lives in. Later scopes take precedence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsScope {
+    User,
+    Project,
+    Local,
+}
+
+impl SettingsScope {
+    pub fn display(&self) -> &str {
+        match self {
+            Self::User => "user",
+            Self::Project => "project",
+            Self::Local => "local",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            Self::User => Self::Project,
+            Self::Project => Self::Local,
+            Self::Local => Self::User,
+        }
+    }
+
+    /// `dest_dir/settings.json`, `<project>/.claude/settings.json` or `settings.local.json`
+    pub fn path(&self, dest_dir: &Path, project_dir: &Path) -> PathBuf {
+        match self {
+            Self::User => dest_dir.join("settings.json"),
+            Self::Project => project_dir.join(".claude").join("settings.json"),
+            Self::Local => project_dir.join(".claude").join("settings.local.json"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RuleDef {
+    pub rule: String,
+    #[serde(default)]
+    pub kind: RuleKind,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PermissionCatalog {
+    #[serde(default)]
+    rules: Vec<RuleDef>,
+}
+
+/// A rule as it stands across the settings files
+#[derive(Clone, Debug)]
+pub struct PermissionRule {
+    pub def: RuleDef,
+    pub scopes: Vec<SettingsScope>, // Settings files that contain the rule
+    pub in_catalog: bool,
+    pub error: Option<String>,      // Syntax problem (see validate_rule)
+    pub shadowed: Option<RuleKind>, // The same rule under a stronger kind wins
+    pub selected: bool,
+}
+
+/// Catalog rules from all source layers (a later layer replaces the same kind + rule)
+pub fn load_catalog(sources: &[PathBuf]) -> Result<Vec<RuleDef>> {
+    let mut rules: Vec<RuleDef> = Vec::new();
+    for source_dir in sources {
+        let path = source_dir.join("permissions").join("permissions.yaml");
+        if !path.exists() {
+            continue;
+        }
+
+        let content = std::fs::read_to_string(&path)?;
+        let catalog: PermissionCatalog = serde_yaml::from_str(&content)
+            .with_context(|| format!("Invalid permissions catalog: {}", path.display()))?;
+        for def in catalog.rules {
+            match rules.iter_mut().find(|r| r.kind == def.kind && r.rule == def.rule) {
+                Some(existing) => *existing = def,
+                None => rules.push(def),
+            }
+        }
+    }
+    Ok(rules)
+}
+
+/// Every rule set in `files`, plus the catalog rules not set anywhere
+pub fn collect(catalog: &[RuleDef], files: &[(SettingsScope, PathBuf)]) -> Vec<PermissionRule> {
+    let mut rules: Vec<PermissionRule> = Vec::new();
+
+    for (scope, path) in files {
+        let permissions = read_settings(path).ok().and_then(|s| s.get("permissions").cloned());
+        for kind in RuleKind::ALL {
+            let entries = permissions.as_ref().and_then(|p| p.get(kind.key())).and_then(|v| v.as_array());
+            for rule in entries.into_iter().flatten().filter_map(|v| v.as_str()) {
+                let entry = match rules.iter().position(|r| r.def.kind == kind && r.def.rule == rule) {
+                    Some(pos) => &mut rules[pos],
+                    None => {
+                        rules.push(PermissionRule::new(RuleDef { rule: rule.to_string(), kind, description: None }));
+                        rules.last_mut().unwrap()
+                    }
+                };
+                if !entry.scopes.contains(scope) {
+                    entry.scopes.push(*scope);
+                }
+            }
+        }
+    }
+
+    for def in catalog {
+        let entry = match rules.iter().position(|r| r.def.kind == def.kind && r.def.rule == def.rule) {
+            Some(pos) => {
+                rules[pos].def.description = def.description.clone();
+                &mut rules[pos]
+            }
+            None => {
+                rules.push(PermissionRule::new(def.clone()));
+                rules.last_mut().unwrap()
+            }
+        };
+        entry.in_catalog = true;
+    }
+
+    // Deny beats ask beats allow for the same rule
+    let set: Vec<(RuleKind, String)> = rules
+        .iter()
+        .filter(|r| !r.scopes.is_empty())
+        .map(|r| (r.def.kind, r.def.rule.clone()))
+        .collect();
+    for r in &mut rules {
+        if r.def.kind != RuleKind::Directory {
+            r.shadowed = set.iter().filter(|(k, rule)| *k < r.def.kind && *rule == r.def.rule).map(|(k, _)| *k).min();
+        }
+    }
+
+    rules.sort_by(|a, b| (a.def.kind, &a.def.rule).cmp(&(b.def.kind, &b.def.rule)));
+    rules
+}
+
+impl PermissionRule {
+    fn new(def: RuleDef) -> Self {
+        let error = match def.kind {
+            RuleKind::Directory if def.rule.trim().is_empty() => Some("empty directory".to_string()),
+            RuleKind::Directory => None,
+            _ => validate_rule(&def.rule).err(),
+        };
+        Self { def, scopes: Vec::new(), in_catalog: false, error, shadowed: None, selected: false }
+    }
+}
+
+/// `defaultMode` of the highest-precedence settings file that sets one
+pub fn default_mode(files: &[(SettingsScope, PathBuf)]) -> Option<(String, SettingsScope)> {
+    files.iter().rev().find_map(|(scope, path)| {
+        let settings = read_settings(path).ok()?;
+        let mode = settings.get("permissions")?.get("defaultMode")?.as_str()?;
+        Some((mode.to_string(), *scope))
+    })
+}
+
+/// Check a rule: `Tool`, `Tool(specifier)` or `mcp__server[__tool]`
+pub fn validate_rule(rule: &str) -> Result<(), String> {
+    let (tool, specifier) = match rule.split_once('(') {
+        Some((tool, rest)) => match rest.strip_suffix(')') {
+            Some(specifier) => (tool, Some(specifier)),
+            None => return Err("missing closing ')'".to_string()),
+        },
+        None => (rule, None),
+    };
+
+    if let Some(name) = tool.strip_prefix("mcp__") {
+        if specifier.is_some() {
+            return Err("MCP rules take no specifier (use mcp__server__tool)".to_string());
+        }
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+            return Err(format!("'{}' is not an MCP server or tool", tool));
+        }
+        return Ok(());
+    }
+
+    if !tool.starts_with(|c: char| c.is_ascii_uppercase()) || !tool.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("'{}' is not a tool name", tool));
+    }
+
+    let Some(specifier) = specifier else {
+        return Ok(());
+    };
+    if specifier.trim().is_empty() {
+        return Err(format!("empty specifier (use '{}' to match every call)", tool));
+    }
+    match tool {
+        "Bash" => match specifier.find(":*") {
+            Some(pos) if pos + 2 != specifier.len() => Err("':*' is only allowed at the end of a Bash prefix".to_string()),
+            _ => Ok(()),
+        },
+        "WebFetch" if specifier.strip_prefix("domain:").is_none_or(str::is_empty) => {
+            Err("WebFetch rules look like WebFetch(domain:example.com)".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Add `rule` to a settings file. Returns false when it is already there.
+pub fn add_rule(settings_path: &Path, kind: RuleKind, rule: &str) -> Result<bool> {
+    if kind != RuleKind::Directory
+        && let Err(e) = validate_rule(rule)
+    {
+        anyhow::bail!("Invalid rule {}: {}", rule, e);
+    }
+
+    edit_permissions(settings_path, |permissions| {
+        let list = permissions
+            .entry(kind.key())
+            .or_insert_with(|| Value::Array(Vec::new()));
+        let Some(items) = list.as_array_mut() else {
+            return false;
+        };
+        if items.iter().any(|v| v.as_str() == Some(rule)) {
+            return false;
+        }
+        items.push(Value::String(rule.to_string()));
+        true
+    })
+}
+
+/// Remove `rule` from a settings file. Returns false when it was not there.
+pub fn remove_rule(settings_path: &Path, kind: RuleKind, rule: &str) -> Result<bool> {
+    if !settings_path.exists() {
+        return Ok(false);
+    }
+
+    edit_permissions(settings_path, |permissions| {
+        let Some(items) = permissions.get_mut(kind.key()).and_then(|v| v.as_array_mut()) else {
+            return false;
+        };
+        let before = items.len();
+        items.retain(|v| v.as_str() != Some(rule));
+        let removed = items.len() != before;
+        if items.is_empty() {
+            permissions.remove(kind.key());
+        }
+        removed
+    })
+}
+
+/// Read-modify-write of the `permissions` object; written only when `edit` changed something
+fn edit_permissions(settings_path: &Path, edit: impl FnOnce(&mut Map<String, Value>) -> bool) -> Result<bool> {
+    let mut settings = if settings_path.exists() {
+        read_settings(settings_path)?
+    } else {
+        Value::Object(Map::new())
+    };
+    let Some(root) = settings.as_object_mut() else {
+        anyhow::bail!("{} is not a JSON object", settings_path.display());
+    };
+
+    let permissions = root
+        .entry("permissions")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Some(permissions) = permissions.as_object_mut() else {
+        anyhow::bail!("permissions in {} is not an object", settings_path.display());
+    };
+
+    let changed = edit(permissions);
+    if permissions.is_empty() {
+        root.remove("permissions");
+    }
+    if changed {
+        if let Some(parent) = settings_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(settings_path, serde_json::to_string_pretty(&settings)?)?;
+    }
+    Ok(changed)
+}
+
+fn read_settings(path: &Path) -> Result<Value> {
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content).with_context(|| format!("Invalid JSON: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_validate_rule() {
+        for ok in ["Bash", "Bash(git push:*)", "Read(~/.ssh/**)", "WebFetch(domain:docs.rs)", "mcp__github", "mcp__github__get_issue"] {
+            assert_eq!(validate_rule(ok), Ok(()), "{}", ok);
+        }
+        for bad in ["Bash(git push:*", "Bash(git:* push)", "bash(ls)", "Bash()", "WebFetch(docs.rs)", "mcp__x(y)", "mcp__"] {
+            assert!(validate_rule(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_add_remove_and_effective_rules() {
+        let dir = temp_dir("permission-rules");
+        let files = vec![
+            (SettingsScope::User, SettingsScope::User.path(&dir, &dir.join("project"))),
+            (SettingsScope::Project, SettingsScope::Project.path(&dir, &dir.join("project"))),
+        ];
+        std::fs::write(&files[0].1, r#"{"model":"opus","permissions":{"allow":["Bash(git push:*)"]}}"#).unwrap();
+
+        assert!(add_rule(&files[1].1, RuleKind::Deny, "Bash(git push:*)").unwrap());
+        assert!(!add_rule(&files[1].1, RuleKind::Deny, "Bash(git push:*)").unwrap());
+        assert!(add_rule(&files[1].1, RuleKind::Allow, "Bash(git:* x)").is_err());
+
+        let catalog = vec![RuleDef { rule: "Read".to_string(), kind: RuleKind::Allow, description: None }];
+        let rules = collect(&catalog, &files);
+        let summary: Vec<(RuleKind, &str, usize, Option<RuleKind>)> = rules
+            .iter()
+            .map(|r| (r.def.kind, r.def.rule.as_str(), r.scopes.len(), r.shadowed))
+            .collect();
+        assert_eq!(summary, [
+            (RuleKind::Deny, "Bash(git push:*)", 1, None),
+            (RuleKind::Allow, "Bash(git push:*)", 1, Some(RuleKind::Deny)),
+            (RuleKind::Allow, "Read", 0, None),
+        ]);
+
+        assert!(remove_rule(&files[0].1, RuleKind::Allow, "Bash(git push:*)").unwrap());
+        assert_eq!(std::fs::read_to_string(&files[0].1).unwrap(), "{\n  \"model\": \"opus\"\n}");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
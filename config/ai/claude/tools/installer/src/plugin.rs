@@ -1,3 +1,5 @@
+use serde::Deserialize;
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum PluginStatus {
     Installed,
@@ -21,6 +23,14 @@ pub struct PluginDef {
     pub comment: Option<String>,
 }
 
+impl PluginDef {
+    /// Marketplace-qualified identity used by the claude CLI and settings.json
+    /// e.g., "document-skills@anthropic-agent-skills"
+    pub fn id(&self) -> String {
+        format!("{}@{}", self.name, self.marketplace)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Plugin {
     pub def: PluginDef,
@@ -49,10 +59,34 @@ impl Plugin {
     }
 }
 
+/// marketplace 저장소의 `.claude-plugin/marketplace.json`
+#[derive(Debug, Deserialize)]
+pub struct MarketplaceManifest {
+    #[serde(default)]
+    pub plugins: Vec<MarketplacePluginEntry>,
+}
+
+/// marketplace.json의 `plugins` 배열 항목 (필요한 필드만 사용)
+#[derive(Debug, Deserialize)]
+pub struct MarketplacePluginEntry {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
 /// plugins.yaml 파싱 결과
 /// 형식: Vec<(marketplace_name, source_url, plugin_name, comment)>
+/// 같은 이름의 플러그인이라도 marketplace가 다르면 별도 항목으로 유지 (name@marketplace 기준)
 pub type PluginCatalog = Vec<(String, String, String, Option<String>)>;
 
+/// "name@marketplace" 형식을 (name, Some(marketplace))로 분리
+pub fn split_plugin_id(id: &str) -> (&str, Option<&str>) {
+    match id.rsplit_once('@') {
+        Some((name, marketplace)) if !name.is_empty() && !marketplace.is_empty() => (name, Some(marketplace)),
+        _ => (id, None),
+    }
+}
+
 /// plugins.yaml 파싱 (새 형식과 이전 형식 모두 지원)
 /// 새 형식:
 /// ```yaml
@@ -67,6 +101,8 @@ pub type PluginCatalog = Vec<(String, String, String, Option<String>)>;
 /// https://github.com/repo.git:
 ///   - plugin-name # comment
 /// ```
+/// 플러그인 이름은 `plugin-name@marketplace-name` 형식도 허용하며,
+/// 이 경우 marketplace 이름이 소속 섹션과 일치해야 함 (불일치 항목은 무시)
 pub fn parse_plugins_yaml(content: &str) -> PluginCatalog {
     use serde_yaml::Value;
 
@@ -93,9 +129,7 @@ pub fn parse_plugins_yaml(content: &str) -> PluginCatalog {
                         if let Some(plugins_seq) = plugins.as_sequence() {
                             for plugin_entry in plugins_seq {
                                 let (name, comment) = parse_plugin_entry(plugin_entry);
-                                if !name.is_empty() {
-                                    catalog.push((marketplace_name.clone(), source.clone(), name, comment));
-                                }
+                                push_catalog_entry(&mut catalog, &marketplace_name, &source, name, comment);
                             }
                         }
                     }
@@ -117,9 +151,7 @@ pub fn parse_plugins_yaml(content: &str) -> PluginCatalog {
                     if let Some(plugins_seq) = value.as_sequence() {
                         for plugin_entry in plugins_seq {
                             let (name, comment) = parse_plugin_entry(plugin_entry);
-                            if !name.is_empty() {
-                                catalog.push((marketplace_name.clone(), repo_url.clone(), name, comment));
-                            }
+                            push_catalog_entry(&mut catalog, &marketplace_name, &repo_url, name, comment);
                         }
                     }
                 }
@@ -130,6 +162,32 @@ pub fn parse_plugins_yaml(content: &str) -> PluginCatalog {
     catalog
 }
 
+/// 카탈로그에 항목 추가 (name@marketplace 정규화 및 중복 제거)
+fn push_catalog_entry(
+    catalog: &mut PluginCatalog,
+    marketplace_name: &str,
+    source: &str,
+    name: String,
+    comment: Option<String>,
+) {
+    let name = match split_plugin_id(&name) {
+        (plain, Some(marketplace)) if marketplace == marketplace_name => plain.to_string(),
+        (_, Some(_)) => return, // 다른 marketplace를 가리키는 항목은 무시
+        (plain, None) => plain.to_string(),
+    };
+
+    if name.is_empty() {
+        return;
+    }
+
+    let duplicate = catalog
+        .iter()
+        .any(|(m, _, n, _)| m == marketplace_name && *n == name);
+    if !duplicate {
+        catalog.push((marketplace_name.to_string(), source.to_string(), name, comment));
+    }
+}
+
 fn parse_plugin_entry(entry: &serde_yaml::Value) -> (String, Option<String>) {
     use serde_yaml::Value;
 
@@ -155,6 +213,135 @@ fn parse_plugin_entry(entry: &serde_yaml::Value) -> (String, Option<String>) {
     }
 }
 
+/// `claude plugin marketplace list` 출력에서 marketplace 이름 목록 추출
+/// 출력 예:
+/// ```text
+/// Configured marketplaces:
+///
+///   ❯ claude-plugins-official
+///     Source: GitHub (anthropics/claude-plugins-official)
+/// ```
+pub fn parse_marketplace_list(stdout: &str) -> Vec<String> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let trimmed = line
+                .trim()
+                .trim_start_matches(|c: char| !c.is_alphanumeric())
+                .trim();
+            // "Source: ..." 같은 상세 라인과 헤더 라인은 제외
+            if trimmed.is_empty() || trimmed.contains(':') || trimmed.contains(char::is_whitespace) {
+                return None;
+            }
+            Some(trimmed.to_string())
+        })
+        .collect()
+}
+
+/// plugins.yaml에 플러그인 추가 (주석과 기존 형식 유지)
+/// 이미 등록된 name@marketplace는 건너뛰고, 없는 marketplace 섹션은 파일 끝에 새로 추가
+/// 이전 형식 파일은 새 형식으로 변환한 뒤 추가
+pub fn add_plugins_to_yaml(content: &str, defs: &[PluginDef]) -> String {
+    let existing = parse_plugins_yaml(content);
+    let has_marketplaces = content.lines().any(|l| l.trim_end() == "marketplaces:");
+
+    let mut lines: Vec<String> = if has_marketplaces {
+        content.lines().map(|l| l.to_string()).collect()
+    } else {
+        // 이전 형식 또는 빈 파일: 기존 항목을 새 형식으로 다시 작성
+        let mut converted = vec!["marketplaces:".to_string()];
+        for (marketplace, source, name, comment) in existing.iter() {
+            let def = PluginDef {
+                name: name.clone(),
+                marketplace: marketplace.clone(),
+                source: source.clone(),
+                comment: comment.clone(),
+            };
+            insert_plugin_line(&mut converted, &def);
+        }
+        converted
+    };
+
+    let mut known: Vec<(String, String)> = existing
+        .into_iter()
+        .map(|(m, _, n, _)| (m, n))
+        .collect();
+
+    for def in defs {
+        let key = (def.marketplace.clone(), def.name.clone());
+        if known.contains(&key) {
+            continue;
+        }
+        insert_plugin_line(&mut lines, def);
+        known.push(key);
+    }
+
+    let mut output = lines.join("\n");
+    output.push('\n');
+    output
+}
+
+/// marketplace 섹션을 찾아 플러그인 항목을 삽입 (섹션이 없으면 생성)
+fn insert_plugin_line(lines: &mut Vec<String>, def: &PluginDef) {
+    let mut entry = vec![format!("      - name: {}", yaml_scalar(&def.name))];
+    if let Some(comment) = &def.comment {
+        entry.push(format!("        description: {}", yaml_scalar(comment)));
+    }
+
+    let header = format!("  {}:", def.marketplace);
+    let Some(start) = lines.iter().position(|l| l.trim_end() == header) else {
+        // 새 marketplace 섹션 추가
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) && lines.len() > 1 {
+            lines.push(String::new());
+        }
+        lines.push(header);
+        lines.push(format!("    source: {}", yaml_scalar(&def.source)));
+        lines.push("    plugins:".to_string());
+        lines.extend(entry);
+        return;
+    };
+
+    // 섹션 끝: 들여쓰기 2칸 이하의 다음 비어있지 않은 라인
+    let mut end = lines[start + 1..]
+        .iter()
+        .position(|l| !l.trim().is_empty() && indent_of(l) <= 2)
+        .map(|p| start + 1 + p)
+        .unwrap_or(lines.len());
+    while end > start + 1 && lines[end - 1].trim().is_empty() {
+        end -= 1;
+    }
+
+    let has_plugins_key = lines[start + 1..end]
+        .iter()
+        .any(|l| l.trim_end() == "    plugins:");
+    if !has_plugins_key {
+        lines.insert(end, "    plugins:".to_string());
+        end += 1;
+    }
+
+    for (offset, line) in entry.into_iter().enumerate() {
+        lines.insert(end + offset, line);
+    }
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// YAML 스칼라 값 직렬화 (특수문자가 있으면 따옴표 처리)
+fn yaml_scalar(value: &str) -> String {
+    let needs_quotes = value.is_empty()
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.starts_with(['-', '?', ':', ',', '[', ']', '{', '}', '#', '&', '*', '!', '|', '>', '\'', '"', '%', '@', '`', ' '])
+        || value.ends_with([':', ' ']);
+    if needs_quotes {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
 fn extract_marketplace_name(repo_url: &str) -> String {
     // https://github.com/anthropics/claude-plugins-official.git -> claude-plugins-official
     repo_url
@@ -257,5 +444,131 @@ marketplaces:
         );
 
         assert_eq!(plugin.short_repo(), "anthropics/claude-plugins-official");
+        assert_eq!(plugin.def.id(), "test@claude-plugins-official");
+    }
+
+    #[test]
+    fn test_parse_plugins_yaml_same_name_different_marketplaces() {
+        let yaml = r#"
+marketplaces:
+  market-a:
+    source: https://github.com/org/market-a.git
+    plugins:
+      - name: formatter
+  market-b:
+    source: https://github.com/org/market-b.git
+    plugins:
+      - name: formatter
+"#;
+
+        let catalog = parse_plugins_yaml(yaml);
+        assert_eq!(catalog.len(), 2);
+        assert!(catalog.iter().any(|(m, _, n, _)| m == "market-a" && n == "formatter"));
+        assert!(catalog.iter().any(|(m, _, n, _)| m == "market-b" && n == "formatter"));
+    }
+
+    #[test]
+    fn test_parse_plugins_yaml_qualified_names() {
+        let yaml = r#"
+marketplaces:
+  market-a:
+    source: https://github.com/org/market-a.git
+    plugins:
+      - name: formatter@market-a
+      - formatter
+      - linter@market-b
+"#;
+
+        let catalog = parse_plugins_yaml(yaml);
+        // Qualified duplicate collapses, mismatched marketplace is dropped
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(catalog[0].0, "market-a");
+        assert_eq!(catalog[0].2, "formatter");
+    }
+
+    #[test]
+    fn test_parse_marketplace_list() {
+        let stdout = "Configured marketplaces:\n\n  ❯ claude-plugins-official\n    Source: GitHub (anthropics/claude-plugins-official)\n  ❯ claude-plugins-official-extra\n    Source: Git (https://example.com/extra.git)\n";
+
+        let names = parse_marketplace_list(stdout);
+        assert_eq!(names, vec!["claude-plugins-official", "claude-plugins-official-extra"]);
+        // Exact matching: a prefix of another marketplace name must not match
+        assert!(!names.iter().any(|n| n == "claude-plugins"));
+    }
+
+    fn make_def(marketplace: &str, source: &str, name: &str, comment: Option<&str>) -> PluginDef {
+        PluginDef {
+            name: name.to_string(),
+            marketplace: marketplace.to_string(),
+            source: source.to_string(),
+            comment: comment.map(|c| c.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_add_plugins_to_yaml_existing_section() {
+        let yaml = r#"# header comment
+marketplaces:
+  market-a:
+    source: https://github.com/org/market-a.git
+    plugins:
+      - name: formatter
+        description: Formats code
+
+  market-b:
+    source: https://github.com/org/market-b.git
+    plugins:
+      - name: linter
+"#;
+
+        let updated = add_plugins_to_yaml(yaml, &[
+            make_def("market-a", "https://github.com/org/market-a.git", "tester", Some("Runs tests: fast")),
+            make_def("market-a", "https://github.com/org/market-a.git", "formatter", None),
+        ]);
+
+        assert!(updated.starts_with("# header comment\n"));
+        let catalog = parse_plugins_yaml(&updated);
+        assert_eq!(catalog.len(), 3);
+        let tester = catalog.iter().find(|(_, _, n, _)| n == "tester").unwrap();
+        assert_eq!(tester.0, "market-a");
+        assert_eq!(tester.3, Some("Runs tests: fast".to_string()));
+        // Inserted inside market-a, before the blank line separating market-b
+        assert!(updated.find("tester").unwrap() < updated.find("market-b").unwrap());
+    }
+
+    #[test]
+    fn test_add_plugins_to_yaml_new_section() {
+        let yaml = "marketplaces:\n  market-a:\n    source: https://github.com/org/market-a.git\n    plugins:\n      - formatter\n";
+
+        let updated = add_plugins_to_yaml(yaml, &[
+            make_def("market-c", "https://github.com/org/market-c.git", "formatter", None),
+        ]);
+
+        let catalog = parse_plugins_yaml(&updated);
+        assert_eq!(catalog.len(), 2);
+        let entry = catalog.iter().find(|(m, _, _, _)| m == "market-c").unwrap();
+        assert_eq!(entry.1, "https://github.com/org/market-c.git");
+        assert_eq!(entry.2, "formatter");
+    }
+
+    #[test]
+    fn test_add_plugins_to_yaml_converts_old_format() {
+        let yaml = "https://github.com/anthropics/skills.git:\n  - document-skills\n";
+
+        let updated = add_plugins_to_yaml(yaml, &[
+            make_def("skills", "https://github.com/anthropics/skills.git", "example-skills", None),
+        ]);
+
+        assert!(updated.starts_with("marketplaces:"));
+        let catalog = parse_plugins_yaml(&updated);
+        assert_eq!(catalog.len(), 2);
+        assert!(catalog.iter().all(|(m, _, _, _)| m == "skills"));
+    }
+
+    #[test]
+    fn test_split_plugin_id() {
+        assert_eq!(split_plugin_id("doc@skills"), ("doc", Some("skills")));
+        assert_eq!(split_plugin_id("doc"), ("doc", None));
+        assert_eq!(split_plugin_id("doc@"), ("doc@", None));
     }
 }
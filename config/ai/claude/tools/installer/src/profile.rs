@@ -0,0 +1,356 @@
+//! Selection profiles (`<source_dir>/profiles/<name>.yaml`)
+//!
+//! ```yaml
+//! description: Laptop for work
+//! components:            # Same references as `requires:` (see deps.rs)
+//!   - agents/*
+//!   - hooks/inject_guide
+//! mcp_servers:
+//!   - context7           # user scope
+//!   - name: serena
+//!     scope: local
+//!     project: ~/work/app
+//! plugins:
+//!   - document-skills@anthropic-agent-skills
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::component::{Component, InstallStatus};
+use crate::deps::{self, DependencyGraph};
+use crate::fs::scanner::McpRegistration;
+use crate::mcp::{McpScope, McpServer, McpStatus};
+use crate::plugin::{Plugin, PluginStatus};
+
+pub fn profiles_dir(source_dir: &Path) -> PathBuf {
+    source_dir.join("profiles")
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub mcp_servers: Vec<ProfileMcp>,
+    #[serde(default)]
+    pub plugins: Vec<String>,
+}
+
+/// MCP entry: a bare name (user scope) or a mapping with scope
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ProfileMcp {
+    Name(String),
+    Scoped {
+        name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        scope: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        project: Option<String>,
+    },
+}
+
+impl ProfileMcp {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Scoped { name, .. } => name,
+        }
+    }
+
+    pub fn scope(&self) -> McpScope {
+        match self {
+            Self::Scoped { scope: Some(scope), .. } if scope == "local" => McpScope::Local,
+            _ => McpScope::User,
+        }
+    }
+
+    pub fn project(&self) -> Option<&str> {
+        match self {
+            Self::Scoped { project, .. } => project.as_deref(),
+            Self::Name(_) => None,
+        }
+    }
+
+    /// Whether `registration` is where this entry wants the server (any project if none is given)
+    pub fn is_registered_at(&self, registration: &McpRegistration) -> bool {
+        registration.name == self.name()
+            && registration.scope == self.scope()
+            && match (self.scope(), self.project()) {
+                (McpScope::Local, Some(project)) => {
+                    registration.project.as_deref().map(PathBuf::from) == Some(crate::config::expand_home(project))
+                }
+                _ => true,
+            }
+    }
+}
+
+impl Profile {
+    pub fn wants_component(&self, c: &Component) -> bool {
+        self.components.iter().any(|r| deps::matches(r, c))
+    }
+
+    pub fn mcp_entry(&self, name: &str) -> Option<&ProfileMcp> {
+        self.mcp_servers.iter().find(|m| m.name() == name)
+    }
+
+    pub fn wants_plugin(&self, plugin: &Plugin) -> bool {
+        self.plugins.contains(&plugin.def.id())
+    }
+
+    /// Build a profile from the current selection
+    pub fn from_selection(components: &[Component], mcp_servers: &[McpServer], scope: McpScope, project: &str, plugins: &[Plugin]) -> Self {
+        let mcp_entry = |name: &str| match scope {
+            McpScope::User => ProfileMcp::Name(name.to_string()),
+            McpScope::Local => ProfileMcp::Scoped {
+                name: name.to_string(),
+                scope: Some("local".to_string()),
+                project: Some(project.to_string()),
+            },
+        };
+
+        Self {
+            description: None,
+            components: components.iter().filter(|c| c.selected).map(|c| c.display_name()).collect(),
+            mcp_servers: mcp_servers.iter().filter(|m| m.selected).map(|m| mcp_entry(&m.def.name)).collect(),
+            plugins: plugins.iter().filter(|p| p.selected).map(|p| p.def.id()).collect(),
+        }
+    }
+}
+
+/// A profile file, parsed or not
+pub struct ProfileEntry {
+    pub name: String,
+    pub result: Result<Profile, String>,
+}
+
+pub fn list(source_dir: &Path) -> Vec<ProfileEntry> {
+    let Ok(entries) = std::fs::read_dir(profiles_dir(source_dir)) else {
+        return Vec::new();
+    };
+
+    let mut profiles: Vec<ProfileEntry> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("yaml" | "yml")))
+        .map(|path| ProfileEntry {
+            name: path.file_stem().and_then(|s| s.to_str()).unwrap_or_default().to_string(),
+            result: load_file(&path).map_err(|e| format!("{:#}", e)),
+        })
+        .collect();
+
+    profiles.sort_by(|a, b| a.name.cmp(&b.name));
+    profiles
+}
+
+fn load_file(path: &Path) -> Result<Profile> {
+    let content = std::fs::read_to_string(path)?;
+    let profile: Profile = serde_yaml::from_str(&content)
+        .with_context(|| format!("Invalid profile: {}", path.display()))?;
+    Ok(profile)
+}
+
+pub fn load(source_dir: &Path, name: &str) -> Result<Profile> {
+    let dir = profiles_dir(source_dir);
+    let path = ["yaml", "yml"]
+        .iter()
+        .map(|ext| dir.join(format!("{}.{}", name, ext)))
+        .find(|p| p.exists())
+        .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", name))?;
+    load_file(&path)
+}
+
+pub fn save(source_dir: &Path, name: &str, profile: &Profile) -> Result<PathBuf> {
+    let dir = profiles_dir(source_dir);
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{}.yaml", name));
+    std::fs::write(&path, serde_yaml::to_string(profile)?)?;
+    Ok(path)
+}
+
+/// What converging to a profile would change (indices into the scanned lists)
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub install_components: Vec<usize>,
+    pub remove_components: Vec<usize>,
+    pub install_mcp: Vec<usize>,
+    pub remove_mcp: Vec<usize>,
+    pub misplaced_mcp: Vec<McpRegistration>, // Wanted servers registered at another scope: removed, then re-added
+    pub install_plugins: Vec<usize>,
+    pub remove_plugins: Vec<usize>,
+    pub unknown: Vec<String>, // Profile entries that match nothing in the catalogs
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.install_components.is_empty()
+            && self.remove_components.is_empty()
+            && self.install_mcp.is_empty()
+            && self.remove_mcp.is_empty()
+            && self.misplaced_mcp.is_empty()
+            && self.install_plugins.is_empty()
+            && self.remove_plugins.is_empty()
+    }
+}
+
+/// Components wanted by a profile, including their dependencies
+pub fn wanted_components(profile: &Profile, components: &[Component], graph: &DependencyGraph) -> Vec<usize> {
+    let roots: Vec<usize> = components
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| profile.wants_component(c))
+        .map(|(i, _)| i)
+        .collect();
+
+    let mut wanted = roots.clone();
+    wanted.extend(graph.closure(&roots));
+    wanted.sort();
+    wanted
+}
+
+/// `registrations` are the CLI's current MCP registrations (scope and project of installed servers)
+pub fn plan(
+    profile: &Profile,
+    components: &[Component],
+    graph: &DependencyGraph,
+    mcp_servers: &[McpServer],
+    registrations: &[McpRegistration],
+    plugins: &[Plugin],
+) -> Plan {
+    let mut plan = Plan::default();
+
+    let wanted = wanted_components(profile, components, graph);
+    for (idx, c) in components.iter().enumerate() {
+        let installed = c.status != InstallStatus::New;
+        if wanted.contains(&idx) {
+            // Merged config files are Managed until the merge is a no-op
+            if c.status != InstallStatus::Unchanged {
+                plan.install_components.push(idx);
+            }
+        } else if installed {
+            plan.remove_components.push(idx);
+        }
+    }
+
+    for (idx, m) in mcp_servers.iter().enumerate() {
+        let installed = m.status == McpStatus::Installed;
+        match profile.mcp_entry(&m.def.name) {
+            Some(_) if !installed => plan.install_mcp.push(idx),
+            Some(entry) => {
+                let current: Vec<&McpRegistration> = registrations.iter().filter(|r| r.name == m.def.name).collect();
+                if !current.is_empty() && !current.iter().any(|r| entry.is_registered_at(r)) {
+                    plan.misplaced_mcp.extend(current.into_iter().cloned());
+                    plan.install_mcp.push(idx);
+                }
+            }
+            None if installed => plan.remove_mcp.push(idx),
+            _ => {}
+        }
+    }
+
+    for (idx, p) in plugins.iter().enumerate() {
+        let installed = p.status == PluginStatus::Installed;
+        match profile.wants_plugin(p) {
+            true if !installed => plan.install_plugins.push(idx),
+            false if installed => plan.remove_plugins.push(idx),
+            _ => {}
+        }
+    }
+
+    for reference in &profile.components {
+        if !components.iter().any(|c| deps::matches(reference, c)) {
+            plan.unknown.push(reference.clone());
+        }
+    }
+    for entry in &profile.mcp_servers {
+        if !mcp_servers.iter().any(|m| m.def.name == entry.name()) {
+            plan.unknown.push(format!("mcp:{}", entry.name()));
+        }
+    }
+    for id in &profile.plugins {
+        if !plugins.iter().any(|p| &p.def.id() == id) {
+            plan.unknown.push(format!("plugin:{}", id));
+        }
+    }
+
+    plan
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::component::ComponentType;
+
+    fn component(component_type: ComponentType, name: &str, status: InstallStatus) -> Component {
+        Component::new(component_type, name.to_string(), PathBuf::from(name), PathBuf::from(name), status)
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let yaml = "components:\n  - agents/*\nmcp_servers:\n  - context7\n  - name: serena\n    scope: local\n    project: /work\nplugins:\n  - a@b\n";
+        let profile: Profile = serde_yaml::from_str(yaml).unwrap();
+
+        assert_eq!(profile.components, vec!["agents/*"]);
+        assert_eq!(profile.mcp_servers[0].name(), "context7");
+        assert_eq!(profile.mcp_servers[0].scope(), McpScope::User);
+        assert_eq!(profile.mcp_servers[1].scope(), McpScope::Local);
+        assert_eq!(profile.mcp_servers[1].project(), Some("/work"));
+        assert_eq!(profile.plugins, vec!["a@b"]);
+    }
+
+    #[test]
+    fn test_plan_converges_components() {
+        let mut components = vec![
+            component(ComponentType::Agents, "new.md", InstallStatus::New),
+            component(ComponentType::Agents, "same.md", InstallStatus::Unchanged),
+            component(ComponentType::Rules, "extra.md", InstallStatus::Unchanged),
+            component(ComponentType::Rules, "needed.md", InstallStatus::Modified),
+            component(ComponentType::Rules, "absent.md", InstallStatus::New),
+        ];
+        components[0].requires = vec!["rules/needed".to_string()];
+        let graph = DependencyGraph::build(&components);
+
+        let profile = Profile {
+            components: vec!["agents/*".to_string(), "skills/missing".to_string()],
+            ..Default::default()
+        };
+        let plan = plan(&profile, &components, &graph, &[], &[], &[]);
+
+        assert_eq!(plan.install_components, vec![0, 3]);
+        assert_eq!(plan.remove_components, vec![2]);
+        assert_eq!(plan.unknown, vec!["skills/missing"]);
+    }
+
+    #[test]
+    fn test_plan_moves_mcp_registered_at_another_scope() {
+        let server = |name: &str| {
+            let def = serde_yaml::from_str(&format!("name: {}\ndescription: d\ncategory: c\ncommand: x", name)).unwrap();
+            McpServer::new(def, McpStatus::Installed)
+        };
+        let mcp_servers = vec![server("docs"), server("search")];
+        let registration = |name: &str, scope, project: Option<&str>| McpRegistration {
+            name: name.into(),
+            scope,
+            project: project.map(str::to_string),
+            env: Vec::new(),
+            launch: None,
+        };
+        let registrations = vec![
+            registration("docs", McpScope::User, None),
+            registration("search", McpScope::Local, Some("/work")),
+        ];
+
+        let yaml = "mcp_servers:\n  - name: docs\n    scope: local\n    project: /work\n  - name: search\n    scope: local\n    project: /work\n";
+        let profile: Profile = serde_yaml::from_str(yaml).unwrap();
+        let plan = plan(&profile, &[], &DependencyGraph::build(&[]), &mcp_servers, &registrations, &[]);
+
+        // docs moves from user to local scope, search is already in place
+        assert_eq!(plan.misplaced_mcp, vec![registrations[0].clone()]);
+        assert_eq!(plan.install_mcp, vec![0]);
+        assert!(plan.remove_mcp.is_empty());
+    }
+}
@@ -0,0 +1,301 @@
+//! Purge: take out everything the installer manages in one go
+//!
+//! Installed components are removed (hooks are unregistered, merged config files lose only
+//! the source's contributions), the default output style and statusline are unset when they
+//! point at a removed component, catalog MCP servers are removed from every scope they are
+//! registered in and catalog plugins are uninstalled. Files edited locally are kept, as are
+//! files, servers and plugins the catalogs do not know about. When every step succeeded the
+//! remembered merge choices (`.installer/merge-choices.json`) go as well.
+
+use std::path::{Path, PathBuf};
+
+use crate::app::TargetCli;
+use crate::component::{Component, ComponentType, InstallStatus};
+use crate::config_file::MergeStrategy;
+use crate::fs;
+use crate::fs::scanner::McpRegistration;
+use crate::mcp::{McpScope, McpServer, McpStatus};
+use crate::plugin::{Plugin, PluginStatus};
+
+/// One purge step (indices into the scanned lists)
+#[derive(Clone, Debug, PartialEq)]
+pub enum Step {
+    UnsetOutputStyle(String),
+    UnsetStatusline(String),
+    RemoveComponent(usize),
+    /// `scope` is None when the CLI reports the server but not where (Codex, project scope)
+    RemoveMcp { idx: usize, scope: Option<McpScope>, project: Option<String> },
+    RemovePlugin(usize),
+}
+
+#[derive(Debug, Default)]
+pub struct Plan {
+    pub steps: Vec<Step>,
+    pub kept: Vec<usize>, // Components edited locally (left in place)
+}
+
+impl Plan {
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+/// Current state the plan is built from
+pub struct Inputs<'a> {
+    pub components: &'a [Component],
+    pub mcp_servers: &'a [McpServer],
+    pub registrations: &'a [McpRegistration], // From the CLI config (see scanner.rs)
+    pub plugins: &'a [Plugin],
+    pub output_style: Option<&'a str>,
+    pub statusline: Option<&'a str>,
+}
+
+pub fn plan(inputs: &Inputs) -> Plan {
+    let mut plan = Plan::default();
+
+    // Merged config files go last, after hooks are unregistered from settings.json
+    let mut removed = Vec::new();
+    let mut config_files = Vec::new();
+    for (idx, c) in inputs.components.iter().enumerate() {
+        if c.status == InstallStatus::New {
+            continue;
+        }
+        if is_local_edit(c) {
+            plan.kept.push(idx);
+        } else if c.component_type == ComponentType::ConfigFile {
+            config_files.push(idx);
+        } else {
+            removed.push(idx);
+        }
+    }
+
+    // Defaults pointing at a component that goes away are unset
+    let removes = |component_type: ComponentType, name: &str| {
+        removed.iter().map(|&i| &inputs.components[i]).any(|c| {
+            c.component_type == component_type && (c.name == name || c.name.strip_suffix(".md") == Some(name))
+        })
+    };
+    if let Some(style) = inputs.output_style
+        && removes(ComponentType::OutputStyles, style)
+    {
+        plan.steps.push(Step::UnsetOutputStyle(style.to_string()));
+    }
+    if let Some(script) = inputs.statusline
+        && removes(ComponentType::Statusline, script)
+    {
+        plan.steps.push(Step::UnsetStatusline(script.to_string()));
+    }
+
+    plan.steps.extend(removed.into_iter().chain(config_files).map(Step::RemoveComponent));
+
+    for (idx, server) in inputs.mcp_servers.iter().enumerate() {
+        let mut registered = inputs.registrations.iter().filter(|r| r.name == server.def.name).peekable();
+        if registered.peek().is_none() {
+            if server.status == McpStatus::Installed {
+                plan.steps.push(Step::RemoveMcp { idx, scope: None, project: None });
+            }
+            continue;
+        }
+        plan.steps.extend(registered.map(|r| Step::RemoveMcp { idx, scope: Some(r.scope), project: r.project.clone() }));
+    }
+
+    for (idx, plugin) in inputs.plugins.iter().enumerate() {
+        if plugin.status == PluginStatus::Installed {
+            plan.steps.push(Step::RemovePlugin(idx));
+        }
+    }
+
+    // Stable, so steps keep the order above within a phase
+    plan.steps.sort_by_key(|step| phase(step, inputs.components));
+    plan
+}
+
+/// Edited after install: the file differs from the source and removing it would lose the edit
+/// (hook binaries are build output, and merges only take out what the source added)
+fn is_local_edit(c: &Component) -> bool {
+    c.status == InstallStatus::Modified
+        && match c.component_type {
+            ComponentType::Hooks => false,
+            ComponentType::ConfigFile => c.config_file_def().strategy == MergeStrategy::Overwrite,
+            _ => true,
+        }
+}
+
+impl Step {
+    pub fn describe(&self, components: &[Component], mcp_servers: &[McpServer], plugins: &[Plugin]) -> String {
+        match self {
+            Self::UnsetOutputStyle(style) => format!("unset output style {}", style),
+            Self::UnsetStatusline(script) => format!("unset statusline {}", script),
+            Self::RemoveComponent(i) => {
+                let c = &components[*i];
+                match c.component_type {
+                    ComponentType::Hooks => format!("unregister and remove {}", c.display_name()),
+                    ComponentType::ConfigFile => format!("take managed entries out of {}", c.display_name()),
+                    _ => format!("remove {}", c.display_name()),
+                }
+            }
+            Self::RemoveMcp { idx, scope, project } => {
+                let name = &mcp_servers[*idx].def.name;
+                match (scope, project) {
+                    (Some(scope), Some(project)) => format!("remove mcp {} ({}: {})", name, scope.display(), project),
+                    (Some(scope), None) => format!("remove mcp {} ({})", name, scope.display()),
+                    (None, _) => format!("remove mcp {}", name),
+                }
+            }
+            Self::RemovePlugin(i) => format!("uninstall plugin {}", plugins[*i].def.id()),
+        }
+    }
+}
+
+/// Steps run in phases: a step waits until every step of an earlier phase has finished.
+/// Defaults are unset and servers and plugins removed first, then components, then merged
+/// config files (after hooks are unregistered from settings.json).
+fn phase(step: &Step, components: &[Component]) -> u8 {
+    match step {
+        Step::RemoveComponent(i) if components[*i].component_type == ComponentType::ConfigFile => 2,
+        Step::RemoveComponent(_) => 1,
+        _ => 0,
+    }
+}
+
+fn apply(step: &Step, components: &[Component], mcp_servers: &[McpServer], plugins: &[Plugin], dest_dir: &Path, target_cli: TargetCli) -> anyhow::Result<()> {
+    match step {
+        Step::UnsetOutputStyle(_) => fs::installer::unset_output_style(dest_dir),
+        Step::UnsetStatusline(_) => fs::installer::unset_statusline(dest_dir),
+        Step::RemoveComponent(i) => fs::installer::remove_component(&components[*i], dest_dir),
+        // Codex has a single scope and no --scope flag
+        Step::RemoveMcp { idx, scope: Some(scope), project } if target_cli == TargetCli::Claude => {
+            fs::installer::remove_mcp_server_from(&mcp_servers[*idx], *scope, project.as_deref())
+        }
+        Step::RemoveMcp { idx, .. } => fs::installer::remove_mcp_server(&mcp_servers[*idx], target_cli),
+        Step::RemovePlugin(i) => fs::installer::remove_plugin(&plugins[*i]),
+    }
+}
+
+/// Drop the remembered merge choices once nothing is left to unmerge. Kept when a step
+/// failed, since a retry still needs to know what the merges added.
+pub fn forget_merge_choices(dest_dir: &Path, failed: bool) -> Option<String> {
+    let path = crate::conflict::choices_path(dest_dir);
+    if !path.exists() {
+        return None;
+    }
+    if failed {
+        return Some("[SKIP] Kept .installer/merge-choices.json for a retry of the failed steps".to_string());
+    }
+    Some(match std::fs::remove_file(&path) {
+        Ok(()) => "[OK] remove .installer/merge-choices.json".to_string(),
+        Err(e) => format!("[ERR] remove .installer/merge-choices.json: {}", e),
+    })
+}
+
+/// Run every step; failures are logged and the rest still run
+pub fn run(plan: &Plan, inputs: &Inputs, dest_dir: &Path, target_cli: TargetCli) -> Vec<String> {
+    let mut log = Vec::new();
+    // In plan order, which is sorted by phase
+    for step in &plan.steps {
+        let result = apply(step, inputs.components, inputs.mcp_servers, inputs.plugins, dest_dir, target_cli);
+        let label = step.describe(inputs.components, inputs.mcp_servers, inputs.plugins);
+        match result {
+            Ok(()) => log.push(format!("[OK] {}", label)),
+            Err(e) => log.push(format!("[ERR] {}: {}", label, e)),
+        }
+    }
+    let failed = log.iter().any(|l| l.starts_with("[ERR]"));
+    log.extend(forget_merge_choices(dest_dir, failed));
+    log
+}
+
+/// A confirmed purge with its own copy of the scanned lists, so the Installing view's workers
+/// can run its steps while the app rescans
+pub struct Batch {
+    pub plan: Plan,
+    pub components: Vec<Component>,
+    pub mcp_servers: Vec<McpServer>,
+    pub plugins: Vec<Plugin>,
+    pub dest_dir: PathBuf,
+    pub target_cli: TargetCli,
+    pub safety: String, // Snapshot taken before the purge
+}
+
+impl Batch {
+    pub fn describe(&self, step: usize) -> String {
+        self.plan.steps[step].describe(&self.components, &self.mcp_servers, &self.plugins)
+    }
+
+    pub fn phase(&self, step: usize) -> u8 {
+        phase(&self.plan.steps[step], &self.components)
+    }
+
+    /// Run one step; the `[OK]`/`[ERR]` log line
+    pub fn run_step(&self, step: usize) -> String {
+        let result = apply(&self.plan.steps[step], &self.components, &self.mcp_servers, &self.plugins, &self.dest_dir, self.target_cli);
+        match result {
+            Ok(()) => format!("[OK] {}", self.describe(step)),
+            Err(e) => format!("[ERR] {}: {}", self.describe(step), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp::McpServerDef;
+    use crate::plugin::PluginDef;
+    use std::path::PathBuf;
+
+    fn component(component_type: ComponentType, name: &str, status: InstallStatus) -> Component {
+        Component::new(component_type, name.to_string(), PathBuf::from(name), PathBuf::from(name), status)
+    }
+
+    #[test]
+    fn test_plan_keeps_edits_and_covers_every_scope() {
+        let components = vec![
+            component(ComponentType::Agents, "a.md", InstallStatus::Unchanged),
+            component(ComponentType::Agents, "edited.md", InstallStatus::Modified),
+            component(ComponentType::Agents, "never.md", InstallStatus::New),
+            component(ComponentType::ConfigFile, "settings.json", InstallStatus::Modified),
+            component(ComponentType::OutputStyles, "terse.md", InstallStatus::Unchanged),
+            component(ComponentType::Hooks, "guard", InstallStatus::Modified),
+        ];
+        let def: McpServerDef = serde_yaml::from_str("name: docs\ndescription: d\ncategory: c\ncommand: x").unwrap();
+        let mcp_servers = vec![McpServer::new(def, McpStatus::NotInstalled)];
+        let registrations = vec![
+            McpRegistration { name: "docs".into(), scope: McpScope::User, project: None, env: Vec::new(), launch: None },
+            McpRegistration { name: "docs".into(), scope: McpScope::Local, project: Some("/work/app".into()), env: Vec::new(), launch: None },
+            McpRegistration { name: "mine".into(), scope: McpScope::User, project: None, env: Vec::new(), launch: None },
+        ];
+        let plugin = |name: &str, status| {
+            Plugin::new(PluginDef { name: name.into(), marketplace: "m".into(), source: "s".into(), comment: None }, status)
+        };
+        let plugins = vec![plugin("p", PluginStatus::Installed), plugin("q", PluginStatus::NotInstalled)];
+
+        let inputs = Inputs {
+            components: &components,
+            mcp_servers: &mcp_servers,
+            registrations: &registrations,
+            plugins: &plugins,
+            output_style: Some("terse"),
+            statusline: Some("custom.sh"),
+        };
+        let plan = plan(&inputs);
+
+        assert_eq!(plan.kept, [1]);
+        assert_eq!(
+            plan.steps,
+            [
+                // Phase 0: defaults, servers and plugins
+                Step::UnsetOutputStyle("terse".into()),
+                Step::RemoveMcp { idx: 0, scope: Some(McpScope::User), project: None },
+                Step::RemoveMcp { idx: 0, scope: Some(McpScope::Local), project: Some("/work/app".into()) },
+                Step::RemovePlugin(0),
+                // Phase 1: components, then phase 2: merged settings.json last
+                Step::RemoveComponent(0),
+                Step::RemoveComponent(4),
+                Step::RemoveComponent(5),
+                Step::RemoveComponent(3),
+            ]
+        );
+        let phases: Vec<u8> = plan.steps.iter().map(|s| phase(s, &components)).collect();
+        assert!(phases.is_sorted(), "{:?}", phases);
+    }
+}
@@ -0,0 +1,226 @@
+//! Git-backed sources cloned into `~/.cache/claude-installer/sources/<url>-<ref>/`
+//!
+//! The checkout is pinned: startup only fetches (the TUI in the background), so
+//! upstream commits show up as "behind" until `installer layers update` moves the
+//! checkout forward.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RemoteSource {
+    pub git: String, // Clone URL (https://, ssh, file:// or a local path)
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>, // Branch, tag or commit (default: remote HEAD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>, // Source root inside the repo (e.g. config/ai/claude)
+}
+
+/// Pinned commit of a synced remote and how far upstream has moved
+#[derive(Clone, Debug)]
+pub struct RemoteStatus {
+    pub source: RemoteSource,
+    pub reference: String,
+    pub commit: String, // Short hash of the checkout
+    pub behind: usize,  // Upstream commits not checked out yet (as of the last fetch)
+    pub fetched: bool,  // False until this run's fetch finished
+    pub error: Option<String>, // Fetch failure (the cached checkout is still used)
+}
+
+impl RemoteStatus {
+    pub fn summary(&self) -> String {
+        match (&self.error, self.fetched, self.behind) {
+            (Some(_), _, _) => format!("{} @ {} (offline)", self.reference, self.commit),
+            (None, false, _) => format!("{} @ {} (fetching)", self.reference, self.commit),
+            (None, true, 0) => format!("{} @ {} (up to date)", self.reference, self.commit),
+            (None, true, n) => format!("{} @ {} ({} new upstream)", self.reference, self.commit, n),
+        }
+    }
+}
+
+impl RemoteSource {
+    /// Repository name from the URL (`.../team-claude.git` -> `team-claude`)
+    pub fn name(&self) -> String {
+        let url = self.git.trim_end_matches('/');
+        let last = url.rsplit(['/', ':']).next().unwrap_or(url);
+        last.trim_end_matches(".git").to_string()
+    }
+
+    fn reference(&self) -> &str {
+        self.reference.as_deref().unwrap_or("HEAD")
+    }
+
+    /// One checkout per URL and ref, so two refs of a repo do not share a pinned commit
+    fn checkout_dir(&self, cache_root: &Path) -> PathBuf {
+        let slug: String = format!("{}-{}", self.git, self.reference())
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' })
+            .collect();
+        cache_root.join(slug.trim_matches('_'))
+    }
+
+    /// Source root inside the checkout
+    fn source_dir(&self, checkout: &Path) -> PathBuf {
+        match &self.path {
+            Some(path) => checkout.join(path),
+            None => checkout.to_path_buf(),
+        }
+    }
+}
+
+pub fn cache_root() -> Result<PathBuf> {
+    dirs::cache_dir()
+        .map(|d| d.join("claude-installer").join("sources"))
+        .ok_or_else(|| anyhow::anyhow!("Cannot find cache directory"))
+}
+
+/// Clone on first use, otherwise fetch (when `fetch` is set) without moving the pinned
+/// checkout. Returns the source root and its status.
+pub fn sync(remote: &RemoteSource, cache_root: &Path, fetch: bool) -> Result<(PathBuf, RemoteStatus)> {
+    let checkout = remote.checkout_dir(cache_root);
+
+    let mut error = None;
+    let mut fetched = true;
+    if checkout.join(".git").exists() {
+        if !fetch {
+            fetched = false;
+        } else if let Err(e) = git(&checkout, &["fetch", "--quiet", "--tags", "--force", "origin"]) {
+            error = Some(format!("{:#}", e));
+        }
+    } else {
+        std::fs::create_dir_all(cache_root)?;
+        let dir = checkout.to_string_lossy();
+        git(cache_root, &["clone", "--quiet", "--no-checkout", &remote.git, &dir])
+            .with_context(|| format!("Cannot clone {}", remote.git))?;
+        let commit = upstream_commit(&checkout, remote.reference())?;
+        git(&checkout, &["checkout", "--quiet", "--detach", &commit])?;
+    }
+
+    let source_dir = remote.source_dir(&checkout);
+    if !source_dir.is_dir() {
+        anyhow::bail!("{} has no {}", remote.git, remote.path.as_deref().unwrap_or("checkout"));
+    }
+
+    let mut status = status(&checkout, remote)?;
+    status.fetched = fetched;
+    status.error = error;
+    Ok((source_dir, status))
+}
+
+/// Move the pinned checkout to the fetched upstream commit
+pub fn update(remote: &RemoteSource, cache_root: &Path) -> Result<RemoteStatus> {
+    let (_, before) = sync(remote, cache_root, true)?;
+    if let Some(e) = before.error {
+        anyhow::bail!("Cannot fetch {}: {}", remote.git, e);
+    }
+
+    let checkout = remote.checkout_dir(cache_root);
+    let commit = upstream_commit(&checkout, remote.reference())?;
+    git(&checkout, &["checkout", "--quiet", "--detach", &commit])?;
+    status(&checkout, remote)
+}
+
+fn status(checkout: &Path, remote: &RemoteSource) -> Result<RemoteStatus> {
+    let upstream = upstream_commit(checkout, remote.reference())?;
+    let behind = git(checkout, &["rev-list", "--count", &format!("HEAD..{}", upstream)])?
+        .parse()
+        .unwrap_or(0);
+
+    Ok(RemoteStatus {
+        source: remote.clone(),
+        reference: remote.reference().to_string(),
+        commit: git(checkout, &["rev-parse", "--short", "HEAD"])?,
+        behind,
+        fetched: true,
+        error: None,
+    })
+}
+
+/// Commit a ref points to upstream: a remote branch first, then a tag or commit
+fn upstream_commit(checkout: &Path, reference: &str) -> Result<String> {
+    [format!("origin/{}", reference), reference.to_string()]
+        .iter()
+        .find_map(|r| git(checkout, &["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", r)]).ok())
+        .ok_or_else(|| anyhow::anyhow!("Unknown ref '{}'", reference))
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        anyhow::bail!("git {}: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    fn commit(work: &Path, rel: &str, content: &str) {
+        let path = work.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+        git(work, &["add", "-A"]).unwrap();
+        git(work, &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "--quiet", "-m", rel]).unwrap();
+    }
+
+    #[test]
+    fn test_sync_pins_until_update() {
+        let root = temp_dir("remote-sync");
+        let work = root.join("work");
+        let bare = root.join("team-claude.git");
+        let cache = root.join("cache");
+
+        std::fs::create_dir_all(&work).unwrap();
+        git(&work, &["init", "--quiet", "-b", "main"]).unwrap();
+        commit(&work, "claude/agents/a.md", "v1");
+        git(&root, &["clone", "--quiet", "--bare", "work", "team-claude.git"]).unwrap();
+
+        let remote = RemoteSource {
+            git: format!("file://{}", bare.display()),
+            reference: Some("main".to_string()),
+            path: Some("claude".to_string()),
+        };
+        assert_eq!(remote.name(), "team-claude");
+
+        let (source, status) = sync(&remote, &cache, false).unwrap();
+        assert_eq!(std::fs::read_to_string(source.join("agents/a.md")).unwrap(), "v1");
+        assert_eq!(status.behind, 0);
+        assert!(status.fetched); // Just cloned
+
+        // Upstream moves on; the checkout stays pinned
+        commit(&work, "claude/agents/a.md", "v2");
+        git(&work, &["push", "--quiet", &bare.to_string_lossy(), "main"]).unwrap();
+
+        let (_, status) = sync(&remote, &cache, false).unwrap();
+        assert!(!status.fetched);
+        assert_eq!(status.behind, 0);
+
+        let (source, status) = sync(&remote, &cache, true).unwrap();
+        assert_eq!(std::fs::read_to_string(source.join("agents/a.md")).unwrap(), "v1");
+        assert_eq!(status.behind, 1);
+
+        // Another ref of the same repo gets its own checkout
+        git(&work, &["tag", "v1", "HEAD~1"]).unwrap();
+        git(&work, &["push", "--quiet", &bare.to_string_lossy(), "v1"]).unwrap();
+        let tagged = RemoteSource { reference: Some("v1".to_string()), ..remote.clone() };
+        let (tagged_source, _) = sync(&tagged, &cache, true).unwrap();
+        assert_ne!(tagged_source, source);
+
+        let status = update(&remote, &cache).unwrap();
+        assert_eq!(std::fs::read_to_string(source.join("agents/a.md")).unwrap(), "v2");
+        assert_eq!(status.behind, 0);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,240 @@
+//! Machine-readable install state for `installer status` (dashboards, CI drift checks)
+//!
+//! Covers every component with its source and destination hashes, MCP servers and plugins
+//! with the scope they are installed in, and the managed keys of JSON-merged config files
+//! that no longer match the source. `differs()` is what `status --check` exits on: only
+//! what is installed and no longer matches counts, not what was never installed.
+
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::app::TargetCli;
+use crate::component::{Component, ComponentType, InstallStatus};
+use crate::conflict::{self, Resolution};
+use crate::fs::cache::ScanCache;
+use crate::fs::merge;
+use crate::fs::scanner::{self, McpRegistration};
+use crate::mcp::{McpScope, McpServer, McpStatus};
+use crate::plugin::{Plugin, PluginStatus};
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub components: Vec<ComponentEntry>,
+    pub mcp_servers: Vec<McpEntry>,
+    pub plugins: Vec<PluginEntry>,
+    pub settings_drift: Vec<DriftEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComponentEntry {
+    #[serde(rename = "type")]
+    pub component_type: String,
+    pub name: String,
+    pub status: &'static str,
+    pub layer: usize,
+    pub source: String,
+    pub dest: String,
+    pub source_hash: Option<String>, // None for directories and missing files
+    pub dest_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct McpEntry {
+    pub name: String,
+    pub status: &'static str,
+    pub scope: Option<&'static str>, // None when not installed
+    pub drift: bool,                 // Installed with another command or URL than the catalog's
+}
+
+#[derive(Debug, Serialize)]
+pub struct PluginEntry {
+    pub id: String,
+    pub status: &'static str,
+    pub scope: Option<&'static str>,
+    pub drift: bool, // Installed, but its marketplace is missing or has another source
+}
+
+#[derive(Debug, Serialize)]
+pub struct DriftEntry {
+    pub file: String,
+    pub key: String,
+    pub repo: Value,
+    pub local: Option<Value>, // None when the key is missing
+    pub kept_local: bool,     // Resolved as "keep local" in the merge review
+}
+
+impl Report {
+    pub fn build(
+        components: &[Component],
+        mcp_servers: &[McpServer],
+        plugins: &[Plugin],
+        dest_dir: &Path,
+        target_cli: TargetCli,
+        cache: &ScanCache,
+    ) -> Result<Self> {
+        let components_out = components
+            .iter()
+            .map(|c| ComponentEntry {
+                component_type: c.component_type.display_name().to_string(),
+                name: c.name.clone(),
+                status: status_key(&c.status),
+                layer: c.layer,
+                source: c.source_path.display().to_string(),
+                dest: c.dest_path.display().to_string(),
+                source_hash: file_hash(&c.source_path, cache),
+                dest_hash: file_hash(&c.dest_path, cache),
+            })
+            .collect();
+
+        let registrations = scanner::mcp_registrations(target_cli, dest_dir);
+        let mcp_out = mcp_servers
+            .iter()
+            .map(|m| {
+                let installed = m.status == McpStatus::Installed;
+                let launch = m.launch();
+                McpEntry {
+                    name: m.def.name.clone(),
+                    status: if installed { "installed" } else { "not-installed" },
+                    scope: installed.then(|| scope_of(&registrations, &m.def.name)),
+                    drift: installed
+                        && registrations.iter().any(|r| r.name == m.def.name && r.launch.is_some() && r.launch != launch),
+                }
+            })
+            .collect();
+
+        // The installer enables plugins in the user settings only
+        let marketplaces = scanner::read_known_marketplaces(&dest_dir.join("plugins"));
+        let plugins_out = plugins
+            .iter()
+            .map(|p| {
+                let installed = p.status == PluginStatus::Installed;
+                let registered = marketplaces.iter().find(|(name, _, _)| *name == p.def.marketplace);
+                PluginEntry {
+                    id: p.def.id(),
+                    status: if installed { "installed" } else { "not-installed" },
+                    scope: installed.then_some("user"),
+                    drift: installed && !registered.is_some_and(|(_, source, _)| same_source(source, &p.def.source)),
+                }
+            })
+            .collect();
+
+        let mut drift = Vec::new();
+        for c in components.iter().filter(|c| c.component_type == ComponentType::ConfigFile) {
+            let choices = conflict::load(dest_dir, &c.name);
+            for d in merge::drift(&c.config_file_def(), &c.source_path, &c.dest_path)? {
+                drift.push(DriftEntry {
+                    file: c.name.clone(),
+                    kept_local: choices.get(&d.key) == Some(&Resolution::KeepLocal),
+                    key: d.key,
+                    repo: d.repo,
+                    local: d.local,
+                });
+            }
+        }
+
+        Ok(Self { components: components_out, mcp_servers: mcp_out, plugins: plugins_out, settings_drift: drift })
+    }
+
+    /// Anything installed that no longer matches the source: modified components, MCP
+    /// servers and plugins that drifted, and settings drift not kept on purpose
+    pub fn differs(&self) -> bool {
+        self.components.iter().any(|c| c.status == "modified")
+            || self.mcp_servers.iter().any(|m| m.drift)
+            || self.plugins.iter().any(|p| p.drift)
+            || self.settings_drift.iter().any(|d| !d.kept_local)
+    }
+}
+
+fn status_key(status: &InstallStatus) -> &'static str {
+    match status {
+        InstallStatus::New => "new",
+        InstallStatus::Modified => "modified",
+        InstallStatus::Unchanged => "unchanged",
+        InstallStatus::Managed => "managed",
+    }
+}
+
+fn file_hash(path: &Path, cache: &ScanCache) -> Option<String> {
+    if !path.is_file() {
+        return None;
+    }
+    cache.hash(path).ok().map(|h| format!("{:016x}", h))
+}
+
+/// Marketplace sources written as `owner/repo` or as a GitHub URL, with or without `.git`
+fn same_source(a: &str, b: &str) -> bool {
+    let normalize = |s: &str| {
+        let s = s.trim().trim_end_matches('/');
+        let s = s.strip_suffix(".git").unwrap_or(s);
+        s.strip_prefix("https://github.com/").unwrap_or(s).to_string()
+    };
+    normalize(a) == normalize(b)
+}
+
+/// Scope of an installed server: local when it is registered only for the current directory.
+/// Codex has only its config.toml (user scope).
+fn scope_of(registrations: &[McpRegistration], name: &str) -> &'static str {
+    let cwd = std::env::current_dir().map(|d| d.display().to_string()).unwrap_or_default();
+    let registered = |scope: McpScope, project: Option<&str>| {
+        registrations.iter().any(|r| r.name == name && r.scope == scope && (project.is_none() || r.project.as_deref() == project))
+    };
+    if registered(McpScope::Local, Some(&cwd)) && !registered(McpScope::User, None) {
+        "local"
+    } else {
+        "user"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{temp_dir, write};
+    use crate::plugin::PluginDef;
+
+    #[test]
+    fn test_report_hashes_and_differs() {
+        let dir = temp_dir("report-build");
+        let dest_dir = dir.join(".claude");
+        let (source, dest) = (write(&dir, "a.md", "agent"), write(&dest_dir, "agents/a.md", "agent"));
+        write(&dest_dir, "plugins/known_marketplaces.json", r#"{"m":{"source":{"source":"github","repo":"o/r"}}}"#);
+        write(&dir, ".claude.json", r#"{"mcpServers":{"docs":{"command":"npx","args":["-y","docs-mcp"]}}}"#);
+
+        let agent = Component::new(ComponentType::Agents, "a.md".into(), source.clone(), dest, InstallStatus::Unchanged);
+        // Never installed: not a difference
+        let new = Component::new(ComponentType::Agents, "b.md".into(), source, dest_dir.join("agents/b.md"), InstallStatus::New);
+        let plugin = Plugin::new(
+            PluginDef { name: "p".into(), marketplace: "m".into(), source: "o/r".into(), comment: None },
+            PluginStatus::Installed,
+        );
+        let server = |command: &str, status| {
+            let def = serde_yaml::from_str(&format!("name: docs\ndescription: d\ncategory: c\ncommand: {}", command)).unwrap();
+            McpServer::new(def, status)
+        };
+        let (components, plugins) = ([agent, new], [plugin]);
+        let build = |server: McpServer| {
+            Report::build(&components, &[server], &plugins, &dest_dir, TargetCli::Claude, &ScanCache::default()).unwrap()
+        };
+
+        let report = build(server("npx -y docs-mcp", McpStatus::Installed));
+        let entry = &report.components[0];
+        assert!(entry.source_hash.is_some() && entry.source_hash == entry.dest_hash);
+        assert_eq!(report.plugins[0].scope, Some("user"));
+        assert!(!report.plugins[0].drift && !report.mcp_servers[0].drift);
+        assert!(!report.differs());
+        assert!(!build(server("other-mcp", McpStatus::NotInstalled)).differs());
+
+        // Registered with an older command than the catalog's
+        let report = build(server("npx -y docs-mcp@2", McpStatus::Installed));
+        assert!(report.mcp_servers[0].drift);
+        assert!(report.differs());
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["components"][0]["type"], "agents");
+        assert_eq!(json["components"][0]["status"], "unchanged");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
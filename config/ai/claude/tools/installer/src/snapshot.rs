@@ -0,0 +1,391 @@
+//! Full snapshots of the managed CLI config (~/.claude or ~/.codex)
+//!
+//! Each snapshot is a single archive `<dest_dir>/.installer/snapshots/<id>.tar`:
+//! - `snapshot.json` (first entry) managed dirs, captured files, MCP registrations and plugins
+//! - `files/<relative path>` copies of everything under the managed dirs, plus the config
+//!   files in config-files.yaml, the CLI's own settings.json or config.toml and the merge
+//!   ledger (`.installer/merge-choices.json`, see conflict.rs)
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use crate::app::{Tab, TargetCli};
+use crate::config_file::load_manifest;
+use crate::fs;
+use crate::fs::scanner::McpRegistration;
+use crate::history::unix_now;
+use crate::mcp::McpServer;
+use crate::plugin::Plugin;
+
+pub fn snapshots_dir(dest_dir: &Path) -> PathBuf {
+    dest_dir.join(".installer").join("snapshots")
+}
+
+fn archive_path(dest_dir: &Path, id: &str) -> PathBuf {
+    snapshots_dir(dest_dir).join(format!("{}.tar", id))
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: String,
+    pub timestamp: u64,
+    pub target: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub dirs: Vec<String>,  // Managed component dirs, relative to dest_dir
+    #[serde(default)]
+    pub config_files: Vec<String>, // Managed top-level files, captured when they existed
+    pub files: Vec<String>, // Captured files, relative to dest_dir
+    pub mcp_servers: Vec<McpRegistration>, // With scope, project and env, replayed on restore
+    pub plugins: Vec<String>,
+}
+
+/// Component dirs and top-level files the installer manages for a CLI: the config files
+/// of every source layer's manifest, the CLI's own config and the merge ledger
+fn managed_paths(sources: &[PathBuf], target_cli: TargetCli) -> (Vec<String>, Vec<String>) {
+    let types = Tab::for_cli(target_cli).into_iter().filter_map(|t| t.to_component_type());
+
+    let mut dirs = Vec::new();
+    let mut files = vec![match target_cli {
+        TargetCli::Claude => "settings.json".to_string(),
+        TargetCli::Codex => "config.toml".to_string(),
+    }];
+    for component_type in types {
+        if let Some(dir) = component_type.dest_subdir() {
+            dirs.push(dir.to_string());
+        }
+    }
+
+    // An unreadable manifest is reported by the scan; the snapshot still covers the rest
+    let defs = sources.iter().flat_map(|source| load_manifest(source).unwrap_or_default());
+    let extra = defs.filter(|def| def.applies_to(target_cli)).map(|def| def.dest().to_string());
+    for file in extra.chain([".installer/merge-choices.json".to_string()]) {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    (dirs, files)
+}
+
+/// Snapshot the managed state, including where and how each MCP server is registered
+pub fn create(dest_dir: &Path, sources: &[PathBuf], target_cli: TargetCli, label: Option<&str>) -> Result<Snapshot> {
+    let timestamp = unix_now();
+    let root = snapshots_dir(dest_dir);
+    let mut id = timestamp.to_string();
+    let mut n = 1;
+    while archive_path(dest_dir, &id).exists() {
+        n += 1;
+        id = format!("{}-{}", timestamp, n);
+    }
+    std::fs::create_dir_all(&root).with_context(|| format!("Cannot create {}", root.display()))?;
+
+    let (dirs, config_files) = managed_paths(sources, target_cli);
+    let mut files = Vec::new();
+
+    for sub in &dirs {
+        for entry in WalkDir::new(dest_dir.join(sub)).into_iter().flatten() {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            if let Ok(rel) = entry.path().strip_prefix(dest_dir) {
+                files.push(rel.to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    files.extend(config_files.iter().filter(|file| dest_dir.join(file).is_file()).cloned());
+
+    let plugins = match target_cli {
+        TargetCli::Claude => fs::scanner::enabled_plugins(&dest_dir.join("settings.json")),
+        TargetCli::Codex => Vec::new(),
+    };
+
+    let snapshot = Snapshot {
+        id,
+        timestamp,
+        target: target_cli.display_name().to_string(),
+        label: label.map(|l| l.to_string()),
+        dirs,
+        config_files,
+        files,
+        mcp_servers: fs::scanner::mcp_registrations(target_cli, dest_dir),
+        plugins,
+    };
+
+    // Written under a temporary name so a half-written archive is never listed
+    let path = archive_path(dest_dir, &snapshot.id);
+    let partial = path.with_extension("tar.partial");
+    write_archive(&partial, dest_dir, &snapshot)
+        .and_then(|()| std::fs::rename(&partial, &path).map_err(Into::into))
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&partial);
+        })
+        .with_context(|| format!("Cannot write {}", path.display()))?;
+    Ok(snapshot)
+}
+
+fn write_archive(path: &Path, dest_dir: &Path, snapshot: &Snapshot) -> Result<()> {
+    let mut builder = tar::Builder::new(File::create(path)?);
+
+    let manifest = serde_json::to_vec_pretty(snapshot)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(manifest.len() as u64);
+    header.set_mode(0o644);
+    header.set_mtime(snapshot.timestamp);
+    header.set_cksum();
+    builder.append_data(&mut header, "snapshot.json", manifest.as_slice())?;
+
+    for rel in &snapshot.files {
+        builder
+            .append_path_with_name(dest_dir.join(rel), format!("files/{}", rel))
+            .with_context(|| format!("Cannot archive {}", rel))?;
+    }
+    builder.into_inner()?.sync_all()?;
+    Ok(())
+}
+
+/// The manifest is the first archive entry, so listing does not read the files
+fn read_manifest(path: &Path) -> Option<Snapshot> {
+    let mut archive = tar::Archive::new(File::open(path).ok()?);
+    let entry = archive.entries().ok()?.next()?.ok()?;
+    if entry.path().ok()?.as_ref() != Path::new("snapshot.json") {
+        return None;
+    }
+    serde_json::from_reader(entry).ok()
+}
+
+/// All snapshots, newest first
+pub fn list(dest_dir: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = std::fs::read_dir(snapshots_dir(dest_dir)) else {
+        return Vec::new();
+    };
+
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "tar"))
+        .filter_map(|path| read_manifest(&path))
+        .collect();
+
+    snapshots.sort_by(|a, b| b.timestamp.cmp(&a.timestamp).then_with(|| b.id.cmp(&a.id)));
+    snapshots
+}
+
+pub fn find(dest_dir: &Path, id: &str) -> Result<Snapshot> {
+    list(dest_dir)
+        .into_iter()
+        .find(|s| s.id == id)
+        .ok_or_else(|| anyhow::anyhow!("Snapshot not found: {}", id))
+}
+
+/// Restore a snapshot: managed dirs and config files are made identical to the snapshot,
+/// MCP servers and plugins are converged through the CLI, and settings.json is restored
+/// last. Returns log lines.
+pub fn restore(
+    dest_dir: &Path,
+    snapshot: &Snapshot,
+    target_cli: TargetCli,
+    mcp_catalog: &[McpServer],
+    plugin_catalog: &[Plugin],
+) -> Result<Vec<String>> {
+    if snapshot.target != target_cli.display_name() {
+        anyhow::bail!("Snapshot {} was taken for {}", snapshot.id, snapshot.target);
+    }
+
+    let mut log = Vec::new();
+
+    // 1. Files: delete what the snapshot does not have, unpack everything it has.
+    //    settings.json is handled last (step 4).
+    for rel in snapshot.config_files.iter().filter(|f| *f != "settings.json" && !snapshot.files.contains(f)) {
+        if dest_dir.join(rel).is_file() {
+            std::fs::remove_file(dest_dir.join(rel))?;
+            log.push(format!("[OK] Deleted {}", rel));
+        }
+    }
+    for sub in &snapshot.dirs {
+        for entry in WalkDir::new(dest_dir.join(sub)).into_iter().flatten() {
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let Ok(rel) = entry.path().strip_prefix(dest_dir) else {
+                continue;
+            };
+            let rel = rel.to_string_lossy().replace('\\', "/");
+            if !snapshot.files.contains(&rel) {
+                std::fs::remove_file(entry.path())?;
+                log.push(format!("[OK] Deleted {}", rel));
+            }
+        }
+    }
+
+    let path = archive_path(dest_dir, &snapshot.id);
+    let mut archive = tar::Archive::new(File::open(&path).with_context(|| format!("Cannot open {}", path.display()))?);
+    let mut settings = None;
+    let mut restored = 0;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let name = entry.path()?.to_string_lossy().replace('\\', "/");
+        // Only files the manifest lists (never paths outside dest_dir)
+        let Some(rel) = name.strip_prefix("files/").filter(|rel| snapshot.files.iter().any(|f| f == rel)) else {
+            continue;
+        };
+        if rel == "settings.json" {
+            let mut content = Vec::new();
+            std::io::Read::read_to_end(&mut entry, &mut content)?;
+            settings = Some(content);
+            continue;
+        }
+        let to = dest_dir.join(rel);
+        if let Some(parent) = to.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&to).with_context(|| format!("Cannot restore {}", rel))?;
+        restored += 1;
+    }
+    log.push(format!("[OK] Restored {} files", restored));
+
+    // 2. MCP servers, with the scope, project and env they had (only catalog servers
+    //    can be re-added). Codex registrations already came back with config.toml.
+    let current = fs::scanner::mcp_registrations(target_cli, dest_dir);
+    for registration in current.iter().filter(|r| !snapshot.mcp_servers.contains(r)) {
+        if let Some(server) = mcp_catalog.iter().find(|m| m.def.name == registration.name) {
+            let project = registration.project.as_deref();
+            let result = match target_cli {
+                TargetCli::Claude => fs::installer::remove_mcp_server_from(server, registration.scope, project),
+                TargetCli::Codex => fs::installer::remove_mcp_server(server, target_cli),
+            };
+            log.push(cli_result("Removed MCP", &registration_label(registration), result));
+        }
+    }
+    for registration in snapshot.mcp_servers.iter().filter(|r| !current.contains(r)) {
+        let result = match mcp_catalog.iter().find(|m| m.def.name == registration.name) {
+            Some(server) => fs::installer::install_mcp_server(
+                server,
+                registration.scope,
+                registration.project.as_deref(),
+                &registration.env,
+                target_cli,
+            )
+            .map(|_| ()),
+            None => Err(anyhow::anyhow!("not in mcps.yaml, add it manually")),
+        };
+        log.push(cli_result("Added MCP", &registration_label(registration), result));
+    }
+
+    // 3. Plugins (before settings.json, which holds enabledPlugins)
+    let enabled = fs::scanner::enabled_plugins(&dest_dir.join("settings.json"));
+    for id in snapshot.plugins.iter().filter(|p| !enabled.contains(p)) {
+        let result = match plugin_catalog.iter().find(|p| &p.def.id() == id) {
+            Some(plugin) => fs::installer::install_plugin(plugin).map(|_| ()),
+            None => Err(anyhow::anyhow!("marketplace unknown, install it manually")),
+        };
+        log.push(cli_result("Installed plugin", id, result));
+    }
+    for id in enabled.iter().filter(|p| !snapshot.plugins.contains(p)) {
+        if let Some(plugin) = plugin_catalog.iter().find(|p| &p.def.id() == id) {
+            let result = fs::installer::remove_plugin(plugin);
+            log.push(cli_result("Removed plugin", id, result));
+        }
+    }
+
+    // 4. settings.json
+    let settings_path = dest_dir.join("settings.json");
+    if let Some(content) = settings {
+        std::fs::write(&settings_path, content)?;
+        log.push("[OK] Restored settings.json".to_string());
+    } else if target_cli == TargetCli::Claude && settings_path.exists() {
+        std::fs::remove_file(&settings_path)?;
+        log.push("[OK] Deleted settings.json".to_string());
+    }
+
+    Ok(log)
+}
+
+/// `name` or `name (local: /project)`
+pub fn registration_label(registration: &McpRegistration) -> String {
+    match &registration.project {
+        Some(project) => format!("{} ({}: {})", registration.name, registration.scope.display(), project),
+        None => registration.name.clone(),
+    }
+}
+
+fn cli_result(action: &str, name: &str, result: Result<()>) -> String {
+    match result {
+        Ok(()) => format!("[OK] {} {}", action, name),
+        Err(e) => format!("[ERR] {}: {}", name, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{temp_dir, write};
+
+    #[test]
+    fn test_snapshot_round_trip() {
+        let home = temp_dir("snapshot-roundtrip");
+        let dest = home.join(".claude");
+        write(&dest, "agents/kept.md", "v1");
+        write(&dest, "skills/demo/SKILL.md", "skill");
+        write(&dest, "settings.json", "{\"enabledPlugins\":{}}");
+        write(&dest, "projects/unmanaged.txt", "untouched");
+        write(&dest, "CLAUDE.md", "# Mine\n");
+        write(&dest, ".installer/merge-choices.json", "{\"settings.json\":{}}");
+        let source = home.join("source");
+        write(&source, "config-files.yaml", "files:\n  - source: CLAUDE.md\n    strategy: markdown-block\n  - source: keybindings.json\n");
+        write(
+            &home,
+            ".claude.json",
+            r#"{"projects":{"/work/app":{"mcpServers":{"docs":{"command":"docs","env":{"TOKEN":"abc"}}}}}}"#,
+        );
+
+        let snapshot = create(&dest, std::slice::from_ref(&source), TargetCli::Claude, Some("before")).unwrap();
+        assert!(snapshot.files.contains(&"agents/kept.md".to_string()));
+        assert_eq!(snapshot.config_files, ["settings.json", "CLAUDE.md", "keybindings.json", ".installer/merge-choices.json"]);
+        assert!(snapshot.files.contains(&"CLAUDE.md".to_string()) && !snapshot.files.contains(&"keybindings.json".to_string()));
+        assert!(!snapshot.files.iter().any(|f| f.starts_with("projects/")));
+        assert_eq!(
+            snapshot.mcp_servers,
+            vec![McpRegistration {
+                name: "docs".into(),
+                scope: crate::mcp::McpScope::Local,
+                project: Some("/work/app".into()),
+                env: vec![("TOKEN".into(), "abc".into())],
+                launch: Some("docs".into()),
+            }]
+        );
+
+        // One archive file per snapshot
+        let entries: Vec<_> = std::fs::read_dir(snapshots_dir(&dest)).unwrap().flatten().map(|e| e.file_name()).collect();
+        assert_eq!(entries, vec![std::ffi::OsString::from(format!("{}.tar", snapshot.id))]);
+
+        // Experiment
+        write(&dest, "agents/kept.md", "v2");
+        write(&dest, "agents/added.md", "new");
+        std::fs::remove_file(dest.join("skills/demo/SKILL.md")).unwrap();
+        write(&dest, "settings.json", "{\"hooks\":{}}");
+        write(&home, ".claude.json", "{}");
+        write(&dest, "CLAUDE.md", "# Mine\n\nInstalled block\n");
+        write(&dest, "keybindings.json", "{}");
+        write(&dest, ".installer/merge-choices.json", "{}");
+
+        let found = find(&dest, &snapshot.id).unwrap();
+        assert_eq!(found.mcp_servers, snapshot.mcp_servers);
+        let log = restore(&dest, &found, TargetCli::Claude, &[], &[]).unwrap();
+        assert!(log.contains(&"[ERR] docs (local: /work/app): not in mcps.yaml, add it manually".to_string()));
+
+        assert_eq!(std::fs::read_to_string(dest.join("agents/kept.md")).unwrap(), "v1");
+        assert!(!dest.join("agents/added.md").exists());
+        assert!(dest.join("skills/demo/SKILL.md").exists());
+        assert_eq!(std::fs::read_to_string(dest.join("settings.json")).unwrap(), "{\"enabledPlugins\":{}}");
+        assert!(dest.join("projects/unmanaged.txt").exists());
+        assert_eq!(std::fs::read_to_string(dest.join("CLAUDE.md")).unwrap(), "# Mine\n");
+        assert!(!dest.join("keybindings.json").exists());
+        assert_eq!(std::fs::read_to_string(dest.join(".installer/merge-choices.json")).unwrap(), "{\"settings.json\":{}}");
+
+        let _ = std::fs::remove_dir_all(&home);
+    }
+}
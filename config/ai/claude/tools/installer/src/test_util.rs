@@ -0,0 +1,19 @@
+//! Fixture helpers for the unit tests
+
+use std::path::{Path, PathBuf};
+
+/// Empty scratch directory `installer-<name>-<pid>` under the system temp dir
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("installer-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write `content` to `dir/rel`, creating parent directories
+pub fn write(dir: &Path, rel: &str, content: &str) -> PathBuf {
+    let path = dir.join(rel);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, content).unwrap();
+    path
+}
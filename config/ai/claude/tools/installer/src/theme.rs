@@ -1,218 +1,469 @@
-use ratatui::style::Color;
+//! Color themes loaded from TOML files
+//!
+//! Bundled themes live in `src/themes/`; files in `~/.config/claude-installer/themes/`
+//! add more (or replace a bundled one with the same file name). See `themes/mocha.toml`
+//! for the format. The chosen theme is saved as `theme:` in the installer config.
+
+use std::path::PathBuf;
 
+use anyhow::{anyhow, Context, Result};
+use ratatui::style::Color;
+use toml_edit::DocumentMut;
+
+const BUNDLED: &[(&str, &str)] = &[
+    ("mocha", include_str!("themes/mocha.toml")),
+    ("latte", include_str!("themes/latte.toml")),
+    ("frappe", include_str!("themes/frappe.toml")),
+    ("macchiato", include_str!("themes/macchiato.toml")),
+    ("high-contrast", include_str!("themes/high-contrast.toml")),
+];
+
+/// Color keys of a theme file, required ones first
+const REQUIRED_KEYS: &[&str] = &[
+    "bg_primary", "bg_secondary", "text_primary", "text_secondary", "text_muted", "accent_primary",
+    "accent_secondary", "success", "warning", "error", "border", "selection_bg", "highlight", "peach",
+];
+const OPTIONAL_KEYS: &[&str] = &["info", "border_focused", "selection_fg", "diff_added", "diff_removed", "spinner"];
+
+/// What the terminal can display
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ThemeMode {
-    Mocha,  // Catppuccin Dark
-    Latte,  // Catppuccin Light
+pub enum ColorSupport {
+    TrueColor,
+    Ansi16,  // Colors mapped by hue (status colors by role) to the 16 terminal colors
+    NoColor, // NO_COLOR is set: terminal defaults only
 }
 
-impl ThemeMode {
-    pub fn toggle(&self) -> Self {
-        match self {
-            Self::Mocha => Self::Latte,
-            Self::Latte => Self::Mocha,
+impl ColorSupport {
+    pub fn detect() -> Self {
+        if std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+            return Self::NoColor;
+        }
+        let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" || std::env::var_os("WT_SESSION").is_some() {
+            Self::TrueColor
+        } else {
+            Self::Ansi16
         }
     }
+}
 
-    pub fn name(&self) -> &str {
-        match self {
-            Self::Mocha => "Mocha",
-            Self::Latte => "Latte",
+/// Semantic colors of one theme
+#[derive(Debug, Clone)]
+struct Palette {
+    bg_primary: Color,
+    bg_secondary: Color,
+    text_primary: Color,
+    text_secondary: Color,
+    text_muted: Color,
+    accent_primary: Color,
+    accent_secondary: Color,
+    success: Color,
+    warning: Color,
+    error: Color,
+    info: Color,
+    border: Color,
+    border_focused: Color,
+    selection_bg: Color,
+    selection_fg: Color,
+    highlight: Color,
+    diff_added: Color,
+    diff_removed: Color,
+    spinner: Color,
+    peach: Color,
+}
+
+#[derive(Debug, Clone)]
+pub struct ThemeDef {
+    pub id: String, // File name without .toml
+    pub name: String,
+    pub dark: bool,
+    palette: Palette,
+}
+
+/// Parse a theme file
+pub fn parse_theme(id: &str, text: &str) -> Result<ThemeDef> {
+    let doc: DocumentMut = text.parse().with_context(|| format!("Invalid theme {}", id))?;
+    let colors = doc
+        .get("colors")
+        .and_then(|c| c.as_table_like())
+        .ok_or_else(|| anyhow!("Theme {}: missing [colors]", id))?;
+
+    if let Some((key, _)) = colors.iter().find(|(k, _)| !REQUIRED_KEYS.contains(k) && !OPTIONAL_KEYS.contains(k)) {
+        anyhow::bail!("Theme {}: unknown color '{}'", id, key);
+    }
+    let color = |key: &str| -> Result<Option<Color>> {
+        let Some(item) = colors.get(key) else {
+            return Ok(None);
+        };
+        let value = item.as_str().ok_or_else(|| anyhow!("Theme {}: {} must be a string", id, key))?;
+        let color = value
+            .parse::<Color>()
+            .map_err(|_| anyhow!("Theme {}: invalid color {} = \"{}\"", id, key, value))?;
+        Ok(Some(color))
+    };
+    let required = |key: &str| color(key)?.ok_or_else(|| anyhow!("Theme {}: missing color '{}'", id, key));
+
+    let (accent_primary, accent_secondary) = (required("accent_primary")?, required("accent_secondary")?);
+    let (text_primary, success, error) = (required("text_primary")?, required("success")?, required("error")?);
+    let palette = Palette {
+        bg_primary: required("bg_primary")?,
+        bg_secondary: required("bg_secondary")?,
+        text_primary,
+        text_secondary: required("text_secondary")?,
+        text_muted: required("text_muted")?,
+        accent_primary,
+        accent_secondary,
+        success,
+        warning: required("warning")?,
+        error,
+        info: color("info")?.unwrap_or(accent_secondary),
+        border: required("border")?,
+        border_focused: color("border_focused")?.unwrap_or(accent_primary),
+        selection_bg: required("selection_bg")?,
+        selection_fg: color("selection_fg")?.unwrap_or(text_primary),
+        highlight: required("highlight")?,
+        diff_added: color("diff_added")?.unwrap_or(success),
+        diff_removed: color("diff_removed")?.unwrap_or(error),
+        spinner: color("spinner")?.unwrap_or(accent_primary),
+        peach: required("peach")?,
+    };
+
+    Ok(ThemeDef {
+        id: id.to_string(),
+        name: doc.get("name").and_then(|v| v.as_str()).unwrap_or(id).to_string(),
+        dark: doc.get("dark").and_then(|v| v.as_bool()).unwrap_or(true),
+        palette,
+    })
+}
+
+pub fn themes_dir() -> Option<PathBuf> {
+    crate::config::config_path().and_then(|p| p.parent().map(|d| d.join("themes")))
+}
+
+/// Bundled themes, then user themes. Returns the problems with user theme files.
+pub fn available() -> (Vec<ThemeDef>, Vec<String>) {
+    let mut themes: Vec<ThemeDef> = BUNDLED
+        .iter()
+        .map(|(id, text)| parse_theme(id, text).expect("bundled theme"))
+        .collect();
+    let mut errors = Vec::new();
+
+    let mut files: Vec<PathBuf> = themes_dir()
+        .and_then(|dir| std::fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "toml"))
+        .collect();
+    files.sort();
+
+    for path in files {
+        let id = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default();
+        let theme = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|text| parse_theme(&id, &text));
+        match theme {
+            Ok(theme) => match themes.iter_mut().find(|t| t.id == theme.id) {
+                Some(existing) => *existing = theme,
+                None => themes.push(theme),
+            },
+            Err(e) => errors.push(format!("{:#}", e)),
         }
     }
+    (themes, errors)
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct Theme {
-    mode: ThemeMode,
+    themes: Vec<ThemeDef>,
+    current: usize,
+    support: ColorSupport,
 }
 
 impl Default for Theme {
     fn default() -> Self {
-        // Detect system theme preference
-        let mode = Self::detect_system_theme();
-        Self { mode }
+        Self::load(None).0
     }
 }
 
 impl Theme {
+    /// Theme `preferred` (an id), else a dark or light theme to match the terminal background.
+    /// Also returns the problems with user theme files.
+    pub fn load(preferred: Option<&str>) -> (Self, Vec<String>) {
+        let (themes, mut errors) = available();
+        let current = match preferred.and_then(|id| themes.iter().position(|t| t.id == id)) {
+            Some(idx) => idx,
+            None => {
+                if let Some(id) = preferred {
+                    errors.push(format!("Unknown theme '{}'", id));
+                }
+                // First theme matching the terminal: Mocha when dark, Latte when light
+                let dark = !Self::detect_light_terminal();
+                themes.iter().position(|t| t.dark == dark).unwrap_or(0)
+            }
+        };
+        (Self { themes, current, support: ColorSupport::detect() }, errors)
+    }
+
+    /// Bundled theme `id` in true color: no user theme files, no terminal queries
+    #[cfg(test)]
+    pub fn bundled(id: &str) -> Self {
+        let themes: Vec<ThemeDef> = BUNDLED.iter().map(|(id, text)| parse_theme(id, text).expect("bundled theme")).collect();
+        let current = themes.iter().position(|t| t.id == id).expect("bundled theme id");
+        Self { themes, current, support: ColorSupport::TrueColor }
+    }
+
     /// Detect system theme preference (light/dark)
-    fn detect_system_theme() -> ThemeMode {
-        match terminal_light::luma() {
-            Ok(luma) if luma > 0.6 => ThemeMode::Latte,  // Light terminal
-            _ => ThemeMode::Mocha,  // Dark terminal or unable to detect
-        }
+    fn detect_light_terminal() -> bool {
+        matches!(terminal_light::luma(), Ok(luma) if luma > 0.6)
+    }
+
+    pub fn id(&self) -> &str {
+        &self.themes[self.current].id
     }
 
-    pub fn mode(&self) -> ThemeMode {
-        self.mode
-    }
-
-    pub fn toggle(&mut self) {
-        self.mode = self.mode.toggle();
-    }
-
-    // Catppuccin Mocha base colors
-    const MOCHA_BASE: Color = Color::Rgb(30, 30, 46);      // #1e1e2e
-    const MOCHA_MANTLE: Color = Color::Rgb(24, 24, 37);    // #181825
-    const MOCHA_SURFACE0: Color = Color::Rgb(49, 50, 68);  // #313244
-    const MOCHA_SURFACE1: Color = Color::Rgb(69, 71, 90);  // #45475a
-    const MOCHA_OVERLAY0: Color = Color::Rgb(108, 112, 134); // #6c7086
-    const MOCHA_TEXT: Color = Color::Rgb(205, 214, 244);   // #cdd6f4
-    const MOCHA_SUBTEXT0: Color = Color::Rgb(166, 173, 200); // #a6adc8
-
-    // Catppuccin Mocha accent colors
-    const MOCHA_BLUE: Color = Color::Rgb(137, 180, 250);   // #89b4fa
-    const MOCHA_SAPPHIRE: Color = Color::Rgb(116, 199, 236); // #74c7ec
-    const MOCHA_GREEN: Color = Color::Rgb(166, 227, 161);  // #a6e3a1
-    const MOCHA_YELLOW: Color = Color::Rgb(249, 226, 175); // #f9e2af
-    const MOCHA_RED: Color = Color::Rgb(243, 139, 168);    // #f38ba8
-    const MOCHA_MAUVE: Color = Color::Rgb(203, 166, 247);  // #cba6f7
-    const MOCHA_PEACH: Color = Color::Rgb(250, 179, 135);  // #fab387
-
-    // Catppuccin Latte base colors
-    const LATTE_BASE: Color = Color::Rgb(239, 241, 245);   // #eff1f5
-    const LATTE_MANTLE: Color = Color::Rgb(230, 233, 239); // #e6e9ef
-    const LATTE_SURFACE0: Color = Color::Rgb(204, 208, 218); // #ccd0da
-    const LATTE_SURFACE1: Color = Color::Rgb(188, 192, 204); // #bcc0cc
-    const LATTE_OVERLAY0: Color = Color::Rgb(140, 143, 161); // #8c8fa1
-    const LATTE_TEXT: Color = Color::Rgb(76, 79, 105);     // #4c4f69
-    const LATTE_SUBTEXT0: Color = Color::Rgb(108, 111, 133); // #6c6f85
-
-    // Catppuccin Latte accent colors
-    const LATTE_BLUE: Color = Color::Rgb(30, 102, 245);    // #1e66f5
-    const LATTE_SAPPHIRE: Color = Color::Rgb(32, 159, 181); // #209fb5
-    const LATTE_GREEN: Color = Color::Rgb(64, 160, 43);    // #40a02b
-    const LATTE_YELLOW: Color = Color::Rgb(223, 142, 29);  // #df8e1d
-    const LATTE_RED: Color = Color::Rgb(210, 15, 57);      // #d20f39
-    const LATTE_MAUVE: Color = Color::Rgb(136, 57, 239);   // #8839ef
-    const LATTE_PEACH: Color = Color::Rgb(254, 100, 11);   // #fe640b
+    pub fn name(&self) -> &str {
+        &self.themes[self.current].name
+    }
+
+    /// Switch to the next theme
+    pub fn cycle(&mut self) {
+        self.current = (self.current + 1) % self.themes.len();
+    }
+
+    fn palette(&self) -> &Palette {
+        &self.themes[self.current].palette
+    }
+
+    /// Adapt a theme color to the terminal
+    fn color(&self, color: Color) -> Color {
+        match self.support {
+            ColorSupport::TrueColor => color,
+            ColorSupport::Ansi16 => to_ansi16(color),
+            ColorSupport::NoColor => Color::Reset,
+        }
+    }
 
     // Background colors
     pub fn bg_primary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_BASE,
-            ThemeMode::Latte => Self::LATTE_BASE,
+        // Limited terminals keep their own background
+        match self.support {
+            ColorSupport::TrueColor => self.palette().bg_primary,
+            _ => Color::Reset,
         }
     }
 
     pub fn bg_secondary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_MANTLE,
-            ThemeMode::Latte => Self::LATTE_MANTLE,
-        }
+        self.color(self.palette().bg_secondary)
     }
 
     // Text colors
     pub fn text_primary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_TEXT,
-            ThemeMode::Latte => Self::LATTE_TEXT,
+        match self.support {
+            ColorSupport::TrueColor => self.palette().text_primary,
+            _ => Color::Reset,
         }
     }
 
     pub fn text_secondary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_SUBTEXT0,
-            ThemeMode::Latte => Self::LATTE_SUBTEXT0,
-        }
+        self.color(self.palette().text_secondary)
     }
 
     pub fn text_muted(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_OVERLAY0,
-            ThemeMode::Latte => Self::LATTE_OVERLAY0,
-        }
+        self.color(self.palette().text_muted)
     }
 
     // Accent colors
     pub fn accent_primary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_BLUE,
-            ThemeMode::Latte => Self::LATTE_BLUE,
-        }
+        self.color(self.palette().accent_primary)
     }
 
     pub fn accent_secondary(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_SAPPHIRE,
-            ThemeMode::Latte => Self::LATTE_SAPPHIRE,
+        self.color(self.palette().accent_secondary)
+    }
+
+    /// Status colors keep their meaning on limited terminals whatever the theme's tint
+    fn role_color(&self, color: Color, ansi: Color) -> Color {
+        match self.support {
+            ColorSupport::TrueColor => color,
+            ColorSupport::Ansi16 => ansi,
+            ColorSupport::NoColor => Color::Reset,
         }
     }
 
     // Status colors
     pub fn success(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_GREEN,
-            ThemeMode::Latte => Self::LATTE_GREEN,
-        }
+        self.role_color(self.palette().success, Color::Green)
     }
 
     pub fn warning(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_YELLOW,
-            ThemeMode::Latte => Self::LATTE_YELLOW,
-        }
+        self.role_color(self.palette().warning, Color::Yellow)
     }
 
     pub fn error(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_RED,
-            ThemeMode::Latte => Self::LATTE_RED,
-        }
+        self.role_color(self.palette().error, Color::Red)
     }
 
     pub fn info(&self) -> Color {
-        self.accent_secondary()
+        self.color(self.palette().info)
     }
 
     // UI element colors
     pub fn border(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_SURFACE1,
-            ThemeMode::Latte => Self::LATTE_SURFACE1,
-        }
+        self.color(self.palette().border)
     }
 
     pub fn border_focused(&self) -> Color {
-        self.accent_primary()
+        self.color(self.palette().border_focused)
     }
 
     pub fn selection_bg(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_SURFACE0,
-            ThemeMode::Latte => Self::LATTE_SURFACE0,
-        }
+        self.color(self.palette().selection_bg)
     }
 
     pub fn selection_fg(&self) -> Color {
-        self.text_primary()
+        self.color(self.palette().selection_fg)
     }
 
     // Special colors
     pub fn highlight(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_MAUVE,
-            ThemeMode::Latte => Self::LATTE_MAUVE,
-        }
+        self.color(self.palette().highlight)
     }
 
     pub fn diff_added(&self) -> Color {
-        self.success()
+        self.role_color(self.palette().diff_added, Color::Green)
     }
 
     pub fn diff_removed(&self) -> Color {
-        self.error()
+        self.role_color(self.palette().diff_removed, Color::Red)
     }
 
     pub fn spinner(&self) -> Color {
-        self.accent_primary()
+        self.color(self.palette().spinner)
     }
 
     pub fn peach(&self) -> Color {
-        match self.mode {
-            ThemeMode::Mocha => Self::MOCHA_PEACH,
-            ThemeMode::Latte => Self::LATTE_PEACH,
+        self.color(self.palette().peach)
+    }
+}
+
+/// The 16 terminal colors in palette order
+const ANSI16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// One of the 16 terminal colors by hue and lightness. The terminal's palette decides
+/// the shade, so a pastel red stays red instead of becoming the nearest gray.
+fn to_ansi16(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(n) if n < 16 => return ANSI16[n as usize],
+        Color::Indexed(n) => indexed_rgb(n),
+        other => return other,
+    };
+
+    let (r, g, b) = (r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0);
+    let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+    let lightness = (max + min) / 2.0;
+    let chroma = max - min;
+
+    // Near-grays (backgrounds, text, borders) by lightness
+    if chroma < 0.18 {
+        return match lightness {
+            l if l < 0.2 => Color::Black,
+            l if l < 0.5 => Color::DarkGray,
+            l if l < 0.8 => Color::Gray,
+            _ => Color::White,
+        };
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / chroma).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / chroma + 2.0)
+    } else {
+        60.0 * ((r - g) / chroma + 4.0)
+    };
+    let (normal, light) = match hue {
+        h if !(15.0..330.0).contains(&h) => (Color::Red, Color::LightRed),
+        h if h < 70.0 => (Color::Yellow, Color::LightYellow),
+        h if h < 160.0 => (Color::Green, Color::LightGreen),
+        h if h < 200.0 => (Color::Cyan, Color::LightCyan),
+        h if h < 250.0 => (Color::Blue, Color::LightBlue),
+        _ => (Color::Magenta, Color::LightMagenta),
+    };
+    if lightness >= 0.5 { light } else { normal }
+}
+
+/// RGB of a 256-color palette entry (16..=255)
+fn indexed_rgb(n: u8) -> (u8, u8, u8) {
+    if n >= 232 {
+        let level = 8 + (n - 232) * 10;
+        return (level, level, level);
+    }
+    let n = n - 16;
+    let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+    (level(n / 36), level((n / 6) % 6), level(n % 6))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_files() {
+        for (id, text) in BUNDLED {
+            assert!(parse_theme(id, text).is_ok(), "{}", id);
+        }
+
+        let minimal: String = REQUIRED_KEYS.iter().map(|k| format!("{} = \"#102030\"\n", k)).collect();
+        let theme = parse_theme("mine", &format!("[colors]\n{}spinner = \"red\"", minimal)).unwrap();
+        assert_eq!(theme.palette.spinner, Color::Red);
+        assert_eq!(theme.palette.info, Color::Rgb(16, 32, 48));
+
+        let typo = parse_theme("typo", &format!("[colors]\n{}accent = \"red\"", minimal));
+        assert!(typo.unwrap_err().to_string().contains("unknown color 'accent'"));
+        assert!(parse_theme("bad", "[colors]\nbg_primary = \"#zz\"").is_err());
+    }
+
+    #[test]
+    fn test_ansi16_fallback() {
+        // Mocha red, green, yellow and blue keep their hue
+        assert_eq!(to_ansi16(Color::Rgb(243, 139, 168)), Color::LightRed);
+        assert_eq!(to_ansi16(Color::Rgb(166, 227, 161)), Color::LightGreen);
+        assert_eq!(to_ansi16(Color::Rgb(249, 226, 175)), Color::LightYellow);
+        assert_eq!(to_ansi16(Color::Rgb(137, 180, 250)), Color::LightBlue);
+        // Latte red is darker
+        assert_eq!(to_ansi16(Color::Rgb(210, 15, 57)), Color::Red);
+        // Mocha base and text
+        assert_eq!(to_ansi16(Color::Rgb(30, 30, 46)), Color::Black);
+        assert_eq!(to_ansi16(Color::Rgb(205, 214, 244)), Color::White);
+        assert_eq!(to_ansi16(Color::Indexed(196)), Color::LightRed);
+    }
+
+    #[test]
+    fn test_ansi16_status_colors_by_role() {
+        let mut theme = Theme { support: ColorSupport::Ansi16, ..Theme::bundled("mocha") };
+        for idx in 0..theme.themes.len() {
+            theme.current = idx;
+            assert_eq!(theme.error(), Color::Red, "{}", theme.id());
+            assert_eq!(theme.success(), Color::Green, "{}", theme.id());
+            assert_eq!(theme.warning(), Color::Yellow, "{}", theme.id());
         }
     }
 }
@@ -6,7 +6,6 @@ use crate::component::Component;
 pub enum TreeNode {
     Folder {
         name: String,
-        #[allow(dead_code)]
         path: String,
         expanded: bool,
         children: Vec<usize>, // Indices into TreeView.nodes
@@ -41,6 +40,14 @@ impl TreeNode {
     }
 }
 
+/// Expanded folders and the row under the cursor, carried over a rebuild
+#[derive(Clone, Debug, Default)]
+pub struct TreeState {
+    folders: HashMap<String, bool>, // Folder path -> expanded
+    cursor: Option<String>,         // Key of the node under the cursor
+    row: usize,
+}
+
 #[derive(Clone, Debug)]
 pub struct TreeView {
     pub nodes: Vec<TreeNode>,
@@ -320,6 +327,61 @@ impl TreeView {
         }
     }
 
+    /// Identity of a node that survives a rebuild: folder path or component name
+    fn node_key(&self, node_idx: usize, components: &[Component]) -> String {
+        match &self.nodes[node_idx] {
+            TreeNode::Folder { path, .. } => format!("{}/", path),
+            TreeNode::File { component_idx, .. } => components[*component_idx].name.clone(),
+        }
+    }
+
+    pub fn save_state(&self, components: &[Component]) -> TreeState {
+        let folders = self
+            .nodes
+            .iter()
+            .filter_map(|n| match n {
+                TreeNode::Folder { path, expanded, .. } => Some((path.clone(), *expanded)),
+                TreeNode::File { .. } => None,
+            })
+            .collect();
+        TreeState {
+            folders,
+            cursor: self.current_node_idx().map(|idx| self.node_key(idx, components)),
+            row: self.cursor,
+        }
+    }
+
+    /// Re-apply a saved state; new folders keep their default, a vanished cursor node keeps its row
+    pub fn restore_state(&mut self, state: &TreeState, components: &[Component]) {
+        for node in &mut self.nodes {
+            if let TreeNode::Folder { path, expanded, .. } = node
+                && let Some(was_expanded) = state.folders.get(path)
+            {
+                *expanded = *was_expanded;
+            }
+        }
+        self.rebuild_visible();
+
+        let row = (0..self.visible_indices.len())
+            .find(|&row| Some(self.node_key(self.visible_indices[row], components)) == state.cursor);
+        self.select(row.unwrap_or(state.row));
+    }
+
+    /// Move cursor to a visible row (clamped to the last row)
+    pub fn select(&mut self, row: usize) {
+        if !self.visible_indices.is_empty() {
+            self.cursor = row.min(self.visible_indices.len() - 1);
+        }
+    }
+
+    /// Depth of the node shown in a visible row
+    pub fn row_depth(&self, row: usize) -> usize {
+        self.visible_indices
+            .get(row)
+            .map(|&idx| self.nodes[idx].depth())
+            .unwrap_or(0)
+    }
+
     /// Get all component indices under a folder (recursive)
     pub fn get_folder_component_indices(&self, folder_idx: usize) -> Vec<usize> {
         let mut indices = Vec::new();
@@ -388,6 +450,39 @@ mod tests {
         assert!(!tree.visible_indices.is_empty());
     }
 
+    #[test]
+    fn test_restore_state() {
+        let mut components = vec![make_component("a/one.md"), make_component("b/two.md")];
+        let filtered: Vec<(usize, &Component)> = components.iter().enumerate().collect();
+        let mut tree = TreeView::build_from_components(&components, &filtered);
+        tree.select(0);
+        tree.toggle_expand(); // Collapse a/
+        tree.select(1); // b/
+        let state = tree.save_state(&components);
+
+        // A new file ahead of the cursor row
+        components.insert(0, make_component("0.md"));
+        let filtered: Vec<(usize, &Component)> = components.iter().enumerate().collect();
+        let mut rebuilt = TreeView::build_from_components(&components, &filtered);
+        rebuilt.restore_state(&state, &components);
+
+        assert_eq!(rebuilt.save_state(&components).cursor.as_deref(), Some("b/"));
+        assert_eq!(rebuilt.visible_indices.len(), 4); // 0.md, a/, b/, b/two.md
+    }
+
+    #[test]
+    fn test_select_row() {
+        let components = vec![make_component("folder/file1.md"), make_component("folder/file2.md")];
+        let filtered: Vec<(usize, &Component)> = components.iter().enumerate().collect();
+        let mut tree = TreeView::build_from_components(&components, &filtered);
+
+        tree.select(99);
+        assert_eq!(tree.cursor, tree.visible_indices.len() - 1);
+        tree.select(0);
+        assert!(tree.is_on_folder());
+        assert_eq!(tree.row_depth(tree.visible_indices.len() - 1), tree.row_depth(0) + 1);
+    }
+
     #[test]
     fn test_folder_collapse() {
         let components = vec![
@@ -0,0 +1,184 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::component::{format_size, estimate_tokens, Component, ComponentType};
+use crate::lint::LintLevel;
+use crate::tree::TreeNode;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let lines = match app.get_tree_view().and_then(|t| t.current_node_idx().map(|idx| (t, idx))) {
+        Some((tree, node_idx)) => match &tree.nodes[node_idx] {
+            TreeNode::Folder { name, .. } => {
+                let indices = tree.get_folder_component_indices(node_idx);
+                folder_lines(app, name, &indices)
+            }
+            TreeNode::File { component_idx, .. } => match app.components.get(*component_idx) {
+                Some(c) => component_lines(app, *component_idx, c),
+                None => Vec::new(),
+            },
+        },
+        None => vec![Line::from(Span::styled(
+            "Nothing selected",
+            Style::default().fg(app.theme.text_muted()),
+        ))],
+    };
+
+    let paragraph = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Details ")
+                .title_style(Style::default().fg(app.theme.text_primary()))
+                .style(Style::default().bg(app.theme.bg_primary())),
+        )
+        .wrap(Wrap { trim: false });
+
+    f.render_widget(paragraph, area);
+}
+
+fn field<'a>(app: &App, label: &'a str, value: String) -> Line<'a> {
+    Line::from(vec![
+        Span::styled(format!("{:<12}", label), Style::default().fg(app.theme.text_secondary())),
+        Span::styled(value, Style::default().fg(app.theme.text_primary())),
+    ])
+}
+
+fn component_lines(app: &App, idx: usize, c: &Component) -> Vec<Line<'static>> {
+    let detail = app.component_detail(idx);
+    let mut lines = Vec::new();
+
+    // Hooks: show hook.yaml instead of frontmatter (binary payload)
+    if let Some(config) = &c.hook_config {
+        lines.push(field(app, "Hook", config.name.clone()));
+        lines.push(field(app, "Event", config.event.clone()));
+        if let Some(timeout) = config.timeout {
+            lines.push(field(app, "Timeout", format!("{} s", timeout)));
+        }
+        if let Some(desc) = &config.description {
+            lines.push(field(app, "Description", desc.clone()));
+        }
+    }
+
+    if let Some(fm) = &detail.frontmatter {
+        if let Some(name) = &fm.name {
+            lines.push(Line::from(Span::styled(
+                name.clone(),
+                Style::default().fg(app.theme.accent_primary()).add_modifier(Modifier::BOLD),
+            )));
+        }
+        if let Some(desc) = &fm.description {
+            lines.push(Line::from(Span::styled(desc.clone(), Style::default().fg(app.theme.text_primary()))));
+        }
+        lines.push(Line::from(""));
+        if !fm.keywords.is_empty() {
+            lines.push(field(app, "Keywords", fm.keywords.join(", ")));
+        }
+        if !fm.tools.is_empty() {
+            lines.push(field(app, "Tools", fm.tools.join(", ")));
+        }
+        if let Some(model) = &fm.model {
+            lines.push(field(app, "Model", model.clone()));
+        }
+    }
+
+    let names = |indices: &[usize]| {
+        indices
+            .iter()
+            .filter_map(|&i| app.components.get(i))
+            .map(|c| c.display_name())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if !app.dep_graph.requires(idx).is_empty() {
+        lines.push(field(app, "Requires", names(app.dep_graph.requires(idx))));
+    }
+    if !app.dep_graph.required_by(idx).is_empty() {
+        lines.push(field(app, "Required by", names(app.dep_graph.required_by(idx))));
+    }
+
+    if !c.issues.is_empty() {
+        lines.push(Line::from(""));
+        for issue in &c.issues {
+            let color = match issue.level {
+                LintLevel::Error => app.theme.error(),
+                LintLevel::Warning => app.theme.warning(),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("{} {}", if issue.level == LintLevel::Error { "✗" } else { "⚠" }, issue.message),
+                Style::default().fg(color),
+            )));
+        }
+        lines.push(Line::from(""));
+    }
+
+    lines.push(field(app, "Path", c.display_name()));
+    if c.component_type == ComponentType::ConfigFile {
+        lines.push(field(app, "Strategy", c.config_file_def().strategy.display().to_string()));
+    }
+    let remote = app.layers.get(c.layer).and_then(|l| l.remote.as_ref());
+    if app.source_layers.len() > 1 || remote.is_some() {
+        let mut layer = app.layer_name(c.layer);
+        if !c.overrides.is_empty() {
+            let shadowed: Vec<String> = c.overrides.iter().map(|&l| app.layer_name(l)).collect();
+            layer = format!("{} (overrides {})", layer, shadowed.join(", "));
+        }
+        if let Some(remote) = remote {
+            layer = format!("{} · {}", layer, remote.summary());
+        }
+        lines.push(field(app, "Layer", layer));
+    }
+    let size = if detail.is_binary {
+        format!("{} (binary)", format_size(detail.size))
+    } else {
+        format!("{} · ~{} tokens", format_size(detail.size), detail.estimated_tokens)
+    };
+    lines.push(field(app, "Size", size));
+
+    if !detail.preview.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "── Preview ──",
+            Style::default().fg(app.theme.text_muted()),
+        )));
+        for line in detail.preview {
+            lines.push(Line::from(Span::styled(line, Style::default().fg(app.theme.text_secondary()))));
+        }
+    }
+
+    lines
+}
+
+fn folder_lines(app: &App, name: &str, indices: &[usize]) -> Vec<Line<'static>> {
+    let total_size: u64 = indices
+        .iter()
+        .filter_map(|&idx| app.components.get(idx))
+        .filter_map(|c| std::fs::metadata(&c.source_path).ok())
+        .map(|m| m.len())
+        .sum();
+    let selected = indices
+        .iter()
+        .filter(|&&idx| app.components.get(idx).map(|c| c.selected).unwrap_or(false))
+        .count();
+
+    vec![
+        Line::from(Span::styled(
+            format!("{}/", name),
+            Style::default().fg(app.theme.accent_primary()).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        field(app, "Files", indices.len().to_string()),
+        field(app, "Selected", selected.to_string()),
+        field(
+            app,
+            "Size",
+            format!("{} · ~{} tokens", format_size(total_size), estimate_tokens(total_size)),
+        ),
+    ]
+}
@@ -0,0 +1,105 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::doctor::Finding;
+use crate::lint::LintLevel;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .doctor_findings
+        .iter()
+        .map(|finding| {
+            let (icon, color) = match finding.level {
+                LintLevel::Error => ("✗", app.theme.error()),
+                LintLevel::Warning => ("⚠", app.theme.warning()),
+            };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", icon), Style::default().fg(color)),
+                Span::styled(format!("{:<14}", finding.area), Style::default().fg(app.theme.text_secondary())),
+                Span::styled(finding.message.clone(), Style::default().fg(app.theme.text_primary())),
+            ]))
+        })
+        .collect();
+
+    let title = format!(" Doctor · {} ({} problems) ", app.dest_dir.join("settings.json").display(), app.doctor_findings.len());
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.doctor_findings.is_empty() {
+        state.select(Some(app.doctor_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let mut lines = match app.doctor_findings.get(app.doctor_index) {
+        Some(finding) => finding_lines(app, finding),
+        None => vec![Line::from(Span::styled(
+            "No problems found",
+            Style::default().fg(app.theme.success()),
+        ))],
+    };
+
+    if !app.doctor_log.is_empty() {
+        lines.push(Line::from(""));
+        for msg in &app.doctor_log {
+            let color = if msg.starts_with("[ERR]") { app.theme.error() } else { app.theme.success() };
+            lines.push(Line::from(Span::styled(msg.clone(), Style::default().fg(color))));
+        }
+    }
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Fix ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
+
+fn finding_lines(app: &App, finding: &Finding) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), Style::default().fg(app.theme.text_secondary())),
+            Span::styled(value, Style::default().fg(app.theme.text_primary())),
+        ])
+    };
+
+    let fix = match &finding.fix {
+        Some(fix) => fix.describe(&app.components, &app.mcp_servers),
+        None => "No automatic fix".to_string(),
+    };
+
+    vec![
+        field("Level", finding.level.display().to_string()),
+        field("Area", finding.area.to_string()),
+        field("Problem", finding.message.clone()),
+        field("Fix", fix),
+    ]
+}
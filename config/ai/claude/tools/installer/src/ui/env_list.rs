@@ -0,0 +1,90 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::app::App;
+use crate::env_var::format_value;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let title = format!(" Env · {} ", app.dest_dir.join("settings.json").display());
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border()))
+        .title(title)
+        .title_style(Style::default().fg(app.theme.text_primary()));
+
+    if app.env_vars.is_empty() {
+        let message = "No env keys. Add an env block to settings.json or create env/env.yaml.";
+        let empty = List::new(vec![ListItem::new(Line::from(vec![Span::styled(
+            message,
+            Style::default().fg(app.theme.text_muted()),
+        )]))])
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .env_vars
+        .iter()
+        .enumerate()
+        .map(|(i, var)| {
+            let show = |value: &Option<serde_json::Value>| value.as_ref().map(format_value).unwrap_or_else(|| "-".to_string());
+
+            let local = match &app.env_edit {
+                Some(buffer) if i == app.env_index => Span::styled(
+                    format!("{}▏", buffer),
+                    Style::default().fg(app.theme.accent_primary()).add_modifier(Modifier::BOLD),
+                ),
+                _ => {
+                    let color = if var.differs() { app.theme.warning() } else { app.theme.text_primary() };
+                    Span::styled(format!("{:<16}", show(&var.local)), Style::default().fg(color))
+                }
+            };
+
+            // First line: name, local value, repo value, type, problems
+            let mut line1 = vec![
+                Span::styled(format!("{:<40}", var.name), Style::default().fg(app.theme.text_primary())),
+                Span::styled("local ", Style::default().fg(app.theme.text_muted())),
+                local,
+                Span::styled(" repo ", Style::default().fg(app.theme.text_muted())),
+                Span::styled(format!("{:<16}", show(&var.repo)), Style::default().fg(app.theme.text_secondary())),
+                Span::styled(format!(" [{}]", var.kind.display()), Style::default().fg(app.theme.accent_primary())),
+            ];
+            if let Some(error) = var.error() {
+                line1.push(Span::styled(format!(" ✗ {}", error), Style::default().fg(app.theme.error())));
+            }
+
+            // Second line: catalog description (indented)
+            let line2 = Line::from(vec![
+                Span::raw("    "),
+                Span::styled(
+                    var.description.clone().unwrap_or_else(|| "(not in catalog)".to_string()),
+                    Style::default().fg(app.theme.text_secondary()),
+                ),
+            ]);
+
+            ListItem::new(vec![Line::from(line1), line2])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.env_index));
+
+    f.render_stateful_widget(list, area, &mut state);
+    super::record_rows(app, area, state.offset(), 2);
+}
@@ -0,0 +1,66 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use crate::app::{App, View};
+use crate::keymap::{keymap_path, KeyView};
+
+/// Key bindings of the current view, drawn over the content area
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let Some(view) = KeyView::of(app.current_view) else {
+        return;
+    };
+
+    let mut lines: Vec<Line> = app
+        .keymap
+        .bindings(view)
+        .iter()
+        .filter(|(action, keys)| !keys.is_empty() && (app.current_view != View::List || action.applies_on(app.tab)))
+        .map(|(action, keys)| {
+            let keys = keys.iter().map(|k| k.display()).collect::<Vec<_>>().join(" / ");
+            Line::from(vec![
+                Span::styled(format!("  {:<18}", keys), Style::default().fg(app.theme.accent_primary()).add_modifier(Modifier::BOLD)),
+                Span::styled(format!("{:<38}", action.description()), Style::default().fg(app.theme.text_primary())),
+                Span::styled(action.name(), Style::default().fg(app.theme.text_muted())),
+            ])
+        })
+        .collect();
+
+    lines.push(Line::from(""));
+    let path = keymap_path().map(|p| p.display().to_string()).unwrap_or_else(|| "keymap.yaml".to_string());
+    lines.push(Line::from(Span::styled(
+        format!("  Rebind in {} (section '{}')  ·  any key closes", path, view.key()),
+        Style::default().fg(app.theme.text_muted()),
+    )));
+
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Fill(1), Constraint::Length(height), Constraint::Fill(1)])
+        .split(area);
+    let horizontal = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(10), Constraint::Percentage(80), Constraint::Percentage(10)])
+        .split(vertical[1]);
+
+    let title = match app.current_view {
+        View::List => format!(" Keys · {} ", app.tab.display_name()),
+        _ => format!(" Keys · {} ", view.display()),
+    };
+    let popup = Paragraph::new(lines)
+        .style(Style::default().bg(app.theme.bg_secondary()))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border_focused()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        );
+
+    f.render_widget(Clear, horizontal[1]);
+    f.render_widget(popup, horizontal[1]);
+}
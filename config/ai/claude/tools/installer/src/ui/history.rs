@@ -0,0 +1,119 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::history::{format_timestamp, Batch};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .history
+        .iter()
+        .map(|batch| {
+            let failed = batch.failed_count();
+            let mut spans = vec![
+                Span::styled(
+                    format!("{}  ", format_timestamp(batch.timestamp)),
+                    Style::default().fg(app.theme.text_secondary()),
+                ),
+                Span::styled(
+                    format!("{:<8}", batch.action),
+                    Style::default().fg(if batch.action == "remove" { app.theme.error() } else { app.theme.success() }),
+                ),
+                Span::styled(
+                    format!("{} items", batch.items.len()),
+                    Style::default().fg(app.theme.text_primary()),
+                ),
+            ];
+            if failed > 0 {
+                spans.push(Span::styled(format!("  {} failed", failed), Style::default().fg(app.theme.error())));
+            }
+            if batch.undone {
+                spans.push(Span::styled("  [undone]", Style::default().fg(app.theme.text_muted())));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(" History ({}) ", app.history.len());
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.history.is_empty() {
+        state.select(Some(app.history_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let lines = match app.history.get(app.history_index) {
+        Some(batch) => batch_lines(app, batch),
+        None => vec![Line::from(Span::styled(
+            "No batches recorded yet",
+            Style::default().fg(app.theme.text_muted()),
+        ))],
+    };
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Batch ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
+
+fn batch_lines(app: &App, batch: &Batch) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+
+    for item in &batch.items {
+        let (icon, color) = if item.ok { ("✓", app.theme.success()) } else { ("✗", app.theme.error()) };
+        lines.push(Line::from(Span::styled(format!("{} {}", icon, item.name), Style::default().fg(color))));
+    }
+
+    lines.push(Line::from(""));
+    let created = batch.files.iter().filter(|f| f.backup.is_none()).count();
+    lines.push(Line::from(Span::styled(
+        format!(
+            "Files: {} ({} backed up, {} new) · settings.json {}",
+            batch.files.len(),
+            batch.files.len() - created,
+            created,
+            if batch.settings_changed() { "changed" } else { "unchanged" }
+        ),
+        Style::default().fg(app.theme.text_secondary()),
+    )));
+
+    for command in &batch.commands {
+        lines.push(Line::from(Span::styled(
+            format!("$ {}", command),
+            Style::default().fg(app.theme.text_muted()),
+        )));
+    }
+
+    lines
+}
@@ -2,11 +2,11 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Gauge, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph},
     Frame,
 };
 
-use crate::app::App;
+use crate::app::{App, ItemState};
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     let chunks = Layout::default()
@@ -15,7 +15,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .constraints([
             Constraint::Length(3),  // Title
             Constraint::Length(3),  // Progress bar
-            Constraint::Min(0),     // Log
+            Constraint::Min(0),     // Items
+            Constraint::Length(8),  // Log
         ])
         .split(area);
 
@@ -60,9 +61,56 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .label(format!("{}/{}", progress, total));
     f.render_widget(gauge, chunks[1]);
 
-    // Log
+    // Items with state and duration
+    let items: Vec<ListItem> = app
+        .processing_items
+        .iter()
+        .map(|item| {
+            let (icon, color) = match item.state {
+                ItemState::Pending => ("·".to_string(), app.theme.text_muted()),
+                ItemState::Running => (super::get_spinner(app.animation_frame).to_string(), app.theme.accent_secondary()),
+                ItemState::Done => ("✓".to_string(), app.theme.success()),
+                ItemState::Failed => ("✗".to_string(), app.theme.error()),
+                ItemState::Cancelled => ("⊘".to_string(), app.theme.warning()),
+            };
+            let duration = item
+                .duration
+                .map(|d| format!("  {:.1}s", d.as_secs_f64()))
+                .unwrap_or_default();
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} {}", icon, item.name), Style::default().fg(color)),
+                Span::styled(duration, Style::default().fg(app.theme.text_muted())),
+            ]))
+        })
+        .collect();
+
+    let item_list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Items ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.processing_items.is_empty() {
+        state.select(Some(app.processing_cursor));
+    }
+    f.render_stateful_widget(item_list, chunks[2], &mut state);
+
+    // Log (most recent lines)
+    let log_height = chunks[3].height.saturating_sub(2) as usize;
+    let log_start = app.processing_log.len().saturating_sub(log_height);
     let log_items: Vec<ListItem> = app
-        .processing_log
+        .processing_log[log_start..]
         .iter()
         .map(|msg| {
             let style = if msg.starts_with("[OK]") {
@@ -86,5 +134,5 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                 .title(" Log ")
                 .title_style(Style::default().fg(app.theme.text_primary())),
         );
-    f.render_widget(log_list, chunks[2]);
+    f.render_widget(log_list, chunks[3]);
 }
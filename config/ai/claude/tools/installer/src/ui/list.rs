@@ -1,5 +1,5 @@
 use ratatui::{
-    layout::Rect,
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, List, ListItem, ListState},
@@ -10,10 +10,25 @@ use crate::app::{App, Tab};
 use crate::component::InstallStatus;
 use crate::tree::TreeNode;
 
+/// Minimum content width for showing the detail pane next to the tree
+const DETAIL_PANE_MIN_WIDTH: u16 = 100;
+
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
     // Use tree-based rendering for component tabs
     if let Some(tree) = app.get_tree_view() {
-        render_tree(f, app, tree, area);
+        if area.width >= DETAIL_PANE_MIN_WIDTH {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Percentage(60), // Tree
+                    Constraint::Percentage(40), // Detail pane
+                ])
+                .split(area);
+            render_tree(f, app, tree, columns[0]);
+            super::detail::render(f, app, columns[1]);
+        } else {
+            render_tree(f, app, tree, area);
+        }
     } else {
         // Fallback to flat list (shouldn't happen for component tabs)
         render_flat(f, app, area);
@@ -69,6 +84,7 @@ fn render_tree(f: &mut Frame, app: &App, tree: &crate::tree::TreeView, area: Rec
     }
 
     f.render_stateful_widget(list, area, &mut state);
+    super::record_rows(app, area, state.offset(), 1);
 }
 
 fn render_tree_node(app: &App, tree: &crate::tree::TreeView, node: &TreeNode, node_idx: usize) -> ListItem<'static> {
@@ -155,6 +171,29 @@ fn render_tree_node(app: &App, tree: &crate::tree::TreeView, node: &TreeNode, no
                 Span::styled(default_marker, Style::default().fg(app.theme.peach()).add_modifier(Modifier::BOLD)),
             ];
 
+            // Lint badge: errors take precedence over warnings
+            let errors = c.error_count();
+            let warnings = c.warning_count();
+            if errors > 0 {
+                spans.push(Span::styled(
+                    format!(" ✗{}", errors),
+                    Style::default().fg(app.theme.error()).add_modifier(Modifier::BOLD),
+                ));
+            } else if warnings > 0 {
+                spans.push(Span::styled(
+                    format!(" ⚠{}", warnings),
+                    Style::default().fg(app.theme.warning()),
+                ));
+            }
+
+            // Files from an overlay (the base layer is not tagged)
+            if c.layer > 0 {
+                spans.push(Span::styled(
+                    format!(" @{}", app.layer_name(c.layer)),
+                    Style::default().fg(app.theme.text_secondary()),
+                ));
+            }
+
             if app.tab == Tab::Hooks {
                 if let Some(ref config) = c.hook_config {
                     // Add event info
@@ -219,7 +258,7 @@ fn render_flat(f: &mut Frame, app: &App, area: Rect) {
                 _ => "",
             };
 
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::raw(format!("{} ", checkbox)),
                 Span::styled(
                     format!("{:<40}", c.name),
@@ -227,7 +266,14 @@ fn render_flat(f: &mut Frame, app: &App, area: Rect) {
                 ),
                 Span::styled(format!("({:^9})", c.status.display()), status_style),
                 Span::styled(default_marker, Style::default().fg(app.theme.peach()).add_modifier(Modifier::BOLD)),
-            ]);
+            ];
+            if c.layer > 0 {
+                spans.push(Span::styled(
+                    format!(" @{}", app.layer_name(c.layer)),
+                    Style::default().fg(app.theme.text_secondary()),
+                ));
+            }
+            let line = Line::from(spans);
 
             ListItem::new(line)
         })
@@ -53,7 +53,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(app.theme.text_primary()),
                 ),
                 Span::styled(
-                    format!("({:^13})", m.status.display()),
+                    // Status is asked from the CLI in the background the first time the tab is shown
+                    format!("({:^13})", if app.mcp_loaded { m.status.display() } else { "checking" }),
                     status_style,
                 ),
                 Span::styled(
@@ -101,4 +102,5 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
     state.select(Some(app.mcp_index));
 
     f.render_stateful_widget(list, area, &mut state);
+    super::record_rows(app, area, state.offset(), 2);
 }
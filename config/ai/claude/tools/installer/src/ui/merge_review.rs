@@ -0,0 +1,99 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::{App, ReviewItem};
+use crate::conflict::Resolution;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .review_items
+        .iter()
+        .map(|item| {
+            let color = match item.resolution {
+                Resolution::KeepLocal => app.theme.text_secondary(),
+                Resolution::TakeRepo => app.theme.accent_primary(),
+            };
+            let marker = if item.remembered { " " } else { "•" };
+            ListItem::new(Line::from(vec![
+                Span::styled(format!("{} ", marker), Style::default().fg(app.theme.warning())),
+                Span::styled(format!("{:<12}", item.resolution.display()), Style::default().fg(color)),
+                Span::styled(format!("{:<16}", item.file), Style::default().fg(app.theme.text_secondary())),
+                Span::styled(item.conflict.key.clone(), Style::default().fg(app.theme.text_primary())),
+            ]))
+        })
+        .collect();
+
+    let unreviewed = app.review_items.iter().filter(|i| !i.remembered).count();
+    let title = format!(" Merge review ({} conflicts, {} new) ", app.review_items.len(), unreviewed);
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.review_items.is_empty() {
+        state.select(Some(app.review_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let lines = app
+        .review_items
+        .get(app.review_index)
+        .map(|item| item_lines(app, item))
+        .unwrap_or_default();
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Conflict ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
+
+fn item_lines(app: &App, item: &ReviewItem) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), Style::default().fg(app.theme.text_secondary())),
+            Span::styled(value, Style::default().fg(app.theme.text_primary())),
+        ])
+    };
+
+    let choice = if item.remembered {
+        format!("{} (remembered)", item.resolution.display())
+    } else {
+        format!("{} (not reviewed yet)", item.resolution.display())
+    };
+
+    vec![
+        field("File", item.file.clone()),
+        field("Key", item.conflict.key.clone()),
+        field("Local", item.conflict.local.to_string()),
+        field("Repo", item.conflict.repo.to_string()),
+        field("Choice", choice),
+    ]
+}
@@ -2,14 +2,26 @@ mod tabs;
 pub mod list;
 mod mcp_list;
 mod plugin_list;
+mod permission_list;
+mod env_list;
 mod diff;
+mod detail;
 mod env_input;
 mod project_path;
 mod installing;
+mod history;
+mod snapshots;
+mod profiles;
+mod doctor;
+mod purge;
+mod merge_review;
 mod cli_selection;
+mod help;
+#[cfg(test)]
+mod tests;
 
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Position, Rect},
     Frame,
 };
 
@@ -22,6 +34,35 @@ pub fn get_spinner(frame: usize) -> &'static str {
     SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
 }
 
+/// Screen areas of the last frame, for mouse hit-testing
+#[derive(Clone, Debug, Default)]
+pub struct HitMap {
+    pub tabs: Vec<(Rect, usize)>, // Tab title and its position in the tab bar
+    pub rows: Option<Rows>,       // List in the content area
+}
+
+/// Rows of a rendered list
+#[derive(Clone, Copy, Debug)]
+pub struct Rows {
+    pub area: Rect,    // Inside the border
+    pub offset: usize, // First visible item
+    pub height: u16,   // Lines per item
+}
+
+impl Rows {
+    /// Item under a screen cell
+    pub fn item_at(&self, column: u16, row: u16) -> Option<usize> {
+        self.area
+            .contains(Position::new(column, row))
+            .then(|| self.offset + ((row - self.area.y) / self.height) as usize)
+    }
+}
+
+/// Remember where a list with `height` lines per item was drawn
+fn record_rows(app: &App, area: Rect, offset: usize, height: u16) {
+    app.hit_map.borrow_mut().rows = Some(Rows { area: area.inner(Margin::new(1, 1)), offset, height });
+}
+
 pub fn draw(f: &mut Frame, app: &App) {
     use ratatui::style::Style;
 
@@ -33,6 +74,8 @@ pub fn draw(f: &mut Frame, app: &App) {
         f.area()
     );
 
+    *app.hit_map.borrow_mut() = HitMap::default();
+
     // CLI selection screen takes full screen
     if app.current_view == View::CliSelection {
         cli_selection::render(f, app, f.area());
@@ -64,6 +107,10 @@ pub fn draw(f: &mut Frame, app: &App) {
                 mcp_list::render(f, app, chunks[1]);
             } else if app.tab == Tab::Plugins {
                 plugin_list::render(f, app, chunks[1]);
+            } else if app.tab == Tab::Permissions {
+                permission_list::render(f, app, chunks[1]);
+            } else if app.tab == Tab::Env {
+                env_list::render(f, app, chunks[1]);
             } else {
                 list::render(f, app, chunks[1]);
             }
@@ -84,9 +131,31 @@ pub fn draw(f: &mut Frame, app: &App) {
         View::Installing => {
             installing::render(f, app, chunks[1]);
         }
+        View::History => {
+            history::render(f, app, chunks[1]);
+        }
+        View::Snapshots => {
+            snapshots::render(f, app, chunks[1]);
+        }
+        View::Profiles => {
+            profiles::render(f, app, chunks[1]);
+        }
+        View::Doctor => {
+            doctor::render(f, app, chunks[1]);
+        }
+        View::Purge => {
+            purge::render(f, app, chunks[1]);
+        }
+        View::MergeReview => {
+            merge_review::render(f, app, chunks[1]);
+        }
     }
 
     render_status_bar(f, app, chunks[2]);
+
+    if app.show_help {
+        help::render(f, app, chunks[1]);
+    }
 }
 
 fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
@@ -96,30 +165,128 @@ fn render_status_bar(f: &mut Frame, app: &App, area: Rect) {
         widgets::{Block, Borders, Paragraph},
     };
 
+    use crate::keymap::{Action, KeyView};
+    use crate::keymap::Action::{
+        Apply, Browse, CancelItem, CancelPending, Close, CollapseFolder, Conflicts, Create, CycleScope, CycleTheme,
+        Confirm, Diff, Doctor, Down, EnvEdit, EnvReset, EnvUnset, ExpandFolder, Fix, FixAll, Help, History, Install,
+        KeepLocal, KeepLocalAll, Load, NextTab, PrevTab, Profiles, Quit, Remove, Restore, Retry, Save, SetDefault,
+        Snapshots, TakeRepo, TakeRepoAll, Toggle, Undo, UnsetDefault, Up, AddToCatalog,
+    };
+
+    let keymap = &app.keymap;
+    let list = |tab_hints: &[(&[Action], &str)]| {
+        let common: &[(&[Action], &str)] = &[
+            (&[Profiles], "Profiles"),
+            (&[History], "History"),
+            (&[Snapshots], "Snapshots"),
+            (&[Doctor], "Doctor"),
+            (&[CycleTheme], "Theme"),
+            (&[NextTab, PrevTab], "Switch"),
+            (&[Help], "Help"),
+            (&[Quit], "Quit"),
+        ];
+        [keymap.hints(KeyView::List, tab_hints), keymap.hints(KeyView::List, common)].join("  ")
+    };
+
     let help_text = match app.current_view {
-        View::CliSelection => "[1/2] Select  [q] Quit",
-        View::Loading => "Loading...  [q] Quit",
+        View::CliSelection => "[1/2] Select  [q] Quit".to_string(),
+        View::Loading => "Loading...  [q] Quit".to_string(),
         View::List => {
             if app.tab == Tab::McpServers {
-                "[Space] Toggle  [i] Install  [r] Remove  [o] Scope  [t] Theme  [Tab/1-0,-] Switch  [q] Quit"
+                list(&[(&[Toggle], "Toggle"), (&[Install], "Install"), (&[Remove], "Remove"), (&[CycleScope], "Scope")])
+            } else if app.tab == Tab::Plugins && app.plugin_browse {
+                list(&[
+                    (&[Toggle], "Toggle"),
+                    (&[Install], "Install"),
+                    (&[Remove], "Remove"),
+                    (&[AddToCatalog], "Add to plugins.yaml"),
+                    (&[Browse], "Back"),
+                ])
             } else if app.tab == Tab::Plugins {
-                "[Space] Toggle  [i] Install  [r] Remove  [t] Theme  [Tab/1-0,-] Switch  [q] Quit"
+                list(&[(&[Toggle], "Toggle"), (&[Install], "Install"), (&[Remove], "Remove"), (&[Browse], "Browse")])
             } else if app.tab == Tab::OutputStyles || app.tab == Tab::Statusline {
-                "[Space] Toggle  [i] Install  [r] Remove  [d] Diff  [s] Set [u] Unset  [t] Theme  [Tab/1-0,-] Switch  [q] Quit"
+                list(&[
+                    (&[Toggle], "Toggle"),
+                    (&[Install], "Install"),
+                    (&[Remove], "Remove"),
+                    (&[Diff], "Diff"),
+                    (&[SetDefault], "Set"),
+                    (&[UnsetDefault], "Unset"),
+                ])
+            } else if app.tab == Tab::Env && app.env_edit.is_some() {
+                "[Enter] Save  [Esc] Cancel  [Backspace] Delete".to_string()
+            } else if app.tab == Tab::Env {
+                list(&[(&[EnvEdit], "Edit"), (&[EnvReset], "Reset to repo"), (&[EnvUnset], "Unset")])
+            } else if app.tab == Tab::Permissions {
+                list(&[(&[Toggle], "Toggle"), (&[Install], "Add"), (&[Remove], "Remove"), (&[CycleScope], "Settings file")])
+            } else if app.tab == Tab::Config {
+                list(&[
+                    (&[Toggle], "Toggle"),
+                    (&[Install], "Install"),
+                    (&[Remove], "Remove"),
+                    (&[Diff], "Diff"),
+                    (&[Conflicts], "Conflicts"),
+                ])
+            } else {
+                list(&[
+                    (&[Toggle], "Toggle"),
+                    (&[Install], "Install"),
+                    (&[Remove], "Remove"),
+                    (&[Diff], "Diff"),
+                    (&[CollapseFolder, ExpandFolder], "Folder"),
+                ])
+            }
+        }
+        View::Diff => keymap.hints(KeyView::Diff, &[(&[Down, Up], "Scroll"), (&[Help], "Help"), (&[Close], "Close")]),
+        View::History => keymap.hints(
+            KeyView::History,
+            &[(&[Down, Up], "Move"), (&[Undo], "Undo"), (&[Help], "Help"), (&[Close], "Close")],
+        ),
+        View::Snapshots => keymap.hints(
+            KeyView::Snapshots,
+            &[(&[Down, Up], "Move"), (&[Create], "Create"), (&[Restore], "Restore"), (&[Help], "Help"), (&[Close], "Close")],
+        ),
+        View::Doctor => keymap.hints(
+            KeyView::Doctor,
+            &[(&[Down, Up], "Move"), (&[Fix], "Fix"), (&[FixAll], "Fix all"), (&[Help], "Help"), (&[Close], "Close")],
+        ),
+        View::Purge if app.purge_plan.as_ref().is_some_and(|plan| !plan.is_empty()) => keymap.hints(
+            KeyView::Purge,
+            &[(&[Down, Up], "Move"), (&[Confirm], "Purge"), (&[Help], "Help"), (&[Close], "Cancel")],
+        ),
+        View::Purge => keymap.hints(KeyView::Purge, &[(&[Down, Up], "Move"), (&[Help], "Help"), (&[Close], "Close")]),
+        View::MergeReview => keymap.hints(
+            KeyView::MergeReview,
+            &[
+                (&[Down, Up], "Move"),
+                (&[Toggle], "Toggle"),
+                (&[KeepLocal], "Keep local"),
+                (&[TakeRepo], "Take repo"),
+                (&[KeepLocalAll, TakeRepoAll], "All"),
+                (&[Apply], "Apply"),
+                (&[Close], "Cancel"),
+            ],
+        ),
+        View::Profiles => {
+            if app.profile_name_input.is_some() {
+                "[Enter] Save  [Esc] Cancel  [Backspace] Delete".to_string()
             } else {
-                "[Space] Toggle  [i] Install  [r] Remove  [d] Diff  [h/l/←/→] Folder  [t] Theme  [Tab/1-0,-] Switch  [q] Quit"
+                keymap.hints(
+                    KeyView::Profiles,
+                    &[(&[Down, Up], "Move"), (&[Load], "Load"), (&[Save], "Save selection"), (&[Help], "Help"), (&[Close], "Close")],
+                )
             }
         }
-        View::Diff => "[j/k/↑/↓] Scroll  [q/Esc] Close",
-        View::EnvInput => "[Enter] Submit  [Esc] Cancel  [Backspace] Delete",
-        View::ProjectPath => "[Enter] Confirm  [Esc] Cancel  [Backspace] Delete",
+        View::EnvInput => "[Enter] Submit  [Esc] Cancel  [Backspace] Delete".to_string(),
+        View::ProjectPath => "[Enter] Confirm  [Esc] Cancel  [Backspace] Delete".to_string(),
         View::Installing => {
             if app.processing_complete {
-                "[Enter/q] Close"
-            } else if app.is_removing {
-                "Removing..."
+                keymap.hints(KeyView::Installing, &[(&[Close], "Close"), (&[Retry], "Retry failed"), (&[Up, Down], "Move")])
             } else {
-                "Installing..."
+                keymap.hints(
+                    KeyView::Installing,
+                    &[(&[CancelPending], "Cancel all"), (&[CancelItem], "Cancel item"), (&[Up, Down], "Move")],
+                )
             }
         }
     };
@@ -0,0 +1,111 @@
+use ratatui::{
+    layout::Rect,
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState},
+    Frame,
+};
+
+use crate::app::App;
+use crate::permission::RuleKind;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let mode = match &app.default_mode {
+        Some((mode, scope)) => format!("{} ({})", mode, scope.display()),
+        None => "default".to_string(),
+    };
+    let title = format!(
+        " Permissions · writes to {} · defaultMode: {} ",
+        app.permission_scope_path().display(),
+        mode
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(app.theme.border()))
+        .title(title)
+        .title_style(Style::default().fg(app.theme.text_primary()));
+
+    if app.permissions.is_empty() {
+        let message = "No permission rules. Create permissions/permissions.yaml to add a catalog.";
+        let empty = List::new(vec![ListItem::new(Line::from(vec![Span::styled(
+            message,
+            Style::default().fg(app.theme.text_muted()),
+        )]))])
+        .block(block);
+        f.render_widget(empty, area);
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .permissions
+        .iter()
+        .map(|r| {
+            let in_scope = r.scopes.contains(&app.permission_scope);
+            let checkbox = if r.selected {
+                "[x]"
+            } else if in_scope {
+                "[*]"
+            } else {
+                "[ ]"
+            };
+
+            let kind_color = match r.def.kind {
+                RuleKind::Deny => app.theme.error(),
+                RuleKind::Ask => app.theme.warning(),
+                RuleKind::Allow => app.theme.success(),
+                RuleKind::Directory => app.theme.accent_primary(),
+            };
+            let scopes = if r.scopes.is_empty() {
+                "not set".to_string()
+            } else {
+                r.scopes.iter().map(|s| s.display()).collect::<Vec<_>>().join(", ")
+            };
+
+            // First line: checkbox, kind, rule, where it is set, problems
+            let mut line1 = vec![
+                Span::raw(format!("{} ", checkbox)),
+                Span::styled(format!("{:<6}", r.def.kind.display()), Style::default().fg(kind_color)),
+                Span::styled(format!("{:<40}", r.def.rule), Style::default().fg(app.theme.text_primary())),
+                Span::styled(format!(" [{}]", scopes), Style::default().fg(app.theme.text_secondary())),
+            ];
+            if let Some(kind) = r.shadowed {
+                line1.push(Span::styled(
+                    format!(" overridden by {}", kind.display()),
+                    Style::default().fg(app.theme.warning()),
+                ));
+            }
+            if let Some(error) = &r.error {
+                line1.push(Span::styled(format!(" ✗ {}", error), Style::default().fg(app.theme.error())));
+            }
+
+            // Second line: catalog description (indented)
+            let description = match (&r.def.description, r.in_catalog) {
+                (Some(d), _) => d.clone(),
+                (None, true) => "(catalog)".to_string(),
+                (None, false) => "(not in catalog)".to_string(),
+            };
+            let line2 = Line::from(vec![
+                Span::raw("    "),
+                Span::styled(description, Style::default().fg(app.theme.text_secondary())),
+            ]);
+
+            ListItem::new(vec![Line::from(line1), line2])
+        })
+        .collect();
+
+    let list = List::new(items)
+        .block(block)
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    state.select(Some(app.permission_index));
+
+    f.render_stateful_widget(list, area, &mut state);
+    super::record_rows(app, area, state.offset(), 2);
+}
@@ -10,24 +10,36 @@ use crate::app::App;
 use crate::plugin::PluginStatus;
 
 pub fn render(f: &mut Frame, app: &App, area: Rect) {
-    if app.plugins.is_empty() {
+    let plugins = app.active_plugins();
+    let title = if app.plugin_browse {
+        " Plugins [Browse marketplaces] "
+    } else {
+        " Plugins "
+    };
+
+    if plugins.is_empty() {
+        let message = if app.plugin_browse {
+            "No plugins found in cloned marketplaces."
+        } else {
+            "No plugins found. Create plugins/plugins.yaml to add plugins."
+        };
+
         let empty = List::new(vec![ListItem::new(Line::from(vec![Span::styled(
-            "No plugins found. Create plugins/plugins.yaml to add plugins.",
+            message,
             Style::default().fg(app.theme.text_muted()),
         )]))])
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.border()))
-                .title(" Plugins ")
+                .title(title)
                 .title_style(Style::default().fg(app.theme.text_primary())),
         );
         f.render_widget(empty, area);
         return;
     }
 
-    let items: Vec<ListItem> = app
-        .plugins
+    let items: Vec<ListItem> = plugins
         .iter()
         .map(|p| {
             let checkbox = if p.selected {
@@ -44,26 +56,40 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             };
 
             // First line: checkbox, name, status
-            let line1 = Line::from(vec![
+            let mut line1_spans = vec![
                 Span::raw(format!("{} ", checkbox)),
                 Span::styled(
                     format!("{:<24}", p.def.name),
                     Style::default().fg(app.theme.text_primary()),
                 ),
                 Span::styled(
-                    format!("({:^13})", p.status.display()),
+                    format!("({:^13})", if app.plugins_loaded || app.plugin_browse { p.status.display() } else { "checking" }),
                     status_style,
                 ),
-            ]);
+            ];
 
-            // Second line: repo and comment (indented)
+            // In browse mode, mark plugins already listed in plugins.yaml
+            if app.plugin_browse && app.plugins.iter().any(|c| c.def.id() == p.def.id()) {
+                line1_spans.push(Span::styled(
+                    " [plugins.yaml]",
+                    Style::default().fg(app.theme.highlight()),
+                ));
+            }
+
+            let line1 = Line::from(line1_spans);
+
+            // Second line: marketplace, repo and comment (indented)
             let short_repo = p.short_repo();
             let comment = p.def.comment.as_deref().unwrap_or("");
 
             let mut line2_spans = vec![
                 Span::raw("    "),
                 Span::styled(
-                    short_repo.clone(),
+                    format!("@{}", p.def.marketplace),
+                    Style::default().fg(app.theme.accent_primary()),
+                ),
+                Span::styled(
+                    format!(" ({})", short_repo),
                     Style::default().fg(app.theme.accent_secondary()),
                 ),
             ];
@@ -86,7 +112,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(Style::default().fg(app.theme.border()))
-                .title(" Plugins ")
+                .title(title)
                 .title_style(Style::default().fg(app.theme.text_primary())),
         )
         .highlight_style(
@@ -98,7 +124,8 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         .highlight_symbol("> ");
 
     let mut state = ListState::default();
-    state.select(Some(app.plugin_index));
+    state.select(Some(app.active_plugin_index()));
 
     f.render_stateful_widget(list, area, &mut state);
+    super::record_rows(app, area, state.offset(), 2);
 }
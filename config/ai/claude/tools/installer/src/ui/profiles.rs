@@ -0,0 +1,118 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::profile::{self, Profile};
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .profiles
+        .iter()
+        .map(|entry| {
+            let mut spans = vec![Span::styled(entry.name.clone(), Style::default().fg(app.theme.text_primary()))];
+            match &entry.result {
+                Ok(p) => {
+                    if let Some(desc) = &p.description {
+                        spans.push(Span::styled(format!("  {}", desc), Style::default().fg(app.theme.text_muted())));
+                    }
+                }
+                Err(_) => spans.push(Span::styled("  ✗ invalid", Style::default().fg(app.theme.error()))),
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = match &app.profile_name_input {
+        Some(input) => format!(" Save selection as: {}█ ", input),
+        None => format!(" Profiles ({}) ", app.profiles.len()),
+    };
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.profiles.is_empty() {
+        state.select(Some(app.profile_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let lines = match app.profiles.get(app.profile_index).map(|e| &e.result) {
+        Some(Ok(p)) => profile_lines(app, p),
+        Some(Err(e)) => vec![Line::from(Span::styled(e.clone(), Style::default().fg(app.theme.error())))],
+        None => vec![Line::from(Span::styled(
+            format!("No profiles in {} · press s to save the current selection", profile::profiles_dir(&app.source_dir).display()),
+            Style::default().fg(app.theme.text_muted()),
+        ))],
+    };
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Profile ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
+
+fn profile_lines(app: &App, p: &Profile) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), Style::default().fg(app.theme.text_secondary())),
+            Span::styled(value, Style::default().fg(app.theme.text_primary())),
+        ])
+    };
+    let join = |items: Vec<String>| if items.is_empty() { "-".to_string() } else { items.join(", ") };
+
+    let mcp: Vec<String> = p
+        .mcp_servers
+        .iter()
+        .map(|m| format!("{} ({})", m.name(), m.scope().display()))
+        .collect();
+
+    // Preview of `installer profile apply` against the current state
+    let plan = profile::plan(p, &app.components, &app.dep_graph, &app.mcp_servers, &app.mcp_registrations(), &app.plugins);
+    let installs = plan.install_components.len() + plan.install_mcp.len() + plan.install_plugins.len();
+    let removals = plan.remove_components.len() + plan.remove_mcp.len() + plan.misplaced_mcp.len() + plan.remove_plugins.len();
+
+    let mut lines = vec![
+        field("Components", join(p.components.clone())),
+        field("MCP", join(mcp)),
+        field("Plugins", join(p.plugins.clone())),
+        Line::from(""),
+        field("Apply", format!("{} to install, {} to remove", installs, removals)),
+    ];
+
+    if !plan.unknown.is_empty() {
+        lines.push(Line::from(Span::styled(
+            format!("⚠ Not found: {}", plan.unknown.join(", ")),
+            Style::default().fg(app.theme.warning()),
+        )));
+    }
+
+    lines
+}
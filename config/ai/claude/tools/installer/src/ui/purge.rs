@@ -0,0 +1,91 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
+        .split(area);
+
+    let Some(plan) = &app.purge_plan else {
+        return;
+    };
+    let steps = plan.steps.iter().map(|step| {
+        ListItem::new(Line::from(vec![
+            Span::styled("✗ ", Style::default().fg(app.theme.error())),
+            Span::styled(
+                step.describe(&app.components, &app.mcp_servers, &app.plugins),
+                Style::default().fg(app.theme.text_primary()),
+            ),
+        ]))
+    });
+    let kept = plan.kept.iter().map(|&i| {
+        ListItem::new(Line::from(vec![
+            Span::styled("• ", Style::default().fg(app.theme.warning())),
+            Span::styled(
+                format!("keep {} (edited locally)", app.components[i].display_name()),
+                Style::default().fg(app.theme.text_secondary()),
+            ),
+        ]))
+    });
+    let items: Vec<ListItem> = steps.chain(kept).collect();
+    let title = format!(" Purge · {} items ", plan.steps.len());
+
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if app.purge_rows() > 0 {
+        state.select(Some(app.purge_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    let lines = if plan.is_empty() {
+        vec![Line::from(Span::styled(
+            "Nothing the installer manages is installed",
+            Style::default().fg(app.theme.success()),
+        ))]
+    } else {
+        vec![
+            Line::from(Span::styled(
+                "Everything above is removed from the live config. MCP servers are removed from every scope.",
+                Style::default().fg(app.theme.warning()),
+            )),
+            Line::from(Span::styled(
+                "Files the installer did not put there are left alone. The current state is saved as a snapshot first.",
+                Style::default().fg(app.theme.text_secondary()),
+            )),
+        ]
+    };
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Confirm ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
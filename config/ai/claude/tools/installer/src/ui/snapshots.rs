@@ -0,0 +1,112 @@
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    Frame,
+};
+
+use crate::app::App;
+use crate::history::format_timestamp;
+use crate::snapshot::Snapshot;
+
+pub fn render(f: &mut Frame, app: &App, area: Rect) {
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(area);
+
+    let items: Vec<ListItem> = app
+        .snapshots
+        .iter()
+        .map(|snapshot| {
+            let mut spans = vec![
+                Span::styled(
+                    format!("{}  ", format_timestamp(snapshot.timestamp)),
+                    Style::default().fg(app.theme.text_secondary()),
+                ),
+                Span::styled(
+                    format!("{} files · {} MCP · {} plugins", snapshot.files.len(), snapshot.mcp_servers.len(), snapshot.plugins.len()),
+                    Style::default().fg(app.theme.text_primary()),
+                ),
+            ];
+            if let Some(label) = &snapshot.label {
+                spans.push(Span::styled(format!("  [{}]", label), Style::default().fg(app.theme.text_muted())));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let title = format!(" Snapshots ({}) ", app.snapshots.len());
+    let list = List::new(items)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(title)
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .highlight_style(
+            Style::default()
+                .bg(app.theme.selection_bg())
+                .fg(app.theme.selection_fg())
+                .add_modifier(Modifier::BOLD),
+        )
+        .highlight_symbol("> ");
+
+    let mut state = ListState::default();
+    if !app.snapshots.is_empty() {
+        state.select(Some(app.snapshot_index));
+    }
+    f.render_stateful_widget(list, chunks[0], &mut state);
+
+    // Restore output takes precedence over snapshot details
+    let lines = if !app.snapshot_log.is_empty() {
+        app.snapshot_log
+            .iter()
+            .map(|msg| {
+                let color = if msg.starts_with("[ERR]") { app.theme.error() } else { app.theme.success() };
+                Line::from(Span::styled(msg.clone(), Style::default().fg(color)))
+            })
+            .collect()
+    } else {
+        match app.snapshots.get(app.snapshot_index) {
+            Some(snapshot) => snapshot_lines(app, snapshot),
+            None => vec![Line::from(Span::styled(
+                "No snapshots yet · press c to create one",
+                Style::default().fg(app.theme.text_muted()),
+            ))],
+        }
+    };
+
+    let detail = Paragraph::new(lines)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(app.theme.border()))
+                .title(" Snapshot ")
+                .title_style(Style::default().fg(app.theme.text_primary())),
+        )
+        .wrap(Wrap { trim: false });
+    f.render_widget(detail, chunks[1]);
+}
+
+fn snapshot_lines(app: &App, snapshot: &Snapshot) -> Vec<Line<'static>> {
+    let field = |label: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<12}", label), Style::default().fg(app.theme.text_secondary())),
+            Span::styled(value, Style::default().fg(app.theme.text_primary())),
+        ])
+    };
+
+    let join = |items: &[String]| if items.is_empty() { "-".to_string() } else { items.join(", ") };
+
+    vec![
+        field("Id", snapshot.id.clone()),
+        field("Target", snapshot.target.clone()),
+        field("Dirs", snapshot.dirs.join(", ")),
+        field("Files", snapshot.files.len().to_string()),
+        field("MCP", join(&snapshot.mcp_servers.iter().map(crate::snapshot::registration_label).collect::<Vec<_>>())),
+        field("Plugins", join(&snapshot.plugins)),
+    ]
+}
@@ -18,7 +18,7 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
 
     // Dynamic title based on selected CLI
     let title = if let Some(cli) = app.target_cli {
-        format!(" {} Config Installer ({}) ", cli.display_name(), app.theme.mode().name())
+        format!(" {} Config Installer ({}) ", cli.display_name(), app.theme.name())
     } else {
         String::from(" Config Installer ")
     };
@@ -46,4 +46,14 @@ pub fn render(f: &mut Frame, app: &App, area: Rect) {
         );
 
     f.render_widget(tabs, area);
+
+    // Each title is drawn with one space of padding on both sides and a divider after it
+    let mut x = area.x + 1;
+    let mut hits = Vec::new();
+    for (i, tab) in app.available_tabs.iter().enumerate() {
+        let width = format!("{}:{}", i + 1, tab.display_name()).chars().count() as u16 + 2;
+        hits.push((Rect::new(x, area.y + 1, width, 1).intersection(area), i));
+        x = x.saturating_add(width + 1);
+    }
+    app.hit_map.borrow_mut().tabs = hits;
 }
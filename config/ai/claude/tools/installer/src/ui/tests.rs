@@ -0,0 +1,324 @@
+//! Snapshot tests: each view rendered into a `TestBackend` from fixture directories
+//!
+//! Keys go through the same input handlers as the terminal loop. Snapshots are plain text
+//! under `src/ui/snapshots/`; run with `UPDATE_SNAPSHOTS=1` to rewrite them after an
+//! intended change and review the diff. Mouse clicks are checked against the hit map
+//! of the last rendered frame.
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui::{backend::TestBackend, layout::Rect, Terminal};
+
+use crate::app::{App, Tab, TargetCli, View};
+use crate::config::SourceLayer;
+use crate::keymap::Keymap;
+use crate::theme::Theme;
+
+const SIZE: (u16, u16) = (100, 30);
+const SMALL: (u16, u16) = (80, 24);
+
+/// Fixture sources and destination. Relative to the package root (the test cwd) so
+/// rendered paths are the same on every machine.
+fn fixture(name: &str) -> PathBuf {
+    let root = Path::new("target").join("ui-fixtures").join(name);
+    let _ = std::fs::remove_dir_all(&root);
+    let write = |path: &str, content: &str| {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    };
+
+    write("source/agents/reviewer.md", "---\nname: reviewer\ndescription: Reviews diffs\n---\n\nReview the change.\n");
+    write("source/agents/team/planner.md", "---\nname: planner\ndescription: Plans work\n---\n\nPlan first.\n");
+    write("source/commands/commit.md", "---\ndescription: Write a commit\n---\n\nCommit staged changes.\n");
+    write(
+        "source/mcps/mcps.yaml",
+        "servers:\n  - name: docs\n    description: \"Documentation search\"\n    command: \"npx -y docs-mcp\"\n    category: docs\n  - name: tracker\n    description: \"Issue tracker\"\n    command: \"npx -y tracker-mcp\"\n    category: project\n    env:\n      - INSTALLER_UI_TEST_TOKEN\n",
+    );
+    write(
+        "source/plugins/plugins.yaml",
+        "marketplaces:\n  tools:\n    source: https://example.com/tools.git\n    plugins:\n      - name: formatter\n        description: Formats code\n",
+    );
+    // Installed but edited locally
+    write("dest/.claude/agents/reviewer.md", "---\nname: reviewer\ndescription: Reviews diffs\n---\n\nReview it my way.\n");
+    root
+}
+
+/// App loaded for Claude from the fixture, on the Agents tab
+fn loaded_app(name: &str) -> App {
+    let root = fixture(name);
+    let mut app = cli_app(&root);
+    app.select_cli(TargetCli::Claude).unwrap();
+    app.dest_dir = root.join("dest/.claude");
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    crate::start_loading_thread(&app, &tx);
+    let (components, mcp_servers, plugins) = rx.recv().unwrap().unwrap();
+    app.finish_loading(components, mcp_servers, plugins);
+    app.mcp_project_path = "/work/project".to_string();
+    app
+}
+
+fn cli_app(root: &Path) -> App {
+    let layers = vec![SourceLayer { name: "base".to_string(), path: root.join("source"), remote: None }];
+    let theme = Theme::bundled("mocha");
+    let mut app = App::with_layers(layers, root.join("dest/.claude"), theme, Keymap::default());
+    app.scan_cache = Some(root.join("scan-cache.json"));
+    app
+}
+
+/// Dispatch a key the way the terminal loop does for the current view
+fn press(app: &mut App, code: KeyCode) {
+    let modifiers = KeyModifiers::NONE;
+    match app.current_view {
+        View::List => crate::handle_list_input(app, code, modifiers).unwrap(),
+        View::Diff => crate::handle_diff_input(app, code, modifiers).unwrap(),
+        View::EnvInput => crate::handle_env_input(app, code).unwrap(),
+        View::ProjectPath => crate::handle_project_path_input(app, code),
+        View::Installing => crate::handle_installing_input(app, code, modifiers).unwrap(),
+        View::Purge => crate::handle_purge_input(app, code, modifiers).unwrap(),
+        view => panic!("no key handling for {:?} in these tests", view),
+    }
+}
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        press(app, KeyCode::Char(c));
+    }
+}
+
+fn render(app: &App, (width, height): (u16, u16)) -> String {
+    let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+    terminal.draw(|f| super::draw(f, app)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let mut out = String::new();
+    for y in 0..buffer.area.height {
+        let row: String = (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect();
+        out.push_str(row.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// Compare against the stored snapshot (or store it with UPDATE_SNAPSHOTS=1)
+fn assert_snapshot(name: &str, app: &App) {
+    assert_snapshot_at(name, app, SIZE);
+}
+
+fn assert_snapshot_at(name: &str, app: &App, size: (u16, u16)) {
+    let actual = render(app, size);
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/ui/snapshots").join(format!("{}.txt", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {}; run with UPDATE_SNAPSHOTS=1", path.display()));
+    assert!(actual == expected, "snapshot {} changed:\n{}", name, actual);
+}
+
+#[test]
+fn test_cli_selection_and_loading() {
+    let root = fixture("cli");
+    let mut app = cli_app(&root);
+    assert_snapshot("cli_selection", &app);
+    assert_snapshot_at("cli_selection_small", &app, SMALL);
+
+    app.select_cli(TargetCli::Claude).unwrap();
+    assert_eq!(app.current_view, View::Loading);
+    assert_snapshot("loading", &app);
+}
+
+#[test]
+fn test_list_navigation_and_toggle() {
+    let mut app = loaded_app("list");
+    assert_eq!(app.current_view, View::List);
+    assert_snapshot("list", &app);
+    assert_snapshot_at("list_small", &app, SMALL);
+
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    assert_snapshot("list_toggled", &app);
+
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.tab, Tab::Commands);
+    assert_snapshot("list_commands", &app);
+}
+
+#[test]
+fn test_diff_view() {
+    let mut app = loaded_app("diff");
+    let reviewer = app.components.iter().position(|c| c.name == "reviewer.md");
+    for _ in 0..5 {
+        if app.selected_component_index() == reviewer {
+            break;
+        }
+        press(&mut app, KeyCode::Down);
+    }
+    press(&mut app, KeyCode::Char('d'));
+    assert_eq!(app.current_view, View::Diff);
+    assert_snapshot("diff", &app);
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_view, View::List);
+}
+
+#[test]
+fn test_mcp_env_and_project_path_input() {
+    let mut app = loaded_app("mcp");
+    while app.tab != Tab::McpServers {
+        press(&mut app, KeyCode::Tab);
+    }
+    app.mcp_loaded = true;
+
+    // The tracker server needs a token that is not set
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Char(' '));
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.current_view, View::EnvInput);
+    type_text(&mut app, "secret");
+    assert_snapshot("env_input", &app);
+
+    press(&mut app, KeyCode::Esc);
+    press(&mut app, KeyCode::Char('o'));
+    assert_eq!(app.current_view, View::ProjectPath);
+    type_text(&mut app, "/api");
+    assert_snapshot("project_path", &app);
+
+    press(&mut app, KeyCode::Enter);
+    assert_eq!(app.mcp_project_path, "/work/project/api");
+}
+
+#[test]
+fn test_installing_view() {
+    let mut app = loaded_app("installing");
+    press(&mut app, KeyCode::Char('i'));
+    assert_eq!(app.current_view, View::Installing);
+    assert_snapshot("installing", &app);
+
+    // Nothing has run yet: the cursor moves, closing waits for the batch
+    press(&mut app, KeyCode::Down);
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_view, View::Installing);
+}
+
+#[test]
+fn test_purge_confirmation() {
+    let mut app = loaded_app("purge");
+    (app.mcp_loaded, app.plugins_loaded) = (true, true);
+    // As if the command had been installed as is
+    let commit = app.components.iter_mut().find(|c| c.name == "commit.md").unwrap();
+    commit.status = crate::component::InstallStatus::Unchanged;
+
+    press(&mut app, KeyCode::Char('X'));
+    assert_eq!(app.current_view, View::Purge);
+    assert_snapshot("purge", &app);
+
+    press(&mut app, KeyCode::Esc);
+    assert_eq!(app.current_view, View::List);
+    assert!(app.purge_plan.is_none());
+}
+
+#[test]
+fn test_purge_runs_in_the_installing_view() {
+    let mut app = loaded_app("purge-run");
+    (app.mcp_loaded, app.plugins_loaded) = (true, true);
+    let commit = app.components.iter_mut().find(|c| c.name == "commit.md").unwrap();
+    commit.status = crate::component::InstallStatus::Unchanged;
+    let installed = commit.dest_path.clone();
+    std::fs::create_dir_all(installed.parent().unwrap()).unwrap();
+    std::fs::copy(&commit.source_path, &installed).unwrap();
+    let choices = crate::conflict::choices_path(&app.dest_dir);
+    std::fs::create_dir_all(choices.parent().unwrap()).unwrap();
+    std::fs::write(&choices, "{}").unwrap();
+
+    press(&mut app, KeyCode::Char('X'));
+    press(&mut app, KeyCode::Char('y'));
+    assert_eq!(app.current_view, View::Installing);
+    assert_eq!(app.processing_items.len(), 1);
+    assert!(installed.exists(), "nothing runs on the UI thread");
+
+    // What the terminal loop's workers do
+    let batch = app.purge_batch.clone().unwrap();
+    let slot = app.next_pending_item().unwrap();
+    let message = batch.run_step(app.processing_items[slot].idx);
+    app.finish_process_item(slot, message, std::time::Duration::ZERO);
+    app.start_finish_processing();
+
+    assert!(!installed.exists());
+    assert!(!choices.exists());
+    assert!(app.processing_log.iter().any(|l| l.starts_with("[OK] remove .installer/merge-choices.json")), "{:?}", app.processing_log);
+}
+
+/// Left click at a screen cell, through the terminal loop's mouse handler
+fn click(app: &mut App, column: u16, row: u16) {
+    // Clear the last click so two clicks on one cell don't count as a double click
+    app.last_click = None;
+    let mouse = MouseEvent { kind: MouseEventKind::Down(MouseButton::Left), column, row, modifiers: KeyModifiers::NONE };
+    crate::handle_mouse_input(app, mouse).unwrap();
+}
+
+fn is_selected(app: &App, name: &str) -> bool {
+    app.components.iter().find(|c| c.name == name).unwrap().selected
+}
+
+#[test]
+fn test_rows_item_at() {
+    // Two lines per item, scrolled down by ten items
+    let rows = super::Rows { area: Rect::new(1, 4, 20, 6), offset: 10, height: 2 };
+    assert_eq!(rows.item_at(1, 4), Some(10));
+    assert_eq!(rows.item_at(20, 5), Some(10));
+    assert_eq!(rows.item_at(5, 6), Some(11));
+    assert_eq!(rows.item_at(5, 9), Some(12));
+
+    // Border rows and columns, and cells past the list
+    assert_eq!(rows.item_at(5, 3), None);
+    assert_eq!(rows.item_at(5, 10), None);
+    assert_eq!(rows.item_at(0, 4), None);
+    assert_eq!(rows.item_at(21, 4), None);
+}
+
+#[test]
+fn test_mouse_tabs_and_checkboxes() {
+    let mut app = loaded_app("mouse");
+    render(&app, SIZE);
+
+    // "│ 1:Agents │ 2:Commands │": the divider belongs to neither tab
+    click(&mut app, 11, 1);
+    assert_eq!(app.tab, Tab::Agents);
+    click(&mut app, 0, 1);
+    assert_eq!(app.tab, Tab::Agents);
+    click(&mut app, 14, 1);
+    assert_eq!(app.tab, Tab::Commands);
+    render(&app, SIZE);
+    click(&mut app, 2, 1);
+    assert_eq!(app.tab, Tab::Agents);
+    render(&app, SIZE);
+
+    // The list border is not a row
+    let reviewer = app.components.iter().position(|c| c.name == "reviewer.md");
+    click(&mut app, 15, 6);
+    click(&mut app, 15, 3);
+    assert_eq!(app.selected_component_index(), reviewer);
+
+    // planner.md is indented under team/: its name selects, its checkbox toggles
+    assert!(is_selected(&app, "team/planner.md"));
+    click(&mut app, 15, 5);
+    assert_eq!(app.selected_component_index(), app.components.iter().position(|c| c.name == "team/planner.md"));
+    assert!(is_selected(&app, "team/planner.md"));
+    click(&mut app, 3, 5);
+    assert!(is_selected(&app, "team/planner.md"));
+    click(&mut app, 5, 5);
+    assert!(!is_selected(&app, "team/planner.md"));
+    click(&mut app, 7, 5);
+    assert!(is_selected(&app, "team/planner.md"));
+
+    // reviewer.md sits at the top level, so its checkbox starts two columns further left
+    assert!(is_selected(&app, "reviewer.md"));
+    click(&mut app, 3, 6);
+    assert!(!is_selected(&app, "reviewer.md"));
+}
@@ -0,0 +1,201 @@
+//! Live refresh: watch the source layers, the destination dir and `~/.claude.json`
+//!
+//! Events are debounced and sorted into the scans they invalidate, so an edited agent does
+//! not re-run `claude mcp list`. In the destination only the directories the installer writes
+//! (agents/, commands/, ...) are watched recursively; the top level (settings.json,
+//! config.toml) and plugins/ are watched without their subdirectories, so Claude's own churn
+//! (projects/, todos/, plugin checkouts) never reaches the watcher.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use anyhow::Result;
+use notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde_json::Value;
+
+/// Quiet period before a burst of changes triggers a refresh
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Scans to re-run
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub components: bool,
+    pub mcp: bool,
+    pub plugins: bool,
+}
+
+impl Changes {
+    pub fn any(&self) -> bool {
+        self.components || self.mcp || self.plugins
+    }
+
+    pub fn merge(&mut self, other: Changes) {
+        self.components |= other.components;
+        self.mcp |= other.mcp;
+        self.plugins |= other.plugins;
+    }
+}
+
+pub struct Watcher {
+    debouncer: Debouncer<RecommendedWatcher>,
+    events: Receiver<DebounceEventResult>,
+    sources: Vec<PathBuf>,
+    dest_dir: PathBuf,
+    managed: HashSet<String>, // Top-level names under dest_dir the installer writes
+    watched: HashSet<String>, // Managed dirs with their own watch
+    claude_json: Option<PathBuf>,
+    mcp_fingerprint: Option<String>,
+}
+
+impl Watcher {
+    /// `managed` are the destination paths of the scanned components
+    pub fn start(sources: &[PathBuf], dest_dir: &Path, managed: &[PathBuf]) -> Result<Self> {
+        let (tx, events) = mpsc::channel();
+        let mut debouncer = new_debouncer(DEBOUNCE, tx)?;
+
+        for source in sources {
+            debouncer.watcher().watch(source, RecursiveMode::Recursive)?;
+        }
+        // ~/.claude.json is replaced on write, so watch its directory
+        let claude_json = dirs::home_dir().map(|h| h.join(".claude.json"));
+        if let Some(home) = claude_json.as_ref().and_then(|p| p.parent()) {
+            debouncer.watcher().watch(home, RecursiveMode::NonRecursive)?;
+        }
+
+        let mut names: HashSet<String> = ["settings.json", "plugins", "config.toml"].iter().map(|s| s.to_string()).collect();
+        names.extend(managed.iter().filter_map(|p| top_level(p, dest_dir)));
+
+        let mut watcher = Self {
+            debouncer,
+            events,
+            sources: sources.to_vec(),
+            dest_dir: dest_dir.to_path_buf(),
+            managed: names,
+            watched: HashSet::new(),
+            mcp_fingerprint: claude_json.as_deref().and_then(mcp_fingerprint),
+            claude_json,
+        };
+        if dest_dir.exists() {
+            // Top-level files are often replaced on write, so they are watched through the dir
+            watcher.debouncer.watcher().watch(dest_dir, RecursiveMode::NonRecursive)?;
+            let names: Vec<String> = watcher.managed.iter().cloned().collect();
+            for name in names {
+                watcher.watch_managed(&name)?;
+            }
+        }
+        Ok(watcher)
+    }
+
+    /// Start watching a managed directory below dest_dir (no-op for files and missing dirs)
+    fn watch_managed(&mut self, name: &str) -> Result<()> {
+        let path = self.dest_dir.join(name);
+        if !path.is_dir() || self.watched.contains(name) {
+            return Ok(());
+        }
+        // plugins/ holds Claude's marketplace checkouts; only its JSON indexes matter
+        let mode = if name == "plugins" { RecursiveMode::NonRecursive } else { RecursiveMode::Recursive };
+        self.debouncer.watcher().watch(&path, mode)?;
+        self.watched.insert(name.to_string());
+        Ok(())
+    }
+
+    /// Changes seen since the last call (non-blocking)
+    pub fn changes(&mut self) -> Changes {
+        let mut changes = Changes::default();
+        while let Ok(result) = self.events.try_recv() {
+            let Ok(events) = result else {
+                continue;
+            };
+            for event in events {
+                // A managed dir created after startup (the first install of an agent, ...)
+                if event.path.parent() == Some(self.dest_dir.as_path())
+                    && let Some(name) = top_level(&event.path, &self.dest_dir)
+                    && self.managed.contains(&name)
+                {
+                    let _ = self.watch_managed(&name);
+                }
+                changes.merge(self.classify(&event.path));
+            }
+        }
+        changes
+    }
+
+    fn classify(&mut self, path: &Path) -> Changes {
+        if self.claude_json.as_deref() == Some(path) {
+            // Claude rewrites this file constantly; only its MCP sections matter
+            let fingerprint = mcp_fingerprint(path);
+            let changed = fingerprint != self.mcp_fingerprint;
+            self.mcp_fingerprint = fingerprint;
+            return Changes { mcp: changed, ..Default::default() };
+        }
+        classify(path, &self.sources, &self.dest_dir, &self.managed)
+    }
+}
+
+/// Scans affected by a changed path in a source layer or the destination dir
+fn classify(path: &Path, sources: &[PathBuf], dest_dir: &Path, managed: &HashSet<String>) -> Changes {
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+
+    if let Some(source) = sources.iter().find(|s| path.starts_with(s)) {
+        if path.strip_prefix(source).is_ok_and(|rel| rel.starts_with(".git")) {
+            return Changes::default();
+        }
+        return match file_name {
+            "mcps.yaml" => Changes { mcp: true, ..Default::default() },
+            "plugins.yaml" => Changes { plugins: true, ..Default::default() },
+            _ => Changes { components: true, ..Default::default() },
+        };
+    }
+
+    match top_level(path, dest_dir) {
+        Some(name) if name == "plugins" => Changes { plugins: true, ..Default::default() },
+        // Codex keeps MCP servers next to the managed settings
+        Some(name) if name == "config.toml" => Changes { components: true, mcp: true, ..Default::default() },
+        Some(name) if managed.contains(&name) => Changes { components: true, ..Default::default() },
+        _ => Changes::default(),
+    }
+}
+
+/// First path component below `dir`
+fn top_level(path: &Path, dir: &Path) -> Option<String> {
+    path.strip_prefix(dir)
+        .ok()?
+        .components()
+        .next()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+}
+
+/// The MCP server sections of ~/.claude.json (user scope and per project)
+fn mcp_fingerprint(path: &Path) -> Option<String> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let json: Value = serde_json::from_str(&content).ok()?;
+    let projects: Vec<(&String, &Value)> = json
+        .get("projects")
+        .and_then(|p| p.as_object())
+        .map(|p| p.iter().filter_map(|(dir, project)| Some((dir, project.get("mcpServers")?))).collect())
+        .unwrap_or_default();
+    Some(format!("{:?}|{:?}", json.get("mcpServers"), projects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let sources = vec![PathBuf::from("/src/claude")];
+        let dest = Path::new("/home/u/.claude");
+        let managed: HashSet<String> = ["agents".to_string(), "settings.json".to_string()].into();
+        let at = |p: &str| classify(Path::new(p), &sources, dest, &managed);
+
+        assert!(at("/src/claude/agents/a.md").components);
+        assert_eq!(at("/src/claude/mcps/mcps.yaml"), Changes { mcp: true, ..Default::default() });
+        assert!(!at("/src/claude/.git/index").any());
+        assert!(at("/home/u/.claude/agents/a.md").components);
+        assert!(at("/home/u/.claude/plugins/installed.json").plugins);
+        assert!(!at("/home/u/.claude/projects/x/session.jsonl").any());
+    }
+}
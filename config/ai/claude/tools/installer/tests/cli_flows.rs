@@ -0,0 +1,252 @@
+//! End-to-end install and remove flows against the `cli-stub` binary (no real claude or
+//! codex CLI). The stub is only built with `cargo test --features test-support`.
+//!
+//! Each test runs `installer profile apply` (or `purge`) from a fixture source dir with HOME
+//! pointed at a scratch directory, then checks the argv the stub recorded.
+
+use std::path::PathBuf;
+use std::process::Output;
+
+struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("installer-flows-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        let fixture = Self { root };
+
+        fixture.write("source/agents/reviewer.md", "---\nname: reviewer\ndescription: Reviews\n---\n\nReview.\n");
+        fixture.write("source/settings.json", "{}\n");
+        fixture.write(
+            "source/mcps/mcps.yaml",
+            "servers:\n  - name: docs\n    description: Docs\n    command: \"npx -y docs-mcp\"\n    category: docs\n",
+        );
+        fixture.write(
+            "source/plugins/plugins.yaml",
+            "marketplaces:\n  tools:\n    source: https://example.com/tools.git\n    plugins:\n      - name: formatter\n",
+        );
+        fixture.write("source/profiles/full.yaml", "mcp_servers:\n  - docs\nplugins:\n  - formatter@tools\n");
+        fixture.write("source/profiles/empty.yaml", "description: Nothing\n");
+        fixture.write("source/profiles/servers.yaml", "mcp_servers:\n  - docs\n");
+        std::fs::create_dir_all(fixture.root.join("home")).unwrap();
+        std::fs::create_dir_all(fixture.root.join("stub")).unwrap();
+        fixture
+    }
+
+    fn write(&self, path: &str, content: &str) {
+        let path = self.root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// Canned stub output: (argv prefix, stdout, stderr, exit code)
+    fn respond(&self, responses: &[(&str, &str, &str, i32)]) {
+        let list: Vec<serde_json::Value> = responses
+            .iter()
+            .map(|(args, stdout, stderr, code)| {
+                serde_json::json!({"args": args, "stdout": stdout, "stderr": stderr, "code": code})
+            })
+            .collect();
+        self.write("stub/responses.json", &serde_json::to_string(&list).unwrap());
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        let home = self.root.join("home");
+        std::process::Command::new(env!("CARGO_BIN_EXE_installer"))
+            .args(args)
+            .current_dir(self.root.join("source"))
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_CACHE_HOME", home.join(".cache"))
+            .env_remove("INSTALLER_LAYERS")
+            .env("INSTALLER_CLAUDE_BIN", env!("CARGO_BIN_EXE_cli-stub"))
+            .env("INSTALLER_CODEX_BIN", env!("CARGO_BIN_EXE_cli-stub"))
+            .env("CLI_STUB_DIR", self.root.join("stub"))
+            .output()
+            .unwrap()
+    }
+
+    /// Argv of every stub call, in order
+    fn calls(&self) -> Vec<Vec<String>> {
+        std::fs::read_to_string(self.root.join("stub/calls.jsonl"))
+            .unwrap_or_default()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    fn called(&self, argv: &[&str]) -> bool {
+        self.calls().iter().any(|call| call == argv)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn test_profile_apply_installs_mcp_and_plugin() {
+    let fixture = Fixture::new("install");
+    let output = fixture.run(&["profile", "apply", "full"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    assert!(fixture.called(&["mcp", "list"]));
+    assert!(fixture.called(&["mcp", "add", "--scope", "user", "docs", "--", "npx", "-y", "docs-mcp"]));
+    // The marketplace is not registered yet, so it is added before the install
+    let calls = fixture.calls();
+    let add = calls.iter().position(|c| c == &["plugin", "marketplace", "add", "https://example.com/tools.git"]);
+    let install = calls.iter().position(|c| c == &["plugin", "install", "formatter@tools"]);
+    assert!(add.is_some() && add < install, "{:?}", calls);
+}
+
+#[test]
+fn test_known_marketplace_is_not_added_again() {
+    let fixture = Fixture::new("marketplace");
+    fixture.respond(&[("plugin marketplace list", "Configured marketplaces:\n\n  ❯ tools\n    Source: URL (https://example.com/tools.git)\n", "", 0)]);
+    let output = fixture.run(&["profile", "apply", "full"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    assert!(fixture.called(&["plugin", "install", "formatter@tools"]));
+    assert!(!fixture.calls().iter().any(|c| c.starts_with(&["plugin".to_string(), "marketplace".to_string(), "add".to_string()])));
+}
+
+#[test]
+fn test_marketplace_without_source_is_not_added() {
+    let fixture = Fixture::new("no-source");
+    fixture.write("source/plugins/plugins.yaml", "marketplaces:\n  tools:\n    source: \"\"\n    plugins:\n      - name: formatter\n");
+    let output = fixture.run(&["profile", "apply", "full"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Marketplace tools is not registered and has no source"), "{}", stdout(&output));
+    assert!(!fixture.calls().iter().any(|c| c.get(1).is_some_and(|a| a == "marketplace") && c.get(2).is_some_and(|a| a == "add")));
+}
+
+#[test]
+fn test_cli_failure_reports_stderr() {
+    let fixture = Fixture::new("failure");
+    fixture.respond(&[("mcp add", "", "docs: already exists\n", 1)]);
+    let output = fixture.run(&["profile", "apply", "full"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let out = stdout(&output);
+    assert!(out.contains("[ERR] install mcp docs: Failed to install MCP server docs: docs: already exists"), "{}", out);
+    // Other steps still run
+    assert!(out.contains("[OK] install plugin formatter@tools"), "{}", out);
+}
+
+#[test]
+fn test_profile_apply_removes_what_is_not_wanted() {
+    let fixture = Fixture::new("remove");
+    fixture.respond(&[("mcp list", "Checking MCP server health...\n\ndocs: npx -y docs-mcp - ✓ Connected\n", "", 0)]);
+    fixture.write("home/.claude/settings.json", r#"{"enabledPlugins": {"formatter@tools": true}}"#);
+    let output = fixture.run(&["profile", "apply", "empty"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    assert!(fixture.called(&["mcp", "remove", "docs"]));
+    assert!(fixture.called(&["plugin", "uninstall", "formatter@tools"]));
+    assert!(!fixture.calls().iter().any(|c| c.get(1).is_some_and(|a| a == "add" || a == "install")));
+}
+
+#[test]
+fn test_dry_run_only_lists_installed_state() {
+    let fixture = Fixture::new("dry-run");
+    let output = fixture.run(&["profile", "apply", "full", "--dry-run"]);
+    assert_eq!(output.status.code(), Some(0));
+
+    assert!(stdout(&output).contains("would install mcp docs"));
+    assert_eq!(fixture.calls(), vec![vec!["mcp".to_string(), "list".to_string()]]);
+}
+
+#[test]
+fn test_codex_profile_apply_installs_mcp() {
+    let fixture = Fixture::new("codex-install");
+    let output = fixture.run(&["profile", "apply", "servers", "--target", "codex"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    // Codex takes no --scope and has no plugins
+    assert!(fixture.called(&["mcp", "list"]));
+    assert!(fixture.called(&["mcp", "add", "docs", "--", "npx", "-y", "docs-mcp"]));
+    assert!(!fixture.calls().iter().any(|c| c.first().is_some_and(|a| a == "plugin")), "{:?}", fixture.calls());
+}
+
+#[test]
+fn test_codex_profile_apply_removes_what_is_not_wanted() {
+    let fixture = Fixture::new("codex-remove");
+    fixture.respond(&[("mcp list", "Name  Command  Args\ndocs  npx      -y docs-mcp\n", "", 0)]);
+    let output = fixture.run(&["profile", "apply", "empty", "--target", "codex"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    assert!(fixture.called(&["mcp", "remove", "docs"]));
+    assert!(!fixture.calls().iter().any(|c| c.get(1).is_some_and(|a| a == "add")));
+}
+
+#[test]
+fn test_unknown_target_is_rejected() {
+    let fixture = Fixture::new("bad-target");
+    let output = fixture.run(&["profile", "apply", "servers", "--target", "gemini"]);
+    assert_ne!(output.status.code(), Some(0));
+    assert!(fixture.calls().is_empty());
+}
+
+#[test]
+fn test_purge_removes_managed_state_and_keeps_user_files() {
+    let fixture = Fixture::new("purge");
+    let agent = "---\nname: reviewer\ndescription: Reviews\n---\n\nReview.\n";
+    fixture.write("home/.claude/agents/reviewer.md", agent);
+    fixture.write("source/agents/planner.md", "---\nname: planner\ndescription: Plans\n---\n\nPlan.\n");
+    fixture.write("home/.claude/agents/planner.md", "---\nname: planner\ndescription: Plans\n---\n\nMy way.\n");
+    fixture.write("home/.claude/agents/mine.md", "---\nname: mine\ndescription: Mine\n---\n");
+    fixture.write("home/.claude/settings.json", r#"{"enabledPlugins": {"formatter@tools": true}}"#);
+    let project = fixture.root.join("work");
+    std::fs::create_dir_all(&project).unwrap();
+    let claude_json = serde_json::json!({
+        "mcpServers": {"docs": {"command": "npx"}, "mine": {"command": "mine"}},
+        "projects": {project.display().to_string(): {"mcpServers": {"docs": {"command": "npx"}}}},
+    });
+    fixture.write("home/.claude.json", &claude_json.to_string());
+    fixture.write("home/.claude/.installer/merge-choices.json", "{}");
+
+    let output = fixture.run(&["purge", "--yes"]);
+    let out = stdout(&output);
+    assert_eq!(output.status.code(), Some(0), "{}", out);
+
+    assert!(fixture.called(&["mcp", "remove", "--scope", "user", "docs"]));
+    assert!(fixture.called(&["mcp", "remove", "--scope", "local", "docs"]));
+    assert!(fixture.called(&["plugin", "uninstall", "formatter@tools"]));
+    assert!(!fixture.calls().iter().any(|c| c.contains(&"mine".to_string())));
+
+    // Servers and plugins go first, merged config files last (same phases as the TUI)
+    let position = |line: &str| out.find(line).unwrap_or_else(|| panic!("{} missing:\n{}", line, out));
+    assert!(position("[OK] uninstall plugin formatter@tools") < position("[OK] remove agents/reviewer.md"));
+    assert!(position("[OK] remove agents/reviewer.md") < position("[OK] take managed entries out of config/settings.json"));
+
+    let agents = fixture.root.join("home/.claude/agents");
+    assert!(!agents.join("reviewer.md").exists());
+    assert!(agents.join("planner.md").exists() && out.contains("keep agents/planner.md (edited locally)"), "{}", out);
+    assert!(agents.join("mine.md").exists());
+    assert!(!fixture.root.join("home/.claude/.installer/merge-choices.json").exists());
+}
+
+#[test]
+fn test_codex_purge_removes_catalog_servers() {
+    let fixture = Fixture::new("codex-purge");
+    fixture.write(
+        "home/.codex/config.toml",
+        "[mcp_servers.docs]\ncommand = \"npx\"\nargs = [\"-y\", \"docs-mcp\"]\n\n[mcp_servers.mine]\ncommand = \"mine\"\n",
+    );
+
+    let output = fixture.run(&["purge", "--yes", "--target", "codex"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stdout(&output));
+
+    assert!(fixture.called(&["mcp", "remove", "docs"]));
+    assert!(!fixture.calls().iter().any(|c| c.contains(&"mine".to_string()) || c.contains(&"--scope".to_string())));
+    assert!(!fixture.calls().iter().any(|c| c.first().is_some_and(|a| a == "plugin")));
+}